@@ -0,0 +1,140 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+use wasm_bindgen::prelude::*;
+
+use crate::canvas::request_animation_frame;
+
+/// Ticks a still view of a tweened visualization stays on screen.
+pub const STEP_TICKS: u32 = 40;
+/// Single-tick frames a tween takes from one layout to the next.
+const TWEEN_TICKS: usize = 24;
+
+/// Plays back pre-computed frames on `requestAnimationFrame`, drawing one
+/// frame every `frames_per_step` ticks unless it was queued with its own
+/// hold. Frames can be queued at any time, so interactive visualizations
+/// keep a single `Animation` around and push the frames of each new
+/// operation onto it.
+pub struct Animation<T> {
+    frames: Rc<RefCell<VecDeque<(T, u32)>>>,
+    frames_per_step: u32,
+}
+
+impl<T: 'static> Animation<T> {
+    pub fn start(frames_per_step: u32, mut draw: impl FnMut(&T) + 'static) -> Animation<T> {
+        let frames = Rc::new(RefCell::new(VecDeque::new()));
+        let queued = frames.clone();
+
+        let f = Rc::new(RefCell::new(None));
+        let g = f.clone();
+
+        let mut ticks_until_next_frame: u32 = 0;
+
+        *g.borrow_mut() = Some(Closure::new(move || {
+            if ticks_until_next_frame == 0 {
+                let frame = queued.borrow_mut().pop_front();
+
+                ticks_until_next_frame = match frame {
+                    Some((frame, ticks)) => {
+                        draw(&frame);
+                        ticks
+                    }
+                    None => frames_per_step,
+                };
+            }
+
+            ticks_until_next_frame = ticks_until_next_frame.saturating_sub(1);
+
+            request_animation_frame(f.borrow().as_ref().unwrap());
+        }));
+
+        request_animation_frame(g.borrow().as_ref().unwrap());
+
+        Animation {
+            frames,
+            frames_per_step,
+        }
+    }
+
+    pub fn push(&self, frames: impl IntoIterator<Item = T>) {
+        let ticks = self.frames_per_step;
+
+        self.frames
+            .borrow_mut()
+            .extend(frames.into_iter().map(|frame| (frame, ticks)));
+    }
+
+    /// Queues frames each held on screen for its own number of ticks.
+    pub fn push_held(&self, frames: impl IntoIterator<Item = (T, u32)>) {
+        self.frames.borrow_mut().extend(frames);
+    }
+
+    /// Drops the frames still waiting and queues `frames` instead, for
+    /// views that should follow the user's input right away.
+    pub fn replace(&self, frames: impl IntoIterator<Item = T>) {
+        self.frames.borrow_mut().clear();
+        self.push(frames);
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.frames.borrow().is_empty()
+    }
+}
+
+/// A view whose nodes can glide from one layout to another.
+pub trait Tween: Clone {
+    /// The view `t` of the way from `self` to `to`.
+    fn interpolate(&self, to: &Self, t: f64) -> Self;
+
+    /// Whether anything sits somewhere else in `other`.
+    fn moves_to(&self, other: &Self) -> bool;
+}
+
+/// Collects the frames of a tweened visualization for `push_held`: every
+/// view recorded is held once for `STEP_TICKS` ticks, eased into from the
+/// view before it whenever nodes moved.
+pub struct TweenRecorder<T> {
+    last: Option<T>,
+    frames: Vec<(T, u32)>,
+}
+
+impl<T: Tween> TweenRecorder<T> {
+    pub fn new() -> TweenRecorder<T> {
+        TweenRecorder {
+            last: None,
+            frames: vec![],
+        }
+    }
+
+    pub fn record(&mut self, view: T) {
+        if let Some(last) = self.last.as_ref() {
+            if last.moves_to(&view) {
+                for tick in 1..=TWEEN_TICKS {
+                    let t = tick as f64 / TWEEN_TICKS as f64;
+
+                    self.frames
+                        .push((last.interpolate(&view, t * t * (3.0 - 2.0 * t)), 1));
+                }
+            }
+        }
+
+        self.frames.push((view.clone(), STEP_TICKS));
+        self.last = Some(view);
+    }
+
+    /// Drops the frames not taken yet and tweens from `view` on, for views
+    /// shown right away.
+    pub fn reset(&mut self, view: T) {
+        self.frames.clear();
+        self.last = Some(view);
+    }
+
+    /// The frames recorded since the last call.
+    pub fn take(&mut self) -> Vec<(T, u32)> {
+        std::mem::take(&mut self.frames)
+    }
+}
+
+impl<T: Tween> Default for TweenRecorder<T> {
+    fn default() -> TweenRecorder<T> {
+        TweenRecorder::new()
+    }
+}
@@ -45,6 +45,21 @@ pub fn canvas(element_id: &str) -> web_sys::HtmlCanvasElement {
         .unwrap()
 }
 
+pub fn context(element_id: &str) -> web_sys::CanvasRenderingContext2d {
+    canvas(element_id)
+        .get_context("2d")
+        .unwrap()
+        .unwrap()
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .unwrap()
+}
+
+pub fn clear(ctx: &web_sys::CanvasRenderingContext2d) {
+    if let Some(canvas) = ctx.canvas() {
+        ctx.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+    }
+}
+
 pub fn request_animation_frame(cb: &Closure<dyn FnMut()>) -> i32 {
     window()
         .request_animation_frame(cb.as_ref().unchecked_ref())
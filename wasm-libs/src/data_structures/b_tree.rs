@@ -0,0 +1,619 @@
+/// One step of a B-tree operation. Operations return their steps so that
+/// visualizations can replay them node by node on a copy of the tree.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BTreeStep {
+    /// Looked through the keys of the node on the way down.
+    Visit(usize),
+    /// Found the key at `index` of the node.
+    Found(usize, usize),
+    /// Made the node, free so far, a new root without keys above the old
+    /// one, which is split right after.
+    Grow(usize),
+    /// Split the full child at `index` of `parent`: its median key moved
+    /// up into `parent` and the keys after the median into the node
+    /// `sibling`, free so far.
+    Split {
+        parent: usize,
+        index: usize,
+        sibling: usize,
+    },
+    /// Inserted the key at `index` of a leaf.
+    InsertKey(usize, usize, i32),
+    /// Removed the key at `index` of a leaf.
+    RemoveKey(usize, usize),
+    /// Overwrote the key at `index` of the node with its predecessor or
+    /// successor, which is removed from below next.
+    ReplaceKey(usize, usize, i32),
+    /// Rotated a key from the left sibling of the child at `index` of
+    /// `parent` through `parent` into the child.
+    BorrowLeft { parent: usize, index: usize },
+    /// Rotated a key from the right sibling of the child at `index` of
+    /// `parent` through `parent` into the child.
+    BorrowRight { parent: usize, index: usize },
+    /// Merged the child at `index + 1` of `parent` and the key between
+    /// them into the child at `index`, freeing the right one.
+    Merge { parent: usize, index: usize },
+    /// The root ran out of keys: its only child became the root.
+    Shrink,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BTreeNode {
+    pub keys: Vec<i32>,
+    /// One more than the keys, or none in a leaf.
+    pub children: Vec<usize>,
+}
+
+impl BTreeNode {
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// A B-tree of distinct keys with minimum degree `t`: every node but the
+/// root holds between `t - 1` and `2t - 1` keys, and all leaves are at the
+/// same depth. Inserts split full nodes and removals refill nodes with
+/// `t - 1` keys on the way down, so that no operation goes back up.
+#[derive(Clone, Debug)]
+pub struct BTree {
+    min_degree: usize,
+    slots: Vec<Option<BTreeNode>>,
+    free: Vec<usize>,
+    root: usize,
+    len: usize,
+}
+
+impl BTree {
+    pub fn new(min_degree: usize) -> BTree {
+        assert!(min_degree >= 2, "the minimum degree is at least 2");
+
+        BTree {
+            min_degree,
+            slots: vec![Some(BTreeNode {
+                keys: vec![],
+                children: vec![],
+            })],
+            free: vec![],
+            root: 0,
+            len: 0,
+        }
+    }
+
+    pub fn min_degree(&self) -> usize {
+        self.min_degree
+    }
+
+    pub fn max_keys(&self) -> usize {
+        2 * self.min_degree - 1
+    }
+
+    /// The number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// The number of node slots, used or not; every node id is below it.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn node(&self, id: usize) -> &BTreeNode {
+        self.slots[id].as_ref().expect("no node in this slot")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut BTreeNode {
+        self.slots[id].as_mut().expect("no node in this slot")
+    }
+
+    fn is_full(&self, id: usize) -> bool {
+        self.node(id).keys.len() == self.max_keys()
+    }
+
+    /// The number of levels, counting the root alone as 1.
+    pub fn height(&self) -> usize {
+        let mut height = 1;
+        let mut id = self.root;
+
+        while let Some(&child) = self.node(id).children.first() {
+            height += 1;
+            id = child;
+        }
+
+        height
+    }
+
+    /// The keys in order.
+    pub fn keys(&self) -> Vec<i32> {
+        let mut keys = vec![];
+
+        self.collect_keys(self.root, &mut keys);
+
+        keys
+    }
+
+    fn collect_keys(&self, id: usize, keys: &mut Vec<i32>) {
+        let node = self.node(id);
+
+        for (index, &key) in node.keys.iter().enumerate() {
+            if let Some(&child) = node.children.get(index) {
+                self.collect_keys(child, keys);
+            }
+
+            keys.push(key);
+        }
+
+        if let Some(&child) = node.children.last() {
+            self.collect_keys(child, keys);
+        }
+    }
+
+    pub fn contains(&self, key: i32) -> bool {
+        let mut id = self.root;
+
+        loop {
+            let node = self.node(id);
+
+            match node.keys.binary_search(&key) {
+                Ok(_) => return true,
+                Err(_) if node.is_leaf() => return false,
+                Err(index) => id = node.children[index],
+            }
+        }
+    }
+
+    pub fn apply(&mut self, step: BTreeStep) {
+        match step {
+            BTreeStep::Visit(_) | BTreeStep::Found(..) => {}
+            BTreeStep::Grow(id) => {
+                let root = self.root;
+
+                self.place(
+                    id,
+                    BTreeNode {
+                        keys: vec![],
+                        children: vec![root],
+                    },
+                );
+                self.root = id;
+            }
+            BTreeStep::Split {
+                parent,
+                index,
+                sibling,
+            } => {
+                let t = self.min_degree;
+                let child = self.node(parent).children[index];
+                let node = self.node_mut(child);
+                let keys = node.keys.split_off(t);
+                let median = node.keys.pop().expect("splitting a node without keys");
+                let children = if node.is_leaf() {
+                    vec![]
+                } else {
+                    node.children.split_off(t)
+                };
+
+                self.place(sibling, BTreeNode { keys, children });
+
+                let parent = self.node_mut(parent);
+
+                parent.keys.insert(index, median);
+                parent.children.insert(index + 1, sibling);
+            }
+            BTreeStep::InsertKey(id, index, key) => {
+                self.node_mut(id).keys.insert(index, key);
+                self.len += 1;
+            }
+            BTreeStep::RemoveKey(id, index) => {
+                self.node_mut(id).keys.remove(index);
+                self.len -= 1;
+            }
+            BTreeStep::ReplaceKey(id, index, key) => self.node_mut(id).keys[index] = key,
+            BTreeStep::BorrowLeft { parent, index } => {
+                let (left, child) = {
+                    let children = &self.node(parent).children;
+
+                    (children[index - 1], children[index])
+                };
+                let left = self.node_mut(left);
+                let key = left.keys.pop().expect("borrowing from a node without keys");
+                let grandchild = left.children.pop();
+                let separator = std::mem::replace(&mut self.node_mut(parent).keys[index - 1], key);
+                let child = self.node_mut(child);
+
+                child.keys.insert(0, separator);
+                child.children.splice(0..0, grandchild);
+            }
+            BTreeStep::BorrowRight { parent, index } => {
+                let (child, right) = {
+                    let children = &self.node(parent).children;
+
+                    (children[index], children[index + 1])
+                };
+                let right = self.node_mut(right);
+                let key = right.keys.remove(0);
+                let grandchild = (!right.is_leaf()).then(|| right.children.remove(0));
+                let separator = std::mem::replace(&mut self.node_mut(parent).keys[index], key);
+                let child = self.node_mut(child);
+
+                child.keys.push(separator);
+                child.children.extend(grandchild);
+            }
+            BTreeStep::Merge { parent, index } => {
+                let node = self.node_mut(parent);
+                let separator = node.keys.remove(index);
+                let right = node.children.remove(index + 1);
+                let left = node.children[index];
+                let BTreeNode { keys, children } =
+                    self.slots[right].take().expect("no node in this slot");
+
+                self.free.push(right);
+
+                let left = self.node_mut(left);
+
+                left.keys.push(separator);
+                left.keys.extend(keys);
+                left.children.extend(children);
+            }
+            BTreeStep::Shrink => {
+                let old = self.root;
+
+                self.root = self.node(old).children[0];
+                self.slots[old] = None;
+                self.free.push(old);
+            }
+        }
+    }
+
+    /// Puts `node` into the slot `id`, which is free or just past the end.
+    fn place(&mut self, id: usize, node: BTreeNode) {
+        if id == self.slots.len() {
+            self.slots.push(None);
+        } else {
+            self.free.retain(|&free| free != id);
+        }
+
+        self.slots[id] = Some(node);
+    }
+
+    /// The slot the next new node goes into.
+    fn next_id(&self) -> usize {
+        self.free.last().copied().unwrap_or(self.slots.len())
+    }
+
+    fn run(&mut self, steps: &mut Vec<BTreeStep>, step: BTreeStep) {
+        self.apply(step);
+        steps.push(step);
+    }
+
+    /// Walks down to `key`, returning whether it was found and the steps.
+    pub fn search(&self, key: i32) -> (bool, Vec<BTreeStep>) {
+        let mut steps = vec![];
+        let mut id = self.root;
+
+        loop {
+            let node = self.node(id);
+
+            steps.push(BTreeStep::Visit(id));
+
+            match node.keys.binary_search(&key) {
+                Ok(index) => {
+                    steps.push(BTreeStep::Found(id, index));
+                    return (true, steps);
+                }
+                Err(_) if node.is_leaf() => return (false, steps),
+                Err(index) => id = node.children[index],
+            }
+        }
+    }
+
+    /// Inserts `key` unless it is there already, splitting every full node
+    /// on the way down to its leaf so that the leaf has room and every
+    /// split median fits into its parent.
+    pub fn insert(&mut self, key: i32) -> (bool, Vec<BTreeStep>) {
+        if self.contains(key) {
+            return (false, self.search(key).1);
+        }
+
+        let mut steps = vec![];
+
+        if self.is_full(self.root) {
+            let root = self.next_id();
+
+            self.run(&mut steps, BTreeStep::Grow(root));
+
+            let sibling = self.next_id();
+
+            self.run(
+                &mut steps,
+                BTreeStep::Split {
+                    parent: root,
+                    index: 0,
+                    sibling,
+                },
+            );
+        }
+
+        let mut id = self.root;
+
+        loop {
+            self.run(&mut steps, BTreeStep::Visit(id));
+
+            let node = self.node(id);
+            let mut index = node.keys.partition_point(|&other| other < key);
+
+            if node.is_leaf() {
+                self.run(&mut steps, BTreeStep::InsertKey(id, index, key));
+                return (true, steps);
+            }
+
+            if self.is_full(node.children[index]) {
+                let sibling = self.next_id();
+
+                self.run(
+                    &mut steps,
+                    BTreeStep::Split {
+                        parent: id,
+                        index,
+                        sibling,
+                    },
+                );
+
+                if key > self.node(id).keys[index] {
+                    index += 1;
+                }
+            }
+
+            id = self.node(id).children[index];
+        }
+    }
+
+    /// Removes `key` if it is there. Every node gone down into is first
+    /// refilled to at least `t` keys, borrowing from a sibling or merging
+    /// with one, so that taking a key out of it never leaves too few.
+    pub fn remove(&mut self, key: i32) -> (bool, Vec<BTreeStep>) {
+        if !self.contains(key) {
+            return (false, self.search(key).1);
+        }
+
+        let t = self.min_degree;
+        let mut steps = vec![];
+        let mut key = key;
+        let mut id = self.root;
+
+        loop {
+            self.run(&mut steps, BTreeStep::Visit(id));
+
+            let node = self.node(id);
+
+            match node.keys.binary_search(&key) {
+                Ok(index) if node.is_leaf() => {
+                    self.run(&mut steps, BTreeStep::Found(id, index));
+                    self.run(&mut steps, BTreeStep::RemoveKey(id, index));
+                    return (true, steps);
+                }
+                Ok(index) => {
+                    let (left, right) = (node.children[index], node.children[index + 1]);
+
+                    self.run(&mut steps, BTreeStep::Found(id, index));
+
+                    if self.node(left).keys.len() >= t {
+                        key = self.last_key(left);
+                        self.run(&mut steps, BTreeStep::ReplaceKey(id, index, key));
+                        id = left;
+                    } else if self.node(right).keys.len() >= t {
+                        key = self.first_key(right);
+                        self.run(&mut steps, BTreeStep::ReplaceKey(id, index, key));
+                        id = right;
+                    } else {
+                        self.merge(&mut steps, id, index);
+                        id = left;
+                    }
+                }
+                Err(index) => id = self.refill(&mut steps, id, index),
+            }
+        }
+    }
+
+    /// Makes sure the child at `index` of `id` has at least `t` keys,
+    /// returning the node to go down into afterwards.
+    fn refill(&mut self, steps: &mut Vec<BTreeStep>, id: usize, index: usize) -> usize {
+        let t = self.min_degree;
+        let children = &self.node(id).children;
+
+        let child = children[index];
+
+        if self.node(child).keys.len() >= t {
+            return child;
+        }
+
+        let left = index.checked_sub(1).map(|left| children[left]);
+        let right = children.get(index + 1).copied();
+
+        if left.is_some_and(|left| self.node(left).keys.len() >= t) {
+            self.run(steps, BTreeStep::BorrowLeft { parent: id, index });
+            child
+        } else if right.is_some_and(|right| self.node(right).keys.len() >= t) {
+            self.run(steps, BTreeStep::BorrowRight { parent: id, index });
+            child
+        } else if right.is_some() {
+            self.merge(steps, id, index);
+            child
+        } else {
+            // The last child merges into its left sibling instead.
+            self.merge(steps, id, index - 1);
+            left.expect("a child without siblings")
+        }
+    }
+
+    /// Merges the children at `index` and `index + 1` of `id`, shrinking
+    /// the tree if that took the last key of the root.
+    fn merge(&mut self, steps: &mut Vec<BTreeStep>, id: usize, index: usize) {
+        self.run(steps, BTreeStep::Merge { parent: id, index });
+
+        if id == self.root && self.node(id).keys.is_empty() {
+            self.run(steps, BTreeStep::Shrink);
+        }
+    }
+
+    fn first_key(&self, mut id: usize) -> i32 {
+        while let Some(&child) = self.node(id).children.first() {
+            id = child;
+        }
+
+        self.node(id).keys[0]
+    }
+
+    fn last_key(&self, mut id: usize) -> i32 {
+        while let Some(&child) = self.node(id).children.last() {
+            id = child;
+        }
+
+        *self.node(id).keys.last().expect("a leaf without keys")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::*;
+    use crate::rng::Rng;
+
+    /// Checks the fill of every node, that all leaves are at the same depth
+    /// and that the keys are those of `expected`, in order.
+    fn check(tree: &BTree, expected: &BTreeSet<i32>) {
+        let t = tree.min_degree();
+        let mut leaf_depths = BTreeSet::new();
+        let mut stack = vec![(tree.root(), 0)];
+
+        while let Some((id, depth)) = stack.pop() {
+            let node = tree.node(id);
+
+            assert!(node.keys.len() <= tree.max_keys(), "node {} overfull", id);
+
+            if id != tree.root() {
+                assert!(node.keys.len() >= t - 1, "node {} underfull", id);
+            }
+
+            if node.is_leaf() {
+                leaf_depths.insert(depth);
+            } else {
+                assert_eq!(node.children.len(), node.keys.len() + 1);
+                stack.extend(node.children.iter().map(|&child| (child, depth + 1)));
+            }
+        }
+
+        assert_eq!(leaf_depths.len(), 1, "leaves at depths {:?}", leaf_depths);
+        assert_eq!(leaf_depths.first(), Some(&(tree.height() - 1)));
+        assert_eq!(tree.keys(), expected.iter().copied().collect::<Vec<_>>());
+        assert_eq!(tree.len(), expected.len());
+    }
+
+    /// Checks that replaying `steps` on `before` gives `after`.
+    fn check_replay(before: &BTree, steps: &[BTreeStep], after: &BTree) {
+        let mut replayed = before.clone();
+
+        for &step in steps {
+            replayed.apply(step);
+        }
+
+        assert_eq!(replayed.slots, after.slots);
+        assert_eq!(replayed.free, after.free);
+        assert_eq!(replayed.root, after.root);
+        assert_eq!(replayed.len, after.len);
+    }
+
+    #[test]
+    fn random_inserts_and_removes_keep_the_invariants() {
+        let mut seen = HashSet::new();
+
+        for min_degree in 2..=4 {
+            for seed in 0..8 {
+                let mut rng = Rng::new(seed);
+                let mut tree = BTree::new(min_degree);
+                let mut expected = BTreeSet::new();
+
+                for _ in 0..400 {
+                    let key = rng.below(64) as i32;
+                    let before = tree.clone();
+                    let (changed, steps) = if rng.chance(0.55) {
+                        let (inserted, steps) = tree.insert(key);
+
+                        assert_eq!(inserted, expected.insert(key));
+                        (inserted, steps)
+                    } else {
+                        let (removed, steps) = tree.remove(key);
+
+                        assert_eq!(removed, expected.remove(&key));
+                        (removed, steps)
+                    };
+
+                    if !changed {
+                        assert_eq!(before.slots, tree.slots);
+                    }
+
+                    check(&tree, &expected);
+                    check_replay(&before, &steps, &tree);
+
+                    seen.extend(steps.iter().map(std::mem::discriminant));
+                }
+            }
+        }
+
+        for step in [
+            BTreeStep::Grow(0),
+            BTreeStep::Split {
+                parent: 0,
+                index: 0,
+                sibling: 0,
+            },
+            BTreeStep::ReplaceKey(0, 0, 0),
+            BTreeStep::BorrowLeft {
+                parent: 0,
+                index: 0,
+            },
+            BTreeStep::BorrowRight {
+                parent: 0,
+                index: 0,
+            },
+            BTreeStep::Merge {
+                parent: 0,
+                index: 0,
+            },
+            BTreeStep::Shrink,
+        ] {
+            assert!(
+                seen.contains(&std::mem::discriminant(&step)),
+                "{:?} never happened",
+                step
+            );
+        }
+    }
+
+    #[test]
+    fn remove_everything_leaves_an_empty_root() {
+        let mut tree = BTree::new(2);
+        let mut expected = (0..50).collect::<BTreeSet<i32>>();
+
+        for key in 0..50 {
+            tree.insert(key);
+        }
+
+        for key in (0..50).rev().step_by(2).chain((0..50).step_by(2)) {
+            tree.remove(key);
+            expected.remove(&key);
+            check(&tree, &expected);
+        }
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.height(), 1);
+    }
+}
@@ -0,0 +1,157 @@
+/// One primitive operation of a heap, in the order it happened, so that
+/// visualizations can replay an operation on a copy of the array.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeapStep {
+    /// `value` appended at the end of the array.
+    Append(i32),
+    /// The last value dropped from the array.
+    RemoveLast,
+    /// A sift-down starting at the index, as done by build-heap.
+    SiftDown(usize),
+    /// Compared the value at the first index with the one at the second.
+    Compare(usize, usize),
+    Swap(usize, usize),
+}
+
+/// An array-backed binary heap, the children of index `i` being at
+/// `2i + 1` and `2i + 2`. A max-heap keeps the largest value at the root,
+/// a min-heap the smallest.
+#[derive(Clone, Debug)]
+pub struct BinaryHeap {
+    values: Vec<i32>,
+    max: bool,
+}
+
+pub fn parent(idx: usize) -> Option<usize> {
+    (idx > 0).then(|| (idx - 1) / 2)
+}
+
+impl BinaryHeap {
+    pub fn new(max: bool) -> BinaryHeap {
+        BinaryHeap {
+            values: vec![],
+            max,
+        }
+    }
+
+    /// Floyd's build-heap: sifts down every inner node from the last one up
+    /// to the root, which takes O(n) operations since most nodes sit near
+    /// the bottom.
+    pub fn build(values: Vec<i32>, max: bool) -> (BinaryHeap, Vec<HeapStep>) {
+        let mut heap = BinaryHeap { values, max };
+        let mut steps = vec![];
+
+        for idx in (0..heap.values.len() / 2).rev() {
+            steps.push(HeapStep::SiftDown(idx));
+            heap.sift_down(idx, &mut steps);
+        }
+
+        (heap, steps)
+    }
+
+    pub fn is_max(&self) -> bool {
+        self.max
+    }
+
+    pub fn values(&self) -> &[i32] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<i32> {
+        self.values.first().copied()
+    }
+
+    /// Appends `value` and sifts it up to its place.
+    pub fn push(&mut self, value: i32) -> Vec<HeapStep> {
+        let mut steps = vec![HeapStep::Append(value)];
+
+        self.values.push(value);
+        self.sift_up(self.values.len() - 1, &mut steps);
+
+        steps
+    }
+
+    /// Moves the last value to the root in place of the top one and sifts
+    /// it down.
+    pub fn pop(&mut self) -> (Option<i32>, Vec<HeapStep>) {
+        let mut steps = vec![];
+        let last = self.values.len().checked_sub(1);
+
+        let Some(last) = last else {
+            return (None, steps);
+        };
+
+        if last > 0 {
+            self.swap(0, last, &mut steps);
+        }
+
+        let top = self.values.pop();
+        steps.push(HeapStep::RemoveLast);
+
+        self.sift_down(0, &mut steps);
+
+        (top, steps)
+    }
+
+    /// Whether `a` belongs above `b`.
+    fn outranks(&self, a: i32, b: i32) -> bool {
+        if self.max {
+            a > b
+        } else {
+            a < b
+        }
+    }
+
+    fn compare(&self, a: usize, b: usize, steps: &mut Vec<HeapStep>) -> bool {
+        steps.push(HeapStep::Compare(a, b));
+
+        self.outranks(self.values[a], self.values[b])
+    }
+
+    fn swap(&mut self, a: usize, b: usize, steps: &mut Vec<HeapStep>) {
+        steps.push(HeapStep::Swap(a, b));
+        self.values.swap(a, b);
+    }
+
+    fn sift_up(&mut self, mut idx: usize, steps: &mut Vec<HeapStep>) {
+        while let Some(up) = parent(idx) {
+            if !self.compare(idx, up, steps) {
+                break;
+            }
+
+            self.swap(idx, up, steps);
+            idx = up;
+        }
+    }
+
+    fn sift_down(&mut self, mut idx: usize, steps: &mut Vec<HeapStep>) {
+        loop {
+            let (left, right) = (2 * idx + 1, 2 * idx + 2);
+
+            if left >= self.values.len() {
+                break;
+            }
+
+            let child = if right < self.values.len() && self.compare(right, left, steps) {
+                right
+            } else {
+                left
+            };
+
+            if !self.compare(child, idx, steps) {
+                break;
+            }
+
+            self.swap(child, idx, steps);
+            idx = child;
+        }
+    }
+}
@@ -0,0 +1,264 @@
+#[derive(Clone, Debug)]
+pub struct TreeNode {
+    pub key: i32,
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+    pub parent: Option<usize>,
+}
+
+/// A binary search tree of distinct keys. Nodes live in slots that keep
+/// their index for as long as the node is in the tree, so visualizations
+/// can follow a node from one frame to the next; removed slots are reused.
+#[derive(Clone, Debug, Default)]
+pub struct BinarySearchTree {
+    slots: Vec<Option<TreeNode>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    len: usize,
+}
+
+impl BinarySearchTree {
+    pub fn new() -> BinarySearchTree {
+        BinarySearchTree::default()
+    }
+
+    pub fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of slots, occupied or not; every node index is below it.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn node(&self, id: usize) -> &TreeNode {
+        self.slots[id].as_ref().expect("no node in this slot")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut TreeNode {
+        self.slots[id].as_mut().expect("no node in this slot")
+    }
+
+    pub fn key(&self, id: usize) -> i32 {
+        self.node(id).key
+    }
+
+    /// The nodes compared while looking for `key`, ending with the node
+    /// holding it or with the last node before falling off the tree.
+    pub fn search_path(&self, key: i32) -> Vec<usize> {
+        let mut path = vec![];
+        let mut current = self.root;
+
+        while let Some(id) = current {
+            path.push(id);
+
+            let node = self.node(id);
+
+            current = match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => node.left,
+                std::cmp::Ordering::Greater => node.right,
+                std::cmp::Ordering::Equal => break,
+            };
+        }
+
+        path
+    }
+
+    pub fn find(&self, key: i32) -> Option<usize> {
+        self.search_path(key)
+            .last()
+            .copied()
+            .filter(|&id| self.key(id) == key)
+    }
+
+    /// Adds `key` as a new leaf and returns its node, or `None` if the key
+    /// is already in the tree.
+    pub fn insert(&mut self, key: i32) -> Option<usize> {
+        let parent = self.search_path(key).last().copied();
+
+        if parent.is_some_and(|parent| self.key(parent) == key) {
+            return None;
+        }
+
+        let node = TreeNode {
+            key,
+            left: None,
+            right: None,
+            parent,
+        };
+
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.slots[id] = Some(node);
+                id
+            }
+            None => {
+                self.slots.push(Some(node));
+                self.slots.len() - 1
+            }
+        };
+
+        match parent {
+            Some(parent) if key < self.key(parent) => self.node_mut(parent).left = Some(id),
+            Some(parent) => self.node_mut(parent).right = Some(id),
+            None => self.root = Some(id),
+        }
+
+        self.len += 1;
+
+        Some(id)
+    }
+
+    /// The leftmost descent from `id`, ending with the minimum of its
+    /// subtree.
+    pub fn min_path(&self, id: usize) -> Vec<usize> {
+        let mut path = vec![id];
+
+        while let Some(left) = self.node(*path.last().unwrap()).left {
+            path.push(left);
+        }
+
+        path
+    }
+
+    /// The node holding the next larger key inside the subtree of `id`.
+    pub fn successor_in_subtree(&self, id: usize) -> Option<usize> {
+        self.node(id)
+            .right
+            .map(|right| *self.min_path(right).last().unwrap())
+    }
+
+    /// Removes the key of node `id`. A node with two children takes over
+    /// the key of its in-order successor, whose node is removed instead.
+    /// Returns the slot that was freed.
+    pub fn remove_node(&mut self, id: usize) -> usize {
+        let removed = match self.successor_in_subtree(id) {
+            Some(successor) if self.node(id).left.is_some() => {
+                self.node_mut(id).key = self.key(successor);
+                successor
+            }
+            _ => id,
+        };
+
+        let node = self.node(removed);
+        let child = node.left.or(node.right);
+        let parent = node.parent;
+
+        self.replace_child(parent, removed, child);
+
+        if let Some(child) = child {
+            self.node_mut(child).parent = parent;
+        }
+
+        self.slots[removed] = None;
+        self.free.push(removed);
+        self.len -= 1;
+
+        removed
+    }
+
+    /// Points the link of `parent` that led to `old` at `new` instead, or
+    /// the root when `parent` is `None`.
+    pub fn replace_child(&mut self, parent: Option<usize>, old: usize, new: Option<usize>) {
+        match parent {
+            Some(parent) if self.node(parent).left == Some(old) => self.node_mut(parent).left = new,
+            Some(parent) => self.node_mut(parent).right = new,
+            None => self.root = new,
+        }
+    }
+
+    /// Moves the right child of `id` up into its place, `id` becoming its
+    /// left child, and returns it.
+    pub fn rotate_left(&mut self, id: usize) -> usize {
+        let pivot = self
+            .node(id)
+            .right
+            .expect("rotate left needs a right child");
+        let inner = self.node(pivot).left;
+        let parent = self.node(id).parent;
+
+        self.node_mut(id).right = inner;
+
+        if let Some(inner) = inner {
+            self.node_mut(inner).parent = Some(id);
+        }
+
+        self.replace_child(parent, id, Some(pivot));
+        self.node_mut(pivot).parent = parent;
+        self.node_mut(pivot).left = Some(id);
+        self.node_mut(id).parent = Some(pivot);
+
+        pivot
+    }
+
+    /// The mirror image of `rotate_left`.
+    pub fn rotate_right(&mut self, id: usize) -> usize {
+        let pivot = self.node(id).left.expect("rotate right needs a left child");
+        let inner = self.node(pivot).right;
+        let parent = self.node(id).parent;
+
+        self.node_mut(id).left = inner;
+
+        if let Some(inner) = inner {
+            self.node_mut(inner).parent = Some(id);
+        }
+
+        self.replace_child(parent, id, Some(pivot));
+        self.node_mut(pivot).parent = parent;
+        self.node_mut(pivot).right = Some(id);
+        self.node_mut(id).parent = Some(pivot);
+
+        pivot
+    }
+
+    /// Nodes in key order.
+    pub fn in_order(&self) -> Vec<usize> {
+        let mut order = vec![];
+        let mut stack = vec![];
+        let mut current = self.root;
+
+        while current.is_some() || !stack.is_empty() {
+            while let Some(id) = current {
+                stack.push(id);
+                current = self.node(id).left;
+            }
+
+            let id = stack.pop().unwrap();
+
+            order.push(id);
+            current = self.node(id).right;
+        }
+
+        order
+    }
+
+    /// The number of edges from the root down to `id`.
+    pub fn depth(&self, id: usize) -> usize {
+        let mut depth = 0;
+        let mut current = id;
+
+        while let Some(parent) = self.node(current).parent {
+            depth += 1;
+            current = parent;
+        }
+
+        depth
+    }
+
+    /// The number of levels, 0 for the empty tree.
+    pub fn height(&self) -> usize {
+        self.in_order()
+            .into_iter()
+            .map(|id| self.depth(id) + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
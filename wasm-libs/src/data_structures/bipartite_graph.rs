@@ -0,0 +1,97 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{canvas::Boundary, data_structures::general_graph::GeneralGraph};
+
+/// An undirected graph whose nodes are split into a left and a right side,
+/// with every edge joining the two sides.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct BipartiteGraph {
+    left: usize,
+    right: usize,
+    edges: Vec<(usize, usize)>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+#[wasm_bindgen]
+impl BipartiteGraph {
+    #[wasm_bindgen(constructor)]
+    pub fn new(left: usize, right: usize) -> BipartiteGraph {
+        BipartiteGraph {
+            left,
+            right,
+            edges: vec![],
+            adjacency: vec![vec![]; left],
+        }
+    }
+
+    /// Joins left node `left` to right node `right` and returns the edge
+    /// index, failing if either is not a node of its side.
+    pub fn add_edge(&mut self, left: usize, right: usize) -> Result<usize, JsValue> {
+        for (side, node, count) in [("left", left, self.left), ("right", right, self.right)] {
+            if node >= count {
+                return Err(JsValue::from_str(&format!(
+                    "{} endpoint {} out of range, the {} side has {} nodes",
+                    side, node, side, count
+                )));
+            }
+        }
+
+        Ok(self.push_edge(left, right))
+    }
+
+    pub fn left_count(&self) -> usize {
+        self.left
+    }
+
+    pub fn right_count(&self) -> usize {
+        self.right
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+impl BipartiteGraph {
+    /// Joins left node `left` to right node `right`, both existing, and
+    /// returns the edge index.
+    pub fn push_edge(&mut self, left: usize, right: usize) -> usize {
+        self.edges.push((left, right));
+        self.adjacency[left].push(self.edges.len() - 1);
+
+        self.edges.len() - 1
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// Indexes of the edges of left node `left`.
+    pub fn incident_edges(&self, left: usize) -> &[usize] {
+        &self.adjacency[left]
+    }
+
+    /// The two column layout: left nodes `L0..` become nodes `0..left` and
+    /// right nodes `R0..` follow them, with edge indexes unchanged.
+    pub fn to_general_graph(&self, boundary: &Boundary) -> GeneralGraph {
+        let mut graph = GeneralGraph::new(false);
+
+        for (side, count, x) in [
+            ("L", self.left, boundary.width * 0.3),
+            ("R", self.right, boundary.width * 0.7),
+        ] {
+            let spacing = boundary.height / (count + 1) as f64;
+
+            for idx in 0..count {
+                graph.add_labeled_node(format!("{}{}", side, idx), x, spacing * (idx + 1) as f64);
+            }
+        }
+
+        for &(left, right) in self.edges.iter() {
+            graph.push_edge(left, self.left + right, 1.0);
+        }
+
+        graph
+    }
+}
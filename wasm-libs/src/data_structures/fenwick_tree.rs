@@ -0,0 +1,90 @@
+/// The lowest set bit of `i`: how many values the Fenwick tree entry at
+/// `i` sums up, and how far its updates and queries jump.
+pub fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+/// A Fenwick tree, or binary indexed tree, of sums. Entries are numbered
+/// from 1, entry `i` holding the sum of the values at `i - lowbit(i) + 1`
+/// through `i`; the values themselves are indexed from 0 like an array.
+#[derive(Clone, Debug)]
+pub struct FenwickTree {
+    /// `tree[0]` is unused.
+    tree: Vec<i64>,
+    values: Vec<i64>,
+}
+
+impl FenwickTree {
+    /// Builds the tree in linear time by adding every entry into the one
+    /// above it.
+    pub fn new(values: &[i32]) -> FenwickTree {
+        let values: Vec<i64> = values.iter().map(|&value| value as i64).collect();
+        let mut tree = vec![0; values.len() + 1];
+
+        for i in 1..tree.len() {
+            tree[i] += values[i - 1];
+
+            let above = i + lowbit(i);
+
+            if above < tree.len() {
+                tree[above] += tree[i];
+            }
+        }
+
+        FenwickTree { tree, values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Entry `i`, for `i` from 1 to `len()`.
+    pub fn entry(&self, i: usize) -> i64 {
+        self.tree[i]
+    }
+
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    /// Adds `amount` to the value at `index`, returning the entries
+    /// updated: from `index + 1` on, each `lowbit` further.
+    pub fn add(&mut self, index: usize, amount: i64) -> Vec<usize> {
+        let mut path = vec![];
+        let mut i = index + 1;
+
+        self.values[index] += amount;
+
+        while i < self.tree.len() {
+            self.tree[i] += amount;
+            path.push(i);
+            i += lowbit(i);
+        }
+
+        path
+    }
+
+    pub fn set(&mut self, index: usize, value: i64) -> Vec<usize> {
+        self.add(index, value - self.values[index])
+    }
+
+    /// The sum of the first `count` values and the entries read for it:
+    /// from `count` down, each `lowbit` nearer to 0.
+    pub fn prefix_sum(&self, count: usize) -> (i64, Vec<usize>) {
+        let mut path = vec![];
+        let mut sum = 0;
+        let mut i = count;
+
+        while i > 0 {
+            sum += self.tree[i];
+            path.push(i);
+            i -= lowbit(i);
+        }
+
+        (sum, path)
+    }
+}
@@ -0,0 +1,146 @@
+use crate::data_structures::general_graph::{GeneralGraph, Vertex};
+
+/// Capacities below this are treated as saturated.
+pub const EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Debug)]
+pub struct FlowArc {
+    pub from: usize,
+    pub to: usize,
+    pub capacity: f64,
+    pub flow: f64,
+}
+
+impl FlowArc {
+    pub fn residual(&self) -> f64 {
+        self.capacity - self.flow
+    }
+}
+
+/// A directed network with capacities. Every edge is stored as a pair of
+/// arcs: the forward arc at an even index and its zero-capacity reverse arc
+/// right after it, so `arc ^ 1` is always the partner of `arc` and the
+/// residual graph is simply the arcs with positive residual capacity.
+#[derive(Clone, Debug)]
+pub struct FlowNetwork {
+    vertices: Vec<Vertex>,
+    arcs: Vec<FlowArc>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    pub fn new(vertices: Vec<Vertex>) -> FlowNetwork {
+        let adjacency = vec![vec![]; vertices.len()];
+
+        FlowNetwork {
+            vertices,
+            arcs: vec![],
+            adjacency,
+        }
+    }
+
+    /// Adds an edge with the given capacity and returns its index, which is
+    /// also `arc / 2` for both of its arcs.
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: f64) -> usize {
+        let idx = self.arcs.len();
+
+        self.arcs.push(FlowArc {
+            from,
+            to,
+            capacity,
+            flow: 0.0,
+        });
+        self.arcs.push(FlowArc {
+            from: to,
+            to: from,
+            capacity: 0.0,
+            flow: 0.0,
+        });
+
+        self.adjacency[from].push(idx);
+        self.adjacency[to].push(idx + 1);
+
+        idx / 2
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.arcs.len() / 2
+    }
+
+    pub fn arcs(&self) -> &[FlowArc] {
+        &self.arcs
+    }
+
+    /// The forward arc of edge `edge`.
+    pub fn edge(&self, edge: usize) -> &FlowArc {
+        &self.arcs[edge * 2]
+    }
+
+    /// Arcs leaving `node`, both forward and reverse.
+    pub fn outgoing(&self, node: usize) -> &[usize] {
+        &self.adjacency[node]
+    }
+
+    /// Pushes `amount` units along `arc`, cancelling the same amount on its
+    /// partner.
+    pub fn push(&mut self, arc: usize, amount: f64) {
+        self.arcs[arc].flow += amount;
+        self.arcs[arc ^ 1].flow -= amount;
+    }
+
+    /// Net flow leaving `node`. Reverse arcs carry the negated flow of the
+    /// edges entering `node`, so summing every outgoing arc is enough.
+    pub fn outflow(&self, node: usize) -> f64 {
+        self.adjacency[node]
+            .iter()
+            .map(|&arc| self.arcs[arc].flow)
+            .sum()
+    }
+
+    /// Nodes reachable from `source` through arcs with residual capacity.
+    pub fn residual_reachable(&self, source: usize) -> Vec<bool> {
+        let mut reachable = vec![false; self.node_count()];
+        let mut stack = vec![source];
+
+        reachable[source] = true;
+
+        while let Some(node) = stack.pop() {
+            for &arc in self.adjacency[node].iter() {
+                let to = self.arcs[arc].to;
+
+                if !reachable[to] && self.arcs[arc].residual() > EPSILON {
+                    reachable[to] = true;
+                    stack.push(to);
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+impl From<&GeneralGraph> for FlowNetwork {
+    /// Uses edge weights as capacities. An undirected edge can carry flow
+    /// either way, so it becomes one edge in each direction.
+    fn from(graph: &GeneralGraph) -> FlowNetwork {
+        let mut network = FlowNetwork::new(graph.vertices().to_vec());
+
+        for edge in graph.edges() {
+            network.add_edge(edge.from, edge.to, edge.weight);
+
+            if !graph.is_directed() {
+                network.add_edge(edge.to, edge.from, edge.weight);
+            }
+        }
+
+        network
+    }
+}
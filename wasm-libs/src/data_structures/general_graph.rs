@@ -0,0 +1,173 @@
+use wasm_bindgen::prelude::*;
+
+use crate::canvas::Coordinate;
+
+#[derive(Clone, Debug)]
+pub struct Vertex {
+    pub label: String,
+    pub coordinate: Coordinate,
+}
+
+#[derive(Clone, Debug)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub weight: f64,
+}
+
+impl Edge {
+    /// The endpoint of the edge that is not `node`.
+    pub fn other(&self, node: usize) -> usize {
+        if self.from == node {
+            self.to
+        } else {
+            self.from
+        }
+    }
+}
+
+/// A directed or undirected weighted graph with positioned vertices, as
+/// opposed to the grid `Graph` used by the path finding visualizations.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct GeneralGraph {
+    directed: bool,
+    vertices: Vec<Vertex>,
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+#[wasm_bindgen]
+impl GeneralGraph {
+    #[wasm_bindgen(constructor)]
+    pub fn new(directed: bool) -> GeneralGraph {
+        GeneralGraph {
+            directed,
+            vertices: vec![],
+            edges: vec![],
+            adjacency: vec![],
+        }
+    }
+
+    pub fn add_node(&mut self, x: f64, y: f64) -> usize {
+        let label = self.vertices.len().to_string();
+
+        self.add_labeled_node(label, x, y)
+    }
+
+    pub fn add_labeled_node(&mut self, label: String, x: f64, y: f64) -> usize {
+        self.vertices.push(Vertex {
+            label,
+            coordinate: Coordinate::new(x, y),
+        });
+        self.adjacency.push(vec![]);
+
+        self.vertices.len() - 1
+    }
+
+    /// Adds an edge and returns its index, failing if an endpoint is not a
+    /// node of the graph.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: f64) -> Result<usize, JsValue> {
+        for node in [from, to] {
+            if node >= self.vertices.len() {
+                return Err(JsValue::from_str(&format!(
+                    "edge endpoint {} out of range, graph has {} nodes",
+                    node,
+                    self.vertices.len()
+                )));
+            }
+        }
+
+        Ok(self.push_edge(from, to, weight))
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+impl GeneralGraph {
+    /// Adds an edge between existing nodes and returns its index. Undirected
+    /// edges are listed in the adjacency of both endpoints.
+    pub fn push_edge(&mut self, from: usize, to: usize, weight: f64) -> usize {
+        let idx = self.edges.len();
+
+        self.edges.push(Edge { from, to, weight });
+        self.adjacency[from].push(idx);
+
+        if !self.directed && from != to {
+            self.adjacency[to].push(idx);
+        }
+
+        idx
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Indexes of the edges leaving `node`; for undirected graphs every
+    /// incident edge.
+    pub fn incident_edges(&self, node: usize) -> &[usize] {
+        &self.adjacency[node]
+    }
+
+    /// `(edge index, neighbor)` pairs reachable from `node` in one step.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency[node]
+            .iter()
+            .map(move |&idx| (idx, self.edges[idx].other(node)))
+    }
+
+    /// The same graph with every edge reversed.
+    pub fn transpose(&self) -> GeneralGraph {
+        let mut transposed = GeneralGraph::new(self.directed);
+
+        for vertex in self.vertices.iter() {
+            transposed.add_labeled_node(
+                vertex.label.clone(),
+                vertex.coordinate.x,
+                vertex.coordinate.y,
+            );
+        }
+
+        for edge in self.edges.iter() {
+            transposed.push_edge(edge.to, edge.from, edge.weight);
+        }
+
+        transposed
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.neighbors(from).any(|(_, neighbor)| neighbor == to)
+    }
+
+    /// The lightest edge leading from `from` to `to`, if any.
+    pub fn edge_between(&self, from: usize, to: usize) -> Option<usize> {
+        self.neighbors(from)
+            .filter(|&(_, neighbor)| neighbor == to)
+            .map(|(idx, _)| idx)
+            .min_by(|&a, &b| self.edges[a].weight.total_cmp(&self.edges[b].weight))
+    }
+
+    /// The largest x coordinate of any vertex, used to place side panels
+    /// next to the drawing.
+    pub fn max_x(&self) -> f64 {
+        self.vertices
+            .iter()
+            .map(|vertex| vertex.coordinate.x)
+            .fold(0.0, f64::max)
+    }
+}
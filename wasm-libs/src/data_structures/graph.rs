@@ -104,6 +104,20 @@ impl Graph {
         indexes
     }
 
+    /// Whether each node is waiting in the queue to be searched from. While
+    /// backtracking the queue holds the path instead, so nothing is.
+    pub fn frontier(&self) -> Vec<bool> {
+        let mut frontier = vec![false; self.nodes.len()];
+
+        if !self.is_backtracking {
+            for &idx in &self.queue {
+                frontier[idx] = true;
+            }
+        }
+
+        frontier
+    }
+
     pub fn set_start_node(&mut self, row: u32, column: u32) {
         let idx = self.get_index(row, column);
         self.start_node_index = Some(idx);
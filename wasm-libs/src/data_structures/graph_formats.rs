@@ -0,0 +1,1006 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    canvas::{Boundary, Coordinate},
+    data_structures::{general_graph::GeneralGraph, graph_generators::circle_position},
+};
+
+/// A node read from either format, before unpositioned nodes are laid out.
+struct ParsedNode {
+    label: String,
+    position: Option<(f64, f64)>,
+}
+
+#[derive(Default)]
+struct ParsedGraph {
+    directed: bool,
+    nodes: Vec<ParsedNode>,
+    edges: Vec<(usize, usize, f64)>,
+}
+
+impl ParsedGraph {
+    /// Builds the graph, placing nodes without a position on a circle
+    /// filling `boundary`.
+    fn build(self, boundary: &Boundary) -> GeneralGraph {
+        let unpositioned = self
+            .nodes
+            .iter()
+            .filter(|node| node.position.is_none())
+            .count();
+        let mut placed = 0;
+        let mut graph = GeneralGraph::new(self.directed);
+
+        for node in self.nodes {
+            let (x, y) = node.position.unwrap_or_else(|| {
+                placed += 1;
+                circle_position(placed - 1, unpositioned, boundary)
+            });
+
+            graph.add_labeled_node(node.label, x, y);
+        }
+
+        for (from, to, weight) in self.edges {
+            graph.push_edge(from, to, weight);
+        }
+
+        graph
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// An identifier, numeral or quoted string, with `quoted` set for the
+    /// latter so `"graph"` is not taken for a keyword.
+    Id {
+        text: String,
+        quoted: bool,
+    },
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Equals,
+    Semicolon,
+    Comma,
+    Colon,
+    DirectedEdge,
+    UndirectedEdge,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Id { text, .. } => format!("`{}`", text),
+            Token::OpenBrace => "`{`".to_string(),
+            Token::CloseBrace => "`}`".to_string(),
+            Token::OpenBracket => "`[`".to_string(),
+            Token::CloseBracket => "`]`".to_string(),
+            Token::Equals => "`=`".to_string(),
+            Token::Semicolon => "`;`".to_string(),
+            Token::Comma => "`,`".to_string(),
+            Token::Colon => "`:`".to_string(),
+            Token::DirectedEdge => "`->`".to_string(),
+            Token::UndirectedEdge => "`--`".to_string(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Id { text, quoted: false } if text.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn dot_error(position: Position, message: String) -> String {
+    format!(
+        "DOT line {}, column {}: {}",
+        position.line, position.column, message
+    )
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Position)>, String> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut idx = 0;
+    let (mut line, mut column) = (1, 1);
+
+    // Moves past `count` characters keeping the line and column current.
+    let advance = |idx: &mut usize, line: &mut usize, column: &mut usize, count: usize| {
+        for _ in 0..count {
+            if chars[*idx] == '\n' {
+                *line += 1;
+                *column = 1;
+            } else {
+                *column += 1;
+            }
+            *idx += 1;
+        }
+    };
+
+    while idx < chars.len() {
+        let position = Position { line, column };
+        let c = chars[idx];
+        let next = chars.get(idx + 1).copied();
+
+        if c.is_whitespace() {
+            advance(&mut idx, &mut line, &mut column, 1);
+        } else if c == '#' || (c == '/' && next == Some('/')) {
+            while idx < chars.len() && chars[idx] != '\n' {
+                advance(&mut idx, &mut line, &mut column, 1);
+            }
+        } else if c == '/' && next == Some('*') {
+            advance(&mut idx, &mut line, &mut column, 2);
+
+            loop {
+                if idx + 1 >= chars.len() {
+                    return Err(dot_error(position, "unterminated comment".to_string()));
+                }
+
+                if chars[idx] == '*' && chars[idx + 1] == '/' {
+                    advance(&mut idx, &mut line, &mut column, 2);
+                    break;
+                }
+
+                advance(&mut idx, &mut line, &mut column, 1);
+            }
+        } else if c == '"' {
+            let mut text = String::new();
+            advance(&mut idx, &mut line, &mut column, 1);
+
+            loop {
+                match chars.get(idx) {
+                    None => {
+                        return Err(dot_error(position, "unterminated string".to_string()));
+                    }
+                    Some('"') => break,
+                    Some('\\') if matches!(chars.get(idx + 1), Some('"') | Some('\\')) => {
+                        text.push(chars[idx + 1]);
+                        advance(&mut idx, &mut line, &mut column, 2);
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        advance(&mut idx, &mut line, &mut column, 1);
+                    }
+                }
+            }
+
+            advance(&mut idx, &mut line, &mut column, 1);
+            tokens.push((Token::Id { text, quoted: true }, position));
+        } else if c.is_alphanumeric()
+            || c == '_'
+            || c == '.'
+            || c == '-' && next != Some('-') && next != Some('>')
+        {
+            let start = idx;
+            let numeral = c.is_ascii_digit() || c == '.' || c == '-';
+
+            advance(&mut idx, &mut line, &mut column, 1);
+
+            while idx < chars.len()
+                && (if numeral {
+                    chars[idx].is_ascii_digit() || chars[idx] == '.'
+                } else {
+                    chars[idx].is_alphanumeric() || chars[idx] == '_'
+                })
+            {
+                advance(&mut idx, &mut line, &mut column, 1);
+            }
+
+            let text = chars[start..idx].iter().collect::<String>();
+
+            if numeral && text.parse::<f64>().is_err() {
+                return Err(dot_error(position, format!("invalid number `{}`", text)));
+            }
+
+            tokens.push((
+                Token::Id {
+                    text,
+                    quoted: false,
+                },
+                position,
+            ));
+        } else {
+            let (token, length) = match (c, next) {
+                ('-', Some('>')) => (Token::DirectedEdge, 2),
+                ('-', Some('-')) => (Token::UndirectedEdge, 2),
+                ('{', _) => (Token::OpenBrace, 1),
+                ('}', _) => (Token::CloseBrace, 1),
+                ('[', _) => (Token::OpenBracket, 1),
+                (']', _) => (Token::CloseBracket, 1),
+                ('=', _) => (Token::Equals, 1),
+                (';', _) => (Token::Semicolon, 1),
+                (',', _) => (Token::Comma, 1),
+                (':', _) => (Token::Colon, 1),
+                ('<', _) => {
+                    return Err(dot_error(
+                        position,
+                        "HTML strings are not supported".to_string(),
+                    ))
+                }
+                _ => return Err(dot_error(position, format!("unexpected character `{}`", c))),
+            };
+
+            advance(&mut idx, &mut line, &mut column, length);
+            tokens.push((token, position));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct DotParser {
+    tokens: Vec<(Token, Position)>,
+    next: usize,
+    end: Position,
+    graph: ParsedGraph,
+    ids: HashMap<String, usize>,
+}
+
+impl DotParser {
+    fn new(source: &str) -> Result<DotParser, String> {
+        let tokens = tokenize(source)?;
+        let lines = source.split('\n').collect::<Vec<_>>();
+        let end = Position {
+            line: lines.len(),
+            column: lines.last().map_or(0, |line| line.chars().count()) + 1,
+        };
+
+        Ok(DotParser {
+            tokens,
+            next: 0,
+            end,
+            graph: ParsedGraph::default(),
+            ids: HashMap::new(),
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> Position {
+        self.tokens
+            .get(self.next)
+            .map_or(self.end, |&(_, position)| position)
+    }
+
+    fn error(&self, message: String) -> String {
+        dot_error(self.position(), message)
+    }
+
+    fn found(&self) -> String {
+        self.peek()
+            .map_or("the end of the input".to_string(), Token::describe)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.next += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            Err(self.error(format!(
+                "expected {}, found {}",
+                token.describe(),
+                self.found()
+            )))
+        }
+    }
+
+    fn expect_id(&mut self, what: &str) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Id { text, .. }) => {
+                let text = text.clone();
+                self.next += 1;
+                Ok(text)
+            }
+            _ => Err(self.error(format!("expected {}, found {}", what, self.found()))),
+        }
+    }
+
+    /// `[strict] (graph | digraph) [ID] { stmt_list }`
+    fn parse(&mut self) -> Result<(), String> {
+        if self.peek().is_some_and(|token| token.is_keyword("strict")) {
+            self.next += 1;
+        }
+
+        self.graph.directed = match self.peek() {
+            Some(token) if token.is_keyword("digraph") => true,
+            Some(token) if token.is_keyword("graph") => false,
+            _ => {
+                return Err(self.error(format!(
+                    "expected `graph` or `digraph`, found {}",
+                    self.found()
+                )))
+            }
+        };
+        self.next += 1;
+
+        if matches!(self.peek(), Some(Token::Id { .. })) {
+            self.next += 1;
+        }
+
+        self.expect(Token::OpenBrace)?;
+
+        while !self.eat(&Token::CloseBrace) {
+            if self.peek().is_none() {
+                return Err(self.error("expected `}` to close the graph".to_string()));
+            }
+
+            self.statement()?;
+
+            if !self.eat(&Token::Semicolon) {
+                self.eat(&Token::Comma);
+            }
+        }
+
+        if self.peek().is_some() {
+            return Err(self.error(format!(
+                "unexpected {} after the end of the graph",
+                self.found()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), String> {
+        let token = self.peek().cloned().unwrap();
+
+        if token == Token::OpenBrace || token.is_keyword("subgraph") {
+            return Err(self.error("subgraphs are not supported".to_string()));
+        }
+
+        // Default attributes only affect styling, so they are skipped.
+        if ["graph", "node", "edge"]
+            .iter()
+            .any(|keyword| token.is_keyword(keyword))
+        {
+            self.next += 1;
+            self.attributes()?;
+            return Ok(());
+        }
+
+        let id = self.expect_id("a node id or statement")?;
+
+        if self.eat(&Token::Equals) {
+            self.expect_id("an attribute value")?;
+            return Ok(());
+        }
+
+        self.reject_port()?;
+
+        if matches!(
+            self.peek(),
+            Some(Token::DirectedEdge) | Some(Token::UndirectedEdge)
+        ) {
+            self.edge_statement(id)
+        } else {
+            let node = self.node(&id);
+            self.node_attributes(node)
+        }
+    }
+
+    fn edge_statement(&mut self, first: String) -> Result<(), String> {
+        let mut chain = vec![self.node(&first)];
+
+        while let Some(op) = self.peek().cloned() {
+            let expected = if self.graph.directed {
+                Token::DirectedEdge
+            } else {
+                Token::UndirectedEdge
+            };
+
+            if op != Token::DirectedEdge && op != Token::UndirectedEdge {
+                break;
+            }
+
+            if op != expected {
+                return Err(self.error(format!(
+                    "{} in {} graph, use {}",
+                    op.describe(),
+                    if self.graph.directed {
+                        "a directed"
+                    } else {
+                        "an undirected"
+                    },
+                    expected.describe()
+                )));
+            }
+
+            self.next += 1;
+
+            if self.peek() == Some(&Token::OpenBrace)
+                || self
+                    .peek()
+                    .is_some_and(|token| token.is_keyword("subgraph"))
+            {
+                return Err(self.error("subgraphs are not supported".to_string()));
+            }
+
+            let id = self.expect_id("a node id")?;
+            self.reject_port()?;
+            chain.push(self.node(&id));
+        }
+
+        let mut weight = None;
+        let mut label_weight = None;
+
+        for (name, value, position) in self.attributes()? {
+            match name.as_str() {
+                "weight" => {
+                    weight =
+                        Some(value.parse::<f64>().map_err(|_| {
+                            dot_error(position, format!("invalid weight `{}`", value))
+                        })?)
+                }
+                // Weighted graphs are often drawn with the weight as the
+                // edge label.
+                "label" => label_weight = value.parse::<f64>().ok(),
+                _ => {}
+            }
+        }
+
+        let weight = weight.or(label_weight).unwrap_or(1.0);
+
+        for pair in chain.windows(2) {
+            self.graph.edges.push((pair[0], pair[1], weight));
+        }
+
+        Ok(())
+    }
+
+    fn node_attributes(&mut self, node: usize) -> Result<(), String> {
+        for (name, value, position) in self.attributes()? {
+            match name.as_str() {
+                "label" => self.graph.nodes[node].label = value,
+                "pos" => {
+                    let coordinates = value
+                        .trim_end_matches('!')
+                        .split(',')
+                        .map(|part| part.trim().parse::<f64>())
+                        .collect::<Result<Vec<_>, _>>();
+
+                    match coordinates.as_deref() {
+                        Ok([x, y]) => self.graph.nodes[node].position = Some((*x, *y)),
+                        _ => {
+                            return Err(dot_error(
+                                position,
+                                format!("invalid pos `{}`, expected \"x,y\"", value),
+                            ))
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Zero or more `[name = value, ...]` lists.
+    fn attributes(&mut self) -> Result<Vec<(String, String, Position)>, String> {
+        let mut attributes = vec![];
+
+        while self.eat(&Token::OpenBracket) {
+            while !self.eat(&Token::CloseBracket) {
+                let name = self.expect_id("an attribute name or `]`")?;
+
+                self.expect(Token::Equals)?;
+
+                let value_position = self.position();
+                let value = self.expect_id(&format!("a value for `{}`", name))?;
+
+                attributes.push((name, value, value_position));
+
+                if !self.eat(&Token::Comma) {
+                    self.eat(&Token::Semicolon);
+                }
+            }
+        }
+
+        Ok(attributes)
+    }
+
+    fn reject_port(&self) -> Result<(), String> {
+        if self.peek() == Some(&Token::Colon) {
+            Err(self.error("node ports are not supported".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The index of node `id`, declaring it on first use.
+    fn node(&mut self, id: &str) -> usize {
+        if let Some(&idx) = self.ids.get(id) {
+            return idx;
+        }
+
+        self.graph.nodes.push(ParsedNode {
+            label: id.to_string(),
+            position: None,
+        });
+        self.ids.insert(id.to_string(), self.graph.nodes.len() - 1);
+
+        self.graph.nodes.len() - 1
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Infinite or NaN weights and positions do not survive a round trip through
+/// either format, so they are not exported.
+fn check_finite(graph: &GeneralGraph) -> Result<(), String> {
+    for (idx, vertex) in graph.vertices().iter().enumerate() {
+        let Coordinate { x, y } = vertex.coordinate;
+
+        if !x.is_finite() || !y.is_finite() {
+            return Err(format!(
+                "node {}: position ({}, {}) is not finite and cannot be exported",
+                idx, x, y
+            ));
+        }
+    }
+
+    for (idx, edge) in graph.edges().iter().enumerate() {
+        if !edge.weight.is_finite() {
+            return Err(format!(
+                "edge {}: weight {} is not finite and cannot be exported",
+                idx, edge.weight
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn read_dot(source: &str) -> Result<ParsedGraph, String> {
+    let mut parser = DotParser::new(source)?;
+
+    parser.parse()?;
+
+    Ok(parser.graph)
+}
+
+/// Reads a graph from a subset of Graphviz DOT: node and edge statements
+/// with `label`, `pos` ("x,y" in canvas pixels) and `weight` attributes.
+/// A numeric edge `label` is taken as the weight when `weight` is missing.
+/// Other attributes are ignored; nodes without `pos` go on a circle.
+#[wasm_bindgen]
+pub fn parse_dot(source: &str, boundary: Boundary) -> Result<GeneralGraph, JsValue> {
+    let graph = read_dot(source).map_err(|message| JsValue::from_str(&message))?;
+
+    Ok(graph.build(&boundary))
+}
+
+/// Writes the graph as DOT with node indexes as ids, so repeated labels
+/// survive a round trip through `parse_dot`. Fails on weights or positions
+/// that are not finite.
+#[wasm_bindgen]
+pub fn to_dot(graph: &GeneralGraph) -> Result<String, JsValue> {
+    write_dot(graph).map_err(|message| JsValue::from_str(&message))
+}
+
+fn write_dot(graph: &GeneralGraph) -> Result<String, String> {
+    check_finite(graph)?;
+
+    let (keyword, op) = if graph.is_directed() {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+
+    let mut dot = format!("{} {{\n", keyword);
+
+    for (idx, vertex) in graph.vertices().iter().enumerate() {
+        dot.push_str(&format!(
+            "    {} [label={}, pos=\"{},{}\"];\n",
+            idx,
+            quote(&vertex.label),
+            vertex.coordinate.x,
+            vertex.coordinate.y
+        ));
+    }
+
+    for edge in graph.edges() {
+        dot.push_str(&format!(
+            "    {} {} {} [weight={}];\n",
+            edge.from, op, edge.to, edge.weight
+        ));
+    }
+
+    dot.push_str("}\n");
+
+    Ok(dot)
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonGraph {
+    #[serde(default)]
+    directed: bool,
+    nodes: Vec<JsonNode>,
+    #[serde(default)]
+    edges: Vec<JsonEdge>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonNode {
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    x: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    y: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonEdge {
+    /// A node id or a node index.
+    from: serde_json::Value,
+    to: serde_json::Value,
+    #[serde(default = "default_weight")]
+    weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+fn resolve_endpoint(
+    value: &serde_json::Value,
+    ids: &HashMap<&str, usize>,
+    node_count: usize,
+    edge: usize,
+    field: &str,
+) -> Result<usize, String> {
+    match value {
+        serde_json::Value::String(id) => ids
+            .get(id.as_str())
+            .copied()
+            .ok_or_else(|| format!("edge {}: unknown node id `{}` in `{}`", edge, id, field)),
+        serde_json::Value::Number(number) => match number.as_u64() {
+            Some(idx) if (idx as usize) < node_count => Ok(idx as usize),
+            _ => Err(format!(
+                "edge {}: `{}` index {} is out of range for {} nodes",
+                edge, field, number, node_count
+            )),
+        },
+        other => Err(format!(
+            "edge {}: `{}` must be a node id or index, found {}",
+            edge, field, other
+        )),
+    }
+}
+
+fn parse_json_graph(source: &str) -> Result<ParsedGraph, String> {
+    let json: JsonGraph =
+        serde_json::from_str(source).map_err(|error| format!("invalid graph JSON: {}", error))?;
+
+    let mut ids = HashMap::new();
+
+    for (idx, node) in json.nodes.iter().enumerate() {
+        if ids.insert(node.id.as_str(), idx).is_some() {
+            return Err(format!("node {}: duplicate id `{}`", idx, node.id));
+        }
+    }
+
+    let mut nodes = vec![];
+
+    for (idx, node) in json.nodes.iter().enumerate() {
+        let position = match (node.x, node.y) {
+            (Some(x), Some(y)) => Some((x, y)),
+            (None, None) => None,
+            _ => {
+                return Err(format!(
+                    "node {} (`{}`): give both `x` and `y` or neither",
+                    idx, node.id
+                ))
+            }
+        };
+
+        nodes.push(ParsedNode {
+            label: node.label.clone().unwrap_or_else(|| node.id.clone()),
+            position,
+        });
+    }
+
+    let mut edges = vec![];
+
+    for (idx, edge) in json.edges.iter().enumerate() {
+        let from = resolve_endpoint(&edge.from, &ids, nodes.len(), idx, "from")?;
+        let to = resolve_endpoint(&edge.to, &ids, nodes.len(), idx, "to")?;
+
+        edges.push((from, to, edge.weight));
+    }
+
+    Ok(ParsedGraph {
+        directed: json.directed,
+        nodes,
+        edges,
+    })
+}
+
+/// Reads a graph from JSON of the form
+/// `{"directed": true, "nodes": [{"id": "a", "label": "A", "x": 10, "y": 20}],
+/// "edges": [{"from": "a", "to": "b", "weight": 2}]}`. Only node `id` and the
+/// edge endpoints, given as ids or node indexes, are required.
+#[wasm_bindgen]
+pub fn parse_graph_json(source: &str, boundary: Boundary) -> Result<GeneralGraph, JsValue> {
+    let graph = parse_json_graph(source).map_err(|message| JsValue::from_str(&message))?;
+
+    Ok(graph.build(&boundary))
+}
+
+/// Writes the graph in the `parse_graph_json` schema, with node indexes as
+/// ids. Fails on weights or positions that are not finite, which JSON has
+/// no numbers for.
+#[wasm_bindgen]
+pub fn to_graph_json(graph: &GeneralGraph) -> Result<String, JsValue> {
+    write_graph_json(graph).map_err(|message| JsValue::from_str(&message))
+}
+
+fn write_graph_json(graph: &GeneralGraph) -> Result<String, String> {
+    check_finite(graph)?;
+
+    let json = JsonGraph {
+        directed: graph.is_directed(),
+        nodes: graph
+            .vertices()
+            .iter()
+            .enumerate()
+            .map(|(idx, vertex)| JsonNode {
+                id: idx.to_string(),
+                label: Some(vertex.label.clone()),
+                x: Some(vertex.coordinate.x),
+                y: Some(vertex.coordinate.y),
+            })
+            .collect(),
+        edges: graph
+            .edges()
+            .iter()
+            .map(|edge| JsonEdge {
+                from: edge.from.into(),
+                to: edge.to.into(),
+                weight: edge.weight,
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&json).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABELS: [&str; 5] = [
+        "plain",
+        "with \"quotes\"",
+        "ends with \\",
+        "a \\\" in the middle",
+        "",
+    ];
+
+    fn sample(directed: bool) -> GeneralGraph {
+        let mut graph = GeneralGraph::new(directed);
+
+        for (idx, label) in LABELS.iter().enumerate() {
+            graph.add_labeled_node(label.to_string(), 10.5 * idx as f64, -3.25 + idx as f64);
+        }
+
+        graph.push_edge(0, 1, 2.5);
+        graph.push_edge(1, 2, -4.0);
+        graph.push_edge(3, 3, 0.0);
+        graph.push_edge(4, 0, 1e-3);
+
+        graph
+    }
+
+    fn build(graph: ParsedGraph) -> GeneralGraph {
+        graph.build(&Boundary::new(800.0, 600.0))
+    }
+
+    fn assert_same(read: &GeneralGraph, written: &GeneralGraph) {
+        assert_eq!(read.is_directed(), written.is_directed());
+        assert_eq!(read.node_count(), written.node_count());
+        assert_eq!(read.edge_count(), written.edge_count());
+
+        for (a, b) in read.vertices().iter().zip(written.vertices()) {
+            assert_eq!(a.label, b.label);
+            assert_eq!(a.coordinate.x, b.coordinate.x);
+            assert_eq!(a.coordinate.y, b.coordinate.y);
+        }
+
+        for (a, b) in read.edges().iter().zip(written.edges()) {
+            assert_eq!((a.from, a.to, a.weight), (b.from, b.to, b.weight));
+        }
+    }
+
+    fn dot_error_of(source: &str) -> String {
+        match read_dot(source) {
+            Ok(_) => panic!("{:?} parsed", source),
+            Err(message) => message,
+        }
+    }
+
+    fn json_error_of(source: &str) -> String {
+        match parse_json_graph(source) {
+            Ok(_) => panic!("{:?} parsed", source),
+            Err(message) => message,
+        }
+    }
+
+    #[test]
+    fn dot_round_trip() {
+        for directed in [false, true] {
+            let graph = sample(directed);
+            let dot = write_dot(&graph).unwrap();
+
+            assert_same(&build(read_dot(&dot).unwrap()), &graph);
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        for directed in [false, true] {
+            let graph = sample(directed);
+            let json = write_graph_json(&graph).unwrap();
+
+            assert_same(&build(parse_json_graph(&json).unwrap()), &graph);
+        }
+    }
+
+    #[test]
+    fn dot_skips_comments_and_reads_chains() {
+        let graph = build(
+            read_dot(
+                "// leading comment\n\
+                 strict digraph G {\n\
+                 \x20   # shell style\n\
+                 \x20   node [shape=circle];\n\
+                 \x20   a [label=\"A\" pos=\"1,2!\"]; /* block\n\
+                 \x20   comment */ b\n\
+                 \x20   a -> b -> c [weight=3]\n\
+                 \x20   c -> a [label=\"-1.5\"], rankdir = LR\n\
+                 }\n",
+            )
+            .unwrap(),
+        );
+
+        assert!(graph.is_directed());
+        assert_eq!(
+            graph
+                .vertices()
+                .iter()
+                .map(|vertex| vertex.label.as_str())
+                .collect::<Vec<_>>(),
+            ["A", "b", "c"]
+        );
+        assert_eq!(graph.vertices()[0].coordinate.x, 1.0);
+        assert_eq!(graph.vertices()[0].coordinate.y, 2.0);
+        assert_eq!(
+            graph
+                .edges()
+                .iter()
+                .map(|edge| (edge.from, edge.to, edge.weight))
+                .collect::<Vec<_>>(),
+            [(0, 1, 3.0), (1, 2, 3.0), (2, 0, -1.5)]
+        );
+    }
+
+    #[test]
+    fn dot_errors_point_at_the_offending_token() {
+        for (source, expected) in [
+            (
+                "graph {\n  a -- \"b\n}",
+                "DOT line 2, column 8: unterminated string",
+            ),
+            (
+                "graph {\n  /* a -- b\n}",
+                "DOT line 2, column 3: unterminated comment",
+            ),
+            (
+                "graph {\n  a -> b\n}",
+                "DOT line 2, column 5: `->` in an undirected graph, use `--`",
+            ),
+            (
+                "digraph {\n  a -> b [weight=heavy]\n}",
+                "DOT line 2, column 18: invalid weight `heavy`",
+            ),
+            (
+                "graph {\n  a [pos=\"1\"]\n}",
+                "DOT line 2, column 10: invalid pos `1`, expected \"x,y\"",
+            ),
+            (
+                "graph {\n  a @ b\n}",
+                "DOT line 2, column 5: unexpected character `@`",
+            ),
+            (
+                "graph {\n  subgraph s { a }\n}",
+                "DOT line 2, column 3: subgraphs are not supported",
+            ),
+            (
+                "graph { a }\nb",
+                "DOT line 2, column 1: unexpected `b` after the end of the graph",
+            ),
+            (
+                "graph {\n  a -- b",
+                "DOT line 2, column 9: expected `}` to close the graph",
+            ),
+            (
+                "tree { }",
+                "DOT line 1, column 1: expected `graph` or `digraph`, found `tree`",
+            ),
+        ] {
+            assert_eq!(dot_error_of(source), expected, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn json_errors_name_the_node_or_edge() {
+        for (source, expected) in [
+            (
+                r#"{"nodes": [{"id": "a"}, {"id": "a"}]}"#,
+                "node 1: duplicate id `a`",
+            ),
+            (
+                r#"{"nodes": [{"id": "a", "x": 1}]}"#,
+                "node 0 (`a`): give both `x` and `y` or neither",
+            ),
+            (
+                r#"{"nodes": [{"id": "a"}], "edges": [{"from": "a", "to": "b"}]}"#,
+                "edge 0: unknown node id `b` in `to`",
+            ),
+            (
+                r#"{"nodes": [{"id": "a"}], "edges": [{"from": 1, "to": 0}]}"#,
+                "edge 0: `from` index 1 is out of range for 1 nodes",
+            ),
+        ] {
+            assert_eq!(json_error_of(source), expected, "{:?}", source);
+        }
+
+        assert!(json_error_of(r#"{"nodes": [], "colour": 1}"#).starts_with("invalid graph JSON"));
+    }
+
+    #[test]
+    fn export_rejects_non_finite_numbers() {
+        let mut graph = sample(false);
+
+        graph.push_edge(0, 2, f64::INFINITY);
+
+        assert_eq!(
+            write_graph_json(&graph).unwrap_err(),
+            "edge 4: weight inf is not finite and cannot be exported"
+        );
+        assert!(write_dot(&graph).is_err());
+
+        let mut graph = GeneralGraph::new(true);
+
+        graph.add_node(f64::NAN, 0.0);
+
+        assert_eq!(
+            write_dot(&graph).unwrap_err(),
+            "node 0: position (NaN, 0) is not finite and cannot be exported"
+        );
+    }
+}
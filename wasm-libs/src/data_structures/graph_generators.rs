@@ -0,0 +1,353 @@
+use std::{collections::VecDeque, f64::consts::PI};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    canvas::Boundary,
+    data_structures::{bipartite_graph::BipartiteGraph, general_graph::GeneralGraph},
+    rng::Rng,
+};
+
+/// Space kept free along the canvas border so nodes are drawn whole.
+const MARGIN: f64 = 32.0;
+
+fn check_probability(p: f64) -> Result<(), JsValue> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(JsValue::from_str(&format!(
+            "edge probability must be between 0 and 1, got {}",
+            p
+        )))
+    }
+}
+
+/// A uniform integer weight in `1..=max_weight`, or 1 for unweighted graphs.
+fn random_weight(rng: &mut Rng, max_weight: u32) -> f64 {
+    if max_weight <= 1 {
+        1.0
+    } else {
+        (1 + rng.below(max_weight as usize)) as f64
+    }
+}
+
+/// Position of the `idx`th of `n` points evenly spaced on a circle filling
+/// `boundary`, starting at the top.
+pub(crate) fn circle_position(idx: usize, n: usize, boundary: &Boundary) -> (f64, f64) {
+    let (center_x, center_y) = (boundary.width / 2.0, boundary.height / 2.0);
+    let radius = (center_x.min(center_y) - MARGIN).max(0.0);
+    let angle = 2.0 * PI * idx as f64 / n as f64 - PI / 2.0;
+
+    (
+        center_x + radius * angle.cos(),
+        center_y + radius * angle.sin(),
+    )
+}
+
+/// Adds `n` nodes evenly spaced on a circle filling `boundary`.
+fn circle_layout(n: usize, directed: bool, boundary: &Boundary) -> GeneralGraph {
+    let mut graph = GeneralGraph::new(directed);
+
+    for idx in 0..n {
+        let (x, y) = circle_position(idx, n, boundary);
+        graph.add_node(x, y);
+    }
+
+    graph
+}
+
+/// G(n, p): every pair of nodes, or ordered pair when `directed`, is joined
+/// independently with probability `p`.
+#[wasm_bindgen]
+pub fn generate_erdos_renyi(
+    n: usize,
+    p: f64,
+    directed: bool,
+    max_weight: u32,
+    seed: u32,
+    boundary: Boundary,
+) -> Result<GeneralGraph, JsValue> {
+    check_probability(p)?;
+
+    let mut rng = Rng::new(seed as u64);
+    let mut graph = circle_layout(n, directed, &boundary);
+
+    for from in 0..n {
+        for to in 0..n {
+            if from == to || (!directed && to < from) {
+                continue;
+            }
+
+            if rng.chance(p) {
+                let weight = random_weight(&mut rng, max_weight);
+                graph.push_edge(from, to, weight);
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Preferential attachment: starting from a clique of `m + 1` nodes, every
+/// new node joins `m` distinct existing nodes chosen with probability
+/// proportional to their degree.
+#[wasm_bindgen]
+pub fn generate_barabasi_albert(
+    n: usize,
+    m: usize,
+    max_weight: u32,
+    seed: u32,
+    boundary: Boundary,
+) -> Result<GeneralGraph, JsValue> {
+    if m == 0 || m >= n {
+        return Err(JsValue::from_str(&format!(
+            "Barabási-Albert needs 0 < m < n, got m = {} and n = {}",
+            m, n
+        )));
+    }
+
+    let mut rng = Rng::new(seed as u64);
+    let mut graph = circle_layout(n, false, &boundary);
+    // Every node appears once per incident edge, so a uniform pick from
+    // this list is a degree proportional pick.
+    let mut endpoints = vec![];
+
+    for from in 0..=m {
+        for to in (from + 1)..=m {
+            let weight = random_weight(&mut rng, max_weight);
+            graph.push_edge(from, to, weight);
+            endpoints.extend([from, to]);
+        }
+    }
+
+    for node in (m + 1)..n {
+        let mut targets: Vec<usize> = vec![];
+
+        while targets.len() < m {
+            let target = endpoints[rng.below(endpoints.len())];
+
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+
+        for target in targets {
+            let weight = random_weight(&mut rng, max_weight);
+            graph.push_edge(node, target, weight);
+            endpoints.extend([node, target]);
+        }
+    }
+
+    Ok(graph)
+}
+
+/// A uniformly random labeled tree decoded from a random Prüfer sequence,
+/// drawn in layers by depth from node 0. Directed trees point away from the
+/// root.
+#[wasm_bindgen]
+pub fn generate_random_tree(
+    n: usize,
+    directed: bool,
+    max_weight: u32,
+    seed: u32,
+    boundary: Boundary,
+) -> GeneralGraph {
+    let mut rng = Rng::new(seed as u64);
+    let mut adjacency = vec![vec![]; n];
+
+    if n == 2 {
+        adjacency[0].push(1);
+        adjacency[1].push(0);
+    } else if n > 2 {
+        let sequence = (0..n - 2).map(|_| rng.below(n)).collect::<Vec<_>>();
+        let mut degree = vec![1; n];
+
+        for &node in sequence.iter() {
+            degree[node] += 1;
+        }
+
+        for &node in sequence.iter() {
+            let leaf = (0..n).find(|&leaf| degree[leaf] == 1).unwrap();
+
+            adjacency[leaf].push(node);
+            adjacency[node].push(leaf);
+            degree[leaf] -= 1;
+            degree[node] -= 1;
+        }
+
+        let last = (0..n).filter(|&node| degree[node] == 1).collect::<Vec<_>>();
+        adjacency[last[0]].push(last[1]);
+        adjacency[last[1]].push(last[0]);
+    }
+
+    // Breadth first from the root gives the parent and depth of every node.
+    let mut parent = vec![None; n];
+    let mut depth = vec![0; n];
+    let mut order = vec![];
+    let mut queue = VecDeque::new();
+    let mut seen = vec![false; n];
+
+    if n > 0 {
+        queue.push_back(0);
+        seen[0] = true;
+    }
+
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+
+        for &neighbor in adjacency[node].iter() {
+            if !seen[neighbor] {
+                seen[neighbor] = true;
+                parent[neighbor] = Some(node);
+                depth[neighbor] = depth[node] + 1;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    let levels = depth.iter().max().map_or(0, |&max| max + 1);
+    let mut level_sizes = vec![0; levels];
+    let mut position = vec![0; n];
+
+    for &node in order.iter() {
+        position[node] = level_sizes[depth[node]];
+        level_sizes[depth[node]] += 1;
+    }
+
+    let mut graph = GeneralGraph::new(directed);
+    let level_height = (boundary.height - 2.0 * MARGIN) / levels.max(1) as f64;
+
+    for node in 0..n {
+        let spacing = boundary.width / (level_sizes[depth[node]] + 1) as f64;
+
+        graph.add_node(
+            spacing * (position[node] + 1) as f64,
+            MARGIN + level_height * (depth[node] as f64 + 0.5),
+        );
+    }
+
+    for &node in order.iter() {
+        if let Some(parent) = parent[node] {
+            let weight = random_weight(&mut rng, max_weight);
+            graph.push_edge(parent, node, weight);
+        }
+    }
+
+    graph
+}
+
+/// K(n), with both directions of every pair when `directed`.
+#[wasm_bindgen]
+pub fn generate_complete_graph(
+    n: usize,
+    directed: bool,
+    max_weight: u32,
+    seed: u32,
+    boundary: Boundary,
+) -> GeneralGraph {
+    let mut rng = Rng::new(seed as u64);
+    let mut graph = circle_layout(n, directed, &boundary);
+
+    for from in 0..n {
+        for to in 0..n {
+            if from != to && (directed || from < to) {
+                let weight = random_weight(&mut rng, max_weight);
+                graph.push_edge(from, to, weight);
+            }
+        }
+    }
+
+    graph
+}
+
+/// Joins every left node to every right node independently with
+/// probability `p`, for the bipartite matching visualization.
+#[wasm_bindgen]
+pub fn generate_random_bipartite_graph(
+    left: usize,
+    right: usize,
+    p: f64,
+    seed: u32,
+) -> Result<BipartiteGraph, JsValue> {
+    check_probability(p)?;
+
+    let mut rng = Rng::new(seed as u64);
+    let mut graph = BipartiteGraph::new(left, right);
+
+    for l in 0..left {
+        for r in 0..right {
+            if rng.chance(p) {
+                graph.push_edge(l, r);
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// The same random bipartite graph as a general graph in the two column
+/// layout, with random weights.
+#[wasm_bindgen]
+pub fn generate_bipartite_graph(
+    left: usize,
+    right: usize,
+    p: f64,
+    max_weight: u32,
+    seed: u32,
+    boundary: Boundary,
+) -> Result<GeneralGraph, JsValue> {
+    let bipartite = generate_random_bipartite_graph(left, right, p, seed)?;
+    let mut graph = BipartiteGraph::new(left, right).to_general_graph(&boundary);
+    let mut rng = Rng::new(seed as u64 ^ 0xFFFF_FFFF);
+
+    for &(l, r) in bipartite.edges() {
+        let weight = random_weight(&mut rng, max_weight);
+        graph.push_edge(l, left + r, weight);
+    }
+
+    Ok(graph)
+}
+
+/// A `rows` x `columns` lattice joining horizontal and vertical neighbors;
+/// directed grids point right and down.
+#[wasm_bindgen]
+pub fn generate_grid_graph(
+    rows: usize,
+    columns: usize,
+    directed: bool,
+    max_weight: u32,
+    seed: u32,
+    boundary: Boundary,
+) -> GeneralGraph {
+    let mut rng = Rng::new(seed as u64);
+    let mut graph = GeneralGraph::new(directed);
+    let spacing_x = (boundary.width - 2.0 * MARGIN) / columns.saturating_sub(1).max(1) as f64;
+    let spacing_y = (boundary.height - 2.0 * MARGIN) / rows.saturating_sub(1).max(1) as f64;
+
+    for row in 0..rows {
+        for column in 0..columns {
+            graph.add_node(
+                MARGIN + spacing_x * column as f64,
+                MARGIN + spacing_y * row as f64,
+            );
+        }
+    }
+
+    for row in 0..rows {
+        for column in 0..columns {
+            let node = row * columns + column;
+
+            if column + 1 < columns {
+                let weight = random_weight(&mut rng, max_weight);
+                graph.push_edge(node, node + 1, weight);
+            }
+
+            if row + 1 < rows {
+                let weight = random_weight(&mut rng, max_weight);
+                graph.push_edge(node, node + columns, weight);
+            }
+        }
+    }
+
+    graph
+}
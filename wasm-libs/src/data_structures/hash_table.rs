@@ -0,0 +1,264 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Collision {
+    /// Every bucket holds a list of the keys hashed to it.
+    Chaining,
+    /// Open addressing probing `h, h + 1, h + 2, ...`.
+    Linear,
+    /// Open addressing probing `h, h + 1, h + 4, h + 9, ...`.
+    Quadratic,
+    /// Open addressing probing `h, h + s, h + 2s, ...` with a second hash
+    /// `s` of the key.
+    Double,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Slot {
+    Empty,
+    Occupied(i32),
+    /// A removed key. Lookups probe past it, inserts may reuse it.
+    Tombstone,
+}
+
+/// A bucket and, with chaining, a position in its chain. Open addressing
+/// always uses position 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    pub bucket: usize,
+    pub position: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Insertion {
+    Stored(Location),
+    Duplicate(Location),
+    /// Every probed slot was taken, which quadratic probing can run into
+    /// above half load.
+    Full,
+}
+
+/// A hash table of integer keys with a prime number of buckets, so that
+/// double hashing steps reach every bucket.
+#[derive(Clone, Debug)]
+pub struct HashTable {
+    collision: Collision,
+    capacity: usize,
+    /// The buckets of open addressing, empty with chaining.
+    slots: Vec<Slot>,
+    /// The buckets of chaining, empty with open addressing.
+    chains: Vec<Vec<i32>>,
+    len: usize,
+    tombstones: usize,
+}
+
+pub fn next_prime(from: usize) -> usize {
+    let is_prime = |n: usize| {
+        n >= 2
+            && (2..)
+                .take_while(|d| d * d <= n)
+                .all(|d| !n.is_multiple_of(d))
+    };
+
+    (from.max(3)..).find(|&n| is_prime(n)).unwrap()
+}
+
+impl HashTable {
+    /// An empty table with the first prime number of buckets from
+    /// `capacity` on.
+    pub fn new(collision: Collision, capacity: usize) -> HashTable {
+        let capacity = next_prime(capacity);
+        let chained = collision == Collision::Chaining;
+
+        HashTable {
+            collision,
+            capacity,
+            slots: if chained {
+                vec![]
+            } else {
+                vec![Slot::Empty; capacity]
+            },
+            chains: if chained {
+                vec![vec![]; capacity]
+            } else {
+                vec![]
+            },
+            len: 0,
+            tombstones: 0,
+        }
+    }
+
+    /// An empty table with about twice the buckets of this one.
+    pub fn grown(&self) -> HashTable {
+        HashTable::new(self.collision, 2 * self.capacity() + 1)
+    }
+
+    pub fn collision(&self) -> Collision {
+        self.collision
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn tombstones(&self) -> usize {
+        self.tombstones
+    }
+
+    /// Keys per bucket. Tombstones are not keys, but they lengthen probe
+    /// sequences just the same, so open addressing counts them.
+    pub fn load_factor(&self) -> f64 {
+        (self.len + self.tombstones) as f64 / self.capacity() as f64
+    }
+
+    /// The slot of `bucket`, always empty with chaining.
+    pub fn slot(&self, bucket: usize) -> Slot {
+        self.slots.get(bucket).copied().unwrap_or(Slot::Empty)
+    }
+
+    /// The chain of `bucket`, always empty with open addressing.
+    pub fn chain(&self, bucket: usize) -> &[i32] {
+        self.chains.get(bucket).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every key, bucket by bucket.
+    pub fn keys(&self) -> Vec<i32> {
+        match self.collision {
+            Collision::Chaining => self.chains.concat(),
+            _ => self
+                .slots
+                .iter()
+                .filter_map(|slot| match slot {
+                    Slot::Occupied(key) => Some(*key),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// `key mod m`, the first bucket looked at.
+    pub fn home(&self, key: i32) -> usize {
+        key.rem_euclid(self.capacity() as i32) as usize
+    }
+
+    /// `1 + key mod (m - 1)`, the stride of double hashing, never 0.
+    pub fn step(&self, key: i32) -> usize {
+        1 + key.rem_euclid(self.capacity() as i32 - 1) as usize
+    }
+
+    /// The `attempt`th bucket of the open addressing probe sequence.
+    fn probe(&self, key: i32, attempt: usize) -> usize {
+        let offset = match self.collision {
+            Collision::Chaining | Collision::Linear => attempt,
+            Collision::Quadratic => attempt * attempt,
+            Collision::Double => attempt * self.step(key),
+        };
+
+        (self.home(key) + offset) % self.capacity()
+    }
+
+    /// The locations compared while looking for `key`, and where it is.
+    pub fn find(&self, key: i32) -> (Vec<Location>, Option<Location>) {
+        let mut path = vec![];
+
+        if self.collision == Collision::Chaining {
+            let bucket = self.home(key);
+
+            for (position, &other) in self.chains[bucket].iter().enumerate() {
+                let location = Location { bucket, position };
+
+                path.push(location);
+
+                if other == key {
+                    return (path, Some(location));
+                }
+            }
+
+            return (path, None);
+        }
+
+        for attempt in 0..self.capacity() {
+            let location = Location {
+                bucket: self.probe(key, attempt),
+                position: 0,
+            };
+
+            path.push(location);
+
+            match self.slots[location.bucket] {
+                Slot::Empty => break,
+                Slot::Occupied(other) if other == key => return (path, Some(location)),
+                _ => {}
+            }
+        }
+
+        (path, None)
+    }
+
+    /// Adds `key` at the end of its chain or in the first free slot of its
+    /// probe sequence, reusing a tombstone if one came before the empty
+    /// slot that proves the key absent.
+    pub fn insert(&mut self, key: i32) -> (Vec<Location>, Insertion) {
+        let (path, found) = self.find(key);
+
+        if let Some(location) = found {
+            return (path, Insertion::Duplicate(location));
+        }
+
+        let location = if self.collision == Collision::Chaining {
+            let bucket = self.home(key);
+
+            self.chains[bucket].push(key);
+
+            Location {
+                bucket,
+                position: self.chains[bucket].len() - 1,
+            }
+        } else {
+            let free = path
+                .iter()
+                .find(|location| !matches!(self.slots[location.bucket], Slot::Occupied(_)));
+
+            let Some(&location) = free else {
+                return (path, Insertion::Full);
+            };
+
+            if self.slots[location.bucket] == Slot::Tombstone {
+                self.tombstones -= 1;
+            }
+
+            self.slots[location.bucket] = Slot::Occupied(key);
+
+            location
+        };
+
+        self.len += 1;
+
+        (path, Insertion::Stored(location))
+    }
+
+    /// Removes `key` from its chain, or leaves a tombstone in its slot so
+    /// that probe sequences passing through it stay intact.
+    pub fn remove(&mut self, key: i32) -> (Vec<Location>, Option<Location>) {
+        let (path, found) = self.find(key);
+
+        if let Some(location) = found {
+            if self.collision == Collision::Chaining {
+                self.chains[location.bucket].remove(location.position);
+            } else {
+                self.slots[location.bucket] = Slot::Tombstone;
+                self.tombstones += 1;
+            }
+
+            self.len -= 1;
+        }
+
+        (path, found)
+    }
+}
@@ -0,0 +1,427 @@
+#[derive(Clone, Debug)]
+pub struct ListNode {
+    pub value: i32,
+    pub next: Option<usize>,
+    pub prev: Option<usize>,
+}
+
+/// One primitive change or look at a list. Every operation is a sequence
+/// of these, returned so that visualizations can replay it pointer by
+/// pointer on a copy of the list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListStep {
+    /// Looked at the node while walking to a position.
+    Visit(usize),
+    /// A named cursor, like `prev` while reversing, moved.
+    Cursor(&'static str, Option<usize>),
+    /// A new unlinked node holding the value took the slot.
+    Alloc(usize, i32),
+    SetNext(usize, Option<usize>),
+    SetPrev(usize, Option<usize>),
+    SetHead(Option<usize>),
+    SetTail(Option<usize>),
+    /// The node, unlinked by now, gave its slot back.
+    Free(usize),
+}
+
+/// A singly or doubly linked list. Nodes live in slots that keep their
+/// index while the node exists, so visualizations can follow a node and
+/// its pointers from one step to the next.
+#[derive(Clone, Debug)]
+pub struct LinkedList {
+    doubly: bool,
+    slots: Vec<Option<ListNode>>,
+    free: Vec<usize>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+impl LinkedList {
+    pub fn new(doubly: bool) -> LinkedList {
+        LinkedList {
+            doubly,
+            slots: vec![],
+            free: vec![],
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn is_doubly(&self) -> bool {
+        self.doubly
+    }
+
+    pub fn head(&self) -> Option<usize> {
+        self.head
+    }
+
+    pub fn tail(&self) -> Option<usize> {
+        self.tail
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of slots, occupied or not; every node index is below it.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn node(&self, id: usize) -> &ListNode {
+        self.slots[id].as_ref().expect("no node in this slot")
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.slots.get(id).is_some_and(|slot| slot.is_some())
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut ListNode {
+        self.slots[id].as_mut().expect("no node in this slot")
+    }
+
+    /// Nodes from the head along the `next` pointers.
+    pub fn ids(&self) -> Vec<usize> {
+        let mut ids = vec![];
+        let mut current = self.head;
+
+        while let Some(id) = current {
+            ids.push(id);
+            current = self.node(id).next;
+        }
+
+        ids
+    }
+
+    pub fn values(&self) -> Vec<i32> {
+        self.ids().iter().map(|&id| self.node(id).value).collect()
+    }
+
+    pub fn apply(&mut self, step: ListStep) {
+        match step {
+            ListStep::Visit(_) | ListStep::Cursor(..) => {}
+            ListStep::Alloc(id, value) => {
+                if id == self.slots.len() {
+                    self.slots.push(None);
+                } else {
+                    self.free.retain(|&free| free != id);
+                }
+
+                self.slots[id] = Some(ListNode {
+                    value,
+                    next: None,
+                    prev: None,
+                });
+                self.len += 1;
+            }
+            ListStep::SetNext(id, next) => self.node_mut(id).next = next,
+            ListStep::SetPrev(id, prev) => self.node_mut(id).prev = prev,
+            ListStep::SetHead(head) => self.head = head,
+            ListStep::SetTail(tail) => self.tail = tail,
+            ListStep::Free(id) => {
+                self.slots[id] = None;
+                self.free.push(id);
+                self.len -= 1;
+            }
+        }
+    }
+
+    fn run(&mut self, steps: &mut Vec<ListStep>, step: ListStep) {
+        self.apply(step);
+        steps.push(step);
+    }
+
+    fn next_id(&self) -> usize {
+        self.free.last().copied().unwrap_or(self.slots.len())
+    }
+
+    pub fn push_front(&mut self, value: i32) -> Vec<ListStep> {
+        let mut steps = vec![];
+        let id = self.next_id();
+        let head = self.head;
+
+        self.run(&mut steps, ListStep::Alloc(id, value));
+        self.run(&mut steps, ListStep::SetNext(id, head));
+
+        if let Some(head) = head.filter(|_| self.doubly) {
+            self.run(&mut steps, ListStep::SetPrev(head, Some(id)));
+        }
+
+        self.run(&mut steps, ListStep::SetHead(Some(id)));
+
+        if self.tail.is_none() {
+            self.run(&mut steps, ListStep::SetTail(Some(id)));
+        }
+
+        steps
+    }
+
+    pub fn push_back(&mut self, value: i32) -> Vec<ListStep> {
+        let mut steps = vec![];
+        let id = self.next_id();
+        let tail = self.tail;
+
+        self.run(&mut steps, ListStep::Alloc(id, value));
+
+        if self.doubly {
+            self.run(&mut steps, ListStep::SetPrev(id, tail));
+        }
+
+        match tail {
+            Some(tail) => self.run(&mut steps, ListStep::SetNext(tail, Some(id))),
+            None => self.run(&mut steps, ListStep::SetHead(Some(id))),
+        }
+
+        self.run(&mut steps, ListStep::SetTail(Some(id)));
+
+        steps
+    }
+
+    pub fn pop_front(&mut self) -> (Option<i32>, Vec<ListStep>) {
+        let mut steps = vec![];
+
+        let Some(head) = self.head else {
+            return (None, steps);
+        };
+
+        let node = self.node(head).clone();
+
+        self.run(&mut steps, ListStep::SetHead(node.next));
+
+        match node.next {
+            Some(next) if self.doubly => self.run(&mut steps, ListStep::SetPrev(next, None)),
+            Some(_) => {}
+            None => self.run(&mut steps, ListStep::SetTail(None)),
+        }
+
+        self.run(&mut steps, ListStep::Free(head));
+
+        (Some(node.value), steps)
+    }
+
+    /// Removes the last node. A singly linked list has to walk to the node
+    /// before it first.
+    pub fn pop_back(&mut self) -> (Option<i32>, Vec<ListStep>) {
+        let mut steps = vec![];
+
+        let Some(tail) = self.tail else {
+            return (None, steps);
+        };
+
+        let value = self.node(tail).value;
+
+        let before = if self.doubly {
+            self.node(tail).prev
+        } else {
+            self.walk(self.len - 1, &mut steps)
+        };
+
+        match before {
+            Some(before) => self.run(&mut steps, ListStep::SetNext(before, None)),
+            None => self.run(&mut steps, ListStep::SetHead(None)),
+        }
+
+        self.run(&mut steps, ListStep::SetTail(before));
+        self.run(&mut steps, ListStep::Free(tail));
+
+        (Some(value), steps)
+    }
+
+    /// Visits the first `count` nodes and returns the last of them.
+    fn walk(&mut self, count: usize, steps: &mut Vec<ListStep>) -> Option<usize> {
+        let mut current = None;
+
+        for _ in 0..count {
+            let id = match current {
+                Some(id) => self.node(id).next,
+                None => self.head,
+            }
+            .expect("walked past the tail");
+
+            self.run(steps, ListStep::Visit(id));
+            current = Some(id);
+        }
+
+        current
+    }
+
+    /// Inserts `value` so that it ends up at `index`, or returns `None` if
+    /// `index` is past the end.
+    pub fn insert(&mut self, index: usize, value: i32) -> Option<Vec<ListStep>> {
+        if index > self.len {
+            return None;
+        }
+
+        if index == 0 {
+            return Some(self.push_front(value));
+        }
+
+        let mut steps = vec![];
+        let before = self.walk(index, &mut steps).unwrap();
+
+        if before == self.tail.unwrap() {
+            steps.extend(self.push_back(value));
+            return Some(steps);
+        }
+
+        let after = self.node(before).next;
+        let id = self.next_id();
+
+        self.run(&mut steps, ListStep::Alloc(id, value));
+        self.run(&mut steps, ListStep::SetNext(id, after));
+
+        if self.doubly {
+            self.run(&mut steps, ListStep::SetPrev(id, Some(before)));
+
+            if let Some(after) = after {
+                self.run(&mut steps, ListStep::SetPrev(after, Some(id)));
+            }
+        }
+
+        self.run(&mut steps, ListStep::SetNext(before, Some(id)));
+
+        Some(steps)
+    }
+
+    /// Removes the node at `index` by pointing its neighbors past it.
+    pub fn remove(&mut self, index: usize) -> (Option<i32>, Vec<ListStep>) {
+        if index >= self.len {
+            return (None, vec![]);
+        }
+
+        if index == 0 {
+            return self.pop_front();
+        }
+
+        let mut steps = vec![];
+        let before = self.walk(index, &mut steps).unwrap();
+        let target = self.node(before).next.unwrap();
+        let node = self.node(target).clone();
+
+        self.run(&mut steps, ListStep::Visit(target));
+        self.run(&mut steps, ListStep::SetNext(before, node.next));
+
+        match node.next {
+            Some(after) if self.doubly => {
+                self.run(&mut steps, ListStep::SetPrev(after, Some(before)))
+            }
+            Some(_) => {}
+            None => self.run(&mut steps, ListStep::SetTail(Some(before))),
+        }
+
+        self.run(&mut steps, ListStep::Free(target));
+
+        (Some(node.value), steps)
+    }
+
+    /// Turns every `next` pointer around while walking the list with the
+    /// cursors `prev`, `curr` and `next`, then swaps head and tail.
+    pub fn reverse(&mut self) -> Vec<ListStep> {
+        let mut steps = vec![];
+        let (head, tail) = (self.head, self.tail);
+        let mut prev = None;
+        let mut current = head;
+
+        while let Some(id) = current {
+            let next = self.node(id).next;
+
+            self.run(&mut steps, ListStep::Cursor("curr", Some(id)));
+            self.run(&mut steps, ListStep::Cursor("next", next));
+            self.run(&mut steps, ListStep::SetNext(id, prev));
+
+            if self.doubly {
+                self.run(&mut steps, ListStep::SetPrev(id, next));
+            }
+
+            self.run(&mut steps, ListStep::Cursor("prev", Some(id)));
+
+            prev = Some(id);
+            current = next;
+        }
+
+        self.run(&mut steps, ListStep::Cursor("curr", None));
+        self.run(&mut steps, ListStep::SetHead(tail));
+        self.run(&mut steps, ListStep::SetTail(head));
+
+        steps
+    }
+}
+
+/// A stack on a singly linked list, its top at the head.
+#[derive(Clone, Debug)]
+pub struct Stack {
+    list: LinkedList,
+}
+
+impl Default for Stack {
+    fn default() -> Stack {
+        Stack::new()
+    }
+}
+
+impl Stack {
+    pub fn new() -> Stack {
+        Stack {
+            list: LinkedList::new(false),
+        }
+    }
+
+    pub fn list(&self) -> &LinkedList {
+        &self.list
+    }
+
+    pub fn push(&mut self, value: i32) -> Vec<ListStep> {
+        self.list.push_front(value)
+    }
+
+    pub fn pop(&mut self) -> (Option<i32>, Vec<ListStep>) {
+        self.list.pop_front()
+    }
+
+    pub fn peek(&self) -> Option<i32> {
+        self.list.head().map(|head| self.list.node(head).value)
+    }
+}
+
+/// A queue on a singly linked list with a tail pointer, entering at the
+/// tail and leaving at the head.
+#[derive(Clone, Debug)]
+pub struct Queue {
+    list: LinkedList,
+}
+
+impl Default for Queue {
+    fn default() -> Queue {
+        Queue::new()
+    }
+}
+
+impl Queue {
+    pub fn new() -> Queue {
+        Queue {
+            list: LinkedList::new(false),
+        }
+    }
+
+    pub fn list(&self) -> &LinkedList {
+        &self.list
+    }
+
+    pub fn enqueue(&mut self, value: i32) -> Vec<ListStep> {
+        self.list.push_back(value)
+    }
+
+    pub fn dequeue(&mut self) -> (Option<i32>, Vec<ListStep>) {
+        self.list.pop_front()
+    }
+
+    pub fn peek(&self) -> Option<i32> {
+        self.list.head().map(|head| self.list.node(head).value)
+    }
+}
@@ -1 +1,16 @@
+pub mod b_tree;
+pub mod binary_heap;
+pub mod binary_search_tree;
+pub mod bipartite_graph;
+pub mod fenwick_tree;
+pub mod flow_network;
+pub mod general_graph;
 pub mod graph;
+pub mod graph_formats;
+pub mod graph_generators;
+pub mod hash_table;
+pub mod linked_list;
+pub mod segment_tree;
+pub mod skip_list;
+pub mod sorting_network;
+pub mod trie;
@@ -0,0 +1,294 @@
+/// One step of a segment tree operation. Operations return their steps so
+/// that visualizations can replay them node by node on a copy of the tree.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SegmentStep {
+    /// Went into the node, whose interval overlaps the range only partly.
+    Visit(usize),
+    /// The interval of the node lies inside the range: its sum is used
+    /// whole, or its update left pending for the children.
+    Take(usize),
+    /// The interval of the node lies outside the range.
+    Skip(usize),
+    /// Passed the pending addition of the node on to its children.
+    PushDown(usize),
+    /// Added the amount to every value under the node, the children's
+    /// share left pending.
+    Add(usize, i64),
+    /// Set the value of a leaf.
+    Set(usize, i64),
+    /// Recomputed the sum of the node from its children.
+    Pull(usize),
+}
+
+/// A segment tree of sums with lazy propagation of range additions. Node 1
+/// is the root over the whole array, and node `i` has the children `2i`
+/// and `2i + 1` splitting its interval in the middle.
+#[derive(Clone, Debug)]
+pub struct SegmentTree {
+    len: usize,
+    sums: Vec<i64>,
+    /// Added to the node's sum already but not yet to its children's.
+    pending: Vec<i64>,
+    intervals: Vec<Option<(usize, usize)>>,
+}
+
+pub const ROOT: usize = 1;
+
+impl SegmentTree {
+    pub fn new(values: &[i32]) -> SegmentTree {
+        let size = 4 * values.len().max(1);
+        let mut tree = SegmentTree {
+            len: values.len(),
+            sums: vec![0; size],
+            pending: vec![0; size],
+            intervals: vec![None; size],
+        };
+
+        if !values.is_empty() {
+            tree.build(ROOT, 0, values.len() - 1, values);
+        }
+
+        tree
+    }
+
+    fn build(&mut self, node: usize, lo: usize, hi: usize, values: &[i32]) {
+        self.intervals[node] = Some((lo, hi));
+
+        if lo == hi {
+            self.sums[node] = values[lo] as i64;
+            return;
+        }
+
+        let middle = (lo + hi) / 2;
+
+        self.build(2 * node, lo, middle, values);
+        self.build(2 * node + 1, middle + 1, hi, values);
+        self.sums[node] = self.sums[2 * node] + self.sums[2 * node + 1];
+    }
+
+    /// The length of the array.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of node indexes, used or not; every node is below it.
+    pub fn capacity(&self) -> usize {
+        self.sums.len()
+    }
+
+    /// The nodes in index order, which is level by level.
+    pub fn nodes(&self) -> Vec<usize> {
+        (0..self.capacity())
+            .filter(|&node| self.intervals[node].is_some())
+            .collect()
+    }
+
+    /// The first and last array index under `node`.
+    pub fn interval(&self, node: usize) -> (usize, usize) {
+        self.intervals[node].expect("no node at this index")
+    }
+
+    fn width(&self, node: usize) -> i64 {
+        let (lo, hi) = self.interval(node);
+
+        (hi - lo + 1) as i64
+    }
+
+    pub fn is_leaf(&self, node: usize) -> bool {
+        let (lo, hi) = self.interval(node);
+
+        lo == hi
+    }
+
+    pub fn sum(&self, node: usize) -> i64 {
+        self.sums[node]
+    }
+
+    pub fn pending(&self, node: usize) -> i64 {
+        self.pending[node]
+    }
+
+    pub fn depth(node: usize) -> usize {
+        (usize::BITS - 1 - node.leading_zeros()) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.nodes()
+            .into_iter()
+            .map(|node| SegmentTree::depth(node) + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The value at `index`, counting the additions still pending above
+    /// its leaf.
+    pub fn value(&self, index: usize) -> i64 {
+        let mut node = ROOT;
+        let mut value = 0;
+
+        while !self.is_leaf(node) {
+            value += self.pending[node];
+
+            let (lo, hi) = self.interval(node);
+
+            node = if index <= (lo + hi) / 2 {
+                2 * node
+            } else {
+                2 * node + 1
+            };
+        }
+
+        value + self.sums[node]
+    }
+
+    pub fn values(&self) -> Vec<i64> {
+        (0..self.len).map(|index| self.value(index)).collect()
+    }
+
+    pub fn apply(&mut self, step: SegmentStep) {
+        match step {
+            SegmentStep::Visit(_) | SegmentStep::Take(_) | SegmentStep::Skip(_) => {}
+            SegmentStep::PushDown(node) => {
+                let amount = self.pending[node];
+
+                for child in [2 * node, 2 * node + 1] {
+                    self.apply(SegmentStep::Add(child, amount));
+                }
+
+                self.pending[node] = 0;
+            }
+            SegmentStep::Add(node, amount) => {
+                self.sums[node] += amount * self.width(node);
+
+                if !self.is_leaf(node) {
+                    self.pending[node] += amount;
+                }
+            }
+            SegmentStep::Set(node, value) => self.sums[node] = value,
+            SegmentStep::Pull(node) => {
+                self.sums[node] = self.sums[2 * node] + self.sums[2 * node + 1]
+            }
+        }
+    }
+
+    fn run(&mut self, steps: &mut Vec<SegmentStep>, step: SegmentStep) {
+        self.apply(step);
+        steps.push(step);
+    }
+
+    /// Goes into `node`, first passing its pending addition on.
+    fn enter(&mut self, node: usize, steps: &mut Vec<SegmentStep>) {
+        self.run(steps, SegmentStep::Visit(node));
+
+        if self.pending[node] != 0 {
+            self.run(steps, SegmentStep::PushDown(node));
+        }
+    }
+
+    /// The sum of the values at `lo..=hi`.
+    pub fn query(&mut self, lo: usize, hi: usize) -> (i64, Vec<SegmentStep>) {
+        assert!(lo <= hi && hi < self.len, "range out of bounds");
+
+        let mut steps = vec![];
+        let sum = self.query_node(ROOT, lo, hi, &mut steps);
+
+        (sum, steps)
+    }
+
+    fn query_node(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        steps: &mut Vec<SegmentStep>,
+    ) -> i64 {
+        let (from, to) = self.interval(node);
+
+        if to < lo || hi < from {
+            self.run(steps, SegmentStep::Skip(node));
+            return 0;
+        }
+
+        if lo <= from && to <= hi {
+            self.run(steps, SegmentStep::Take(node));
+            return self.sums[node];
+        }
+
+        self.enter(node, steps);
+
+        self.query_node(2 * node, lo, hi, steps) + self.query_node(2 * node + 1, lo, hi, steps)
+    }
+
+    /// Sets the value at `index`, walking down to its leaf and updating
+    /// the sums on the way back up.
+    pub fn set(&mut self, index: usize, value: i64) -> Vec<SegmentStep> {
+        assert!(index < self.len, "index out of bounds");
+
+        let mut steps = vec![];
+        let mut node = ROOT;
+
+        while !self.is_leaf(node) {
+            self.enter(node, &mut steps);
+
+            let (lo, hi) = self.interval(node);
+
+            node = if index <= (lo + hi) / 2 {
+                2 * node
+            } else {
+                2 * node + 1
+            };
+        }
+
+        self.run(&mut steps, SegmentStep::Set(node, value));
+
+        while node > ROOT {
+            node /= 2;
+            self.run(&mut steps, SegmentStep::Pull(node));
+        }
+
+        steps
+    }
+
+    /// Adds `amount` to every value at `lo..=hi`, stopping at the nodes
+    /// that lie inside the range and leaving the rest pending there.
+    pub fn add(&mut self, lo: usize, hi: usize, amount: i64) -> Vec<SegmentStep> {
+        assert!(lo <= hi && hi < self.len, "range out of bounds");
+
+        let mut steps = vec![];
+
+        self.add_node(ROOT, lo, hi, amount, &mut steps);
+
+        steps
+    }
+
+    fn add_node(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        amount: i64,
+        steps: &mut Vec<SegmentStep>,
+    ) {
+        let (from, to) = self.interval(node);
+
+        if to < lo || hi < from {
+            self.run(steps, SegmentStep::Skip(node));
+            return;
+        }
+
+        if lo <= from && to <= hi {
+            self.run(steps, SegmentStep::Take(node));
+            self.run(steps, SegmentStep::Add(node, amount));
+            return;
+        }
+
+        self.enter(node, steps);
+        self.add_node(2 * node, lo, hi, amount, steps);
+        self.add_node(2 * node + 1, lo, hi, amount, steps);
+        self.run(steps, SegmentStep::Pull(node));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::rng::Rng;
+
+/// Lanes a node can reach; with promotion probability 1/2 this is plenty
+/// for any list that fits on a canvas.
+pub const MAX_LEVEL: usize = 16;
+/// The chance of a new node being promoted to every next lane up.
+pub const PROMOTION_PROBABILITY: f64 = 0.5;
+
+/// One step of a skip list operation. Operations return their steps so
+/// that visualizations can replay them link by link on a copy of the list.
+/// `None` stands for the head wherever a node is expected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SkipStep {
+    /// Moved along the lane `level` to the node, whose key is below the
+    /// one searched for.
+    Right {
+        level: usize,
+        to: usize,
+    },
+    /// The next key on the lane `level` is not below the one searched for,
+    /// or the lane ends: dropped from the node down to the lane below, or
+    /// stopped at the bottom lane.
+    Down {
+        at: Option<usize>,
+        level: usize,
+    },
+    /// The next node on the bottom lane holds the key.
+    Found(usize),
+    /// The coin came up heads: the new node reaches one more lane, up to
+    /// the given number of lanes.
+    Promote(usize),
+    /// The list now has this many lanes.
+    SetLevel(usize),
+    /// Allocated a node for the key, reaching the given number of lanes,
+    /// its pointers not linked yet.
+    Alloc(usize, i32, usize),
+    /// Pointed the pointer on lane `level` of `from` at `to`.
+    Link {
+        from: Option<usize>,
+        level: usize,
+        to: Option<usize>,
+    },
+    Free(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct SkipNode {
+    pub key: i32,
+    /// The next node on every lane the node reaches, from the bottom up.
+    pub next: Vec<Option<usize>>,
+}
+
+/// A skip list of distinct keys: a sorted linked list with express lanes
+/// above it, every node reaching each next lane up with probability 1/2,
+/// decided by a seeded generator so that lists can be rebuilt exactly.
+#[derive(Clone, Debug)]
+pub struct SkipList {
+    slots: Vec<Option<SkipNode>>,
+    free: Vec<usize>,
+    /// The first node on every lane.
+    head: Vec<Option<usize>>,
+    /// Lanes in use, at least the bottom one.
+    level: usize,
+    len: usize,
+    rng: Rng,
+}
+
+impl SkipList {
+    pub fn new(seed: u64) -> SkipList {
+        SkipList {
+            slots: vec![],
+            free: vec![],
+            head: vec![None; MAX_LEVEL],
+            level: 1,
+            len: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of lanes in use.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// The number of node slots, used or not; every node id is below it.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn node(&self, id: usize) -> &SkipNode {
+        self.slots[id].as_ref().expect("no node in this slot")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut SkipNode {
+        self.slots[id].as_mut().expect("no node in this slot")
+    }
+
+    /// The allocated nodes, whether linked in yet or not.
+    pub fn ids(&self) -> Vec<usize> {
+        (0..self.slots.len())
+            .filter(|&id| self.slots[id].is_some())
+            .collect()
+    }
+
+    /// The node after `at`, or after the head for `None`, on lane `level`.
+    pub fn next(&self, at: Option<usize>, level: usize) -> Option<usize> {
+        match at {
+            Some(id) => self.node(id).next[level],
+            None => self.head[level],
+        }
+    }
+
+    /// The keys in order, along the bottom lane.
+    pub fn keys(&self) -> Vec<i32> {
+        let mut keys = vec![];
+        let mut at = self.head[0];
+
+        while let Some(id) = at {
+            keys.push(self.node(id).key);
+            at = self.node(id).next[0];
+        }
+
+        keys
+    }
+
+    pub fn contains(&self, key: i32) -> bool {
+        self.search(key).0
+    }
+
+    /// Pugh's bound on the expected number of steps right and down a search
+    /// takes: `log_{1/p} n / p + 1 / (1 - p)` for `n` keys.
+    pub fn expected_search_length(&self) -> f64 {
+        let p = PROMOTION_PROBABILITY;
+        let lanes = (self.len.max(1) as f64).ln() / (1.0 / p).ln();
+
+        lanes / p + 1.0 / (1.0 - p)
+    }
+
+    pub fn apply(&mut self, step: SkipStep) {
+        match step {
+            SkipStep::Right { .. }
+            | SkipStep::Down { .. }
+            | SkipStep::Found(_)
+            | SkipStep::Promote(_) => {}
+            SkipStep::SetLevel(level) => self.level = level,
+            SkipStep::Alloc(id, key, height) => {
+                if id == self.slots.len() {
+                    self.slots.push(None);
+                } else {
+                    self.free.retain(|&free| free != id);
+                }
+
+                self.slots[id] = Some(SkipNode {
+                    key,
+                    next: vec![None; height],
+                });
+                self.len += 1;
+            }
+            SkipStep::Link { from, level, to } => match from {
+                Some(id) => self.node_mut(id).next[level] = to,
+                None => self.head[level] = to,
+            },
+            SkipStep::Free(id) => {
+                self.slots[id] = None;
+                self.free.push(id);
+                self.len -= 1;
+            }
+        }
+    }
+
+    fn run(&mut self, steps: &mut Vec<SkipStep>, step: SkipStep) {
+        self.apply(step);
+        steps.push(step);
+    }
+
+    /// Walks from the top lane down towards `key`, returning the last node
+    /// before it on every lane and the steps taken.
+    fn descend(&self, key: i32) -> (Vec<Option<usize>>, Vec<SkipStep>) {
+        let mut steps = vec![];
+        let mut before = vec![None; self.level];
+        let mut at = None;
+
+        for level in (0..self.level).rev() {
+            while let Some(next) = self
+                .next(at, level)
+                .filter(|&next| self.node(next).key < key)
+            {
+                steps.push(SkipStep::Right { level, to: next });
+                at = Some(next);
+            }
+
+            steps.push(SkipStep::Down { at, level });
+            before[level] = at;
+        }
+
+        (before, steps)
+    }
+
+    /// The node holding `key` right after `at` on the bottom lane, if any.
+    fn found(&self, at: Option<usize>, key: i32) -> Option<usize> {
+        self.next(at, 0).filter(|&next| self.node(next).key == key)
+    }
+
+    pub fn search(&self, key: i32) -> (bool, Vec<SkipStep>) {
+        let (before, mut steps) = self.descend(key);
+        let found = self.found(before[0], key);
+
+        steps.extend(found.map(SkipStep::Found));
+
+        (found.is_some(), steps)
+    }
+
+    /// Inserts `key` unless it is there already, flipping coins for the
+    /// lanes it reaches and linking it in after the last node before it on
+    /// each of them.
+    pub fn insert(&mut self, key: i32) -> (bool, Vec<SkipStep>) {
+        let (mut before, mut steps) = self.descend(key);
+
+        if let Some(id) = self.found(before[0], key) {
+            steps.push(SkipStep::Found(id));
+            return (false, steps);
+        }
+
+        let mut height = 1;
+
+        while height < MAX_LEVEL && self.rng.chance(PROMOTION_PROBABILITY) {
+            height += 1;
+            steps.push(SkipStep::Promote(height));
+        }
+
+        if height > self.level {
+            // The head is the last node before the key on the new lanes.
+            before.resize(height, None);
+            self.run(&mut steps, SkipStep::SetLevel(height));
+        }
+
+        let id = self.free.last().copied().unwrap_or(self.slots.len());
+
+        self.run(&mut steps, SkipStep::Alloc(id, key, height));
+
+        for (level, &from) in before.iter().enumerate().take(height) {
+            let to = self.next(from, level);
+
+            self.run(
+                &mut steps,
+                SkipStep::Link {
+                    from: Some(id),
+                    level,
+                    to,
+                },
+            );
+            self.run(
+                &mut steps,
+                SkipStep::Link {
+                    from,
+                    level,
+                    to: Some(id),
+                },
+            );
+        }
+
+        (true, steps)
+    }
+
+    /// Removes `key` if it is there, linking the last node before it on
+    /// every lane it reaches past it and dropping lanes left empty.
+    pub fn remove(&mut self, key: i32) -> (bool, Vec<SkipStep>) {
+        let (before, mut steps) = self.descend(key);
+        let Some(id) = self.found(before[0], key) else {
+            return (false, steps);
+        };
+
+        steps.push(SkipStep::Found(id));
+
+        for level in (0..self.node(id).next.len()).rev() {
+            let to = self.node(id).next[level];
+
+            self.run(
+                &mut steps,
+                SkipStep::Link {
+                    from: before[level],
+                    level,
+                    to,
+                },
+            );
+        }
+
+        self.run(&mut steps, SkipStep::Free(id));
+
+        let mut level = self.level;
+
+        while level > 1 && self.head[level - 1].is_none() {
+            level -= 1;
+        }
+
+        if level < self.level {
+            self.run(&mut steps, SkipStep::SetLevel(level));
+        }
+
+        (true, steps)
+    }
+}
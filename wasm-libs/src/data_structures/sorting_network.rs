@@ -0,0 +1,179 @@
+/// A comparator network on `wires` wires. Every comparator `(low, high)`
+/// leaves the smaller of the two values on wire `low`.
+#[derive(Clone, Debug)]
+pub struct SortingNetwork {
+    wires: usize,
+    comparators: Vec<(usize, usize)>,
+}
+
+impl SortingNetwork {
+    pub fn new(wires: usize) -> SortingNetwork {
+        SortingNetwork {
+            wires,
+            comparators: vec![],
+        }
+    }
+
+    pub fn add_comparator(&mut self, a: usize, b: usize) {
+        assert!(
+            a < self.wires && b < self.wires && a != b,
+            "comparator wires out of range"
+        );
+
+        self.comparators.push((a.min(b), a.max(b)));
+    }
+
+    /// Bitonic sort in the variant whose comparators all point the same
+    /// way: each merge starts by comparing mirrored pairs, then halves.
+    /// Built for the next power of two, dropping comparators on the extra
+    /// wires, which behave as if they held +∞.
+    pub fn bitonic(wires: usize) -> SortingNetwork {
+        let size = wires.next_power_of_two();
+        let mut network = SortingNetwork::new(wires);
+
+        let mut block = 2;
+
+        while block <= size {
+            for start in (0..size).step_by(block) {
+                for offset in 0..block / 2 {
+                    network.add_padded(start + offset, start + block - 1 - offset);
+                }
+            }
+
+            let mut half = block / 4;
+
+            while half >= 1 {
+                for start in (0..size).step_by(2 * half) {
+                    for offset in 0..half {
+                        network.add_padded(start + offset, start + offset + half);
+                    }
+                }
+
+                half /= 2;
+            }
+
+            block *= 2;
+        }
+
+        network
+    }
+
+    /// Batcher's odd-even merge sort, padded like `bitonic`.
+    pub fn odd_even_merge(wires: usize) -> SortingNetwork {
+        let size = wires.next_power_of_two();
+        let mut network = SortingNetwork::new(wires);
+
+        let mut p = 1;
+
+        while p < size {
+            let mut k = p;
+
+            while k >= 1 {
+                let mut j = k % p;
+
+                while j + k < size {
+                    for i in 0..k.min(size - j - k) {
+                        if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                            network.add_padded(i + j, i + j + k);
+                        }
+                    }
+
+                    j += 2 * k;
+                }
+
+                k /= 2;
+            }
+
+            p *= 2;
+        }
+
+        network
+    }
+
+    fn add_padded(&mut self, a: usize, b: usize) {
+        if a < self.wires && b < self.wires {
+            self.add_comparator(a, b);
+        }
+    }
+
+    pub fn wires(&self) -> usize {
+        self.wires
+    }
+
+    pub fn comparators(&self) -> &[(usize, usize)] {
+        &self.comparators
+    }
+
+    /// Comparator indexes grouped into layers that can run in parallel,
+    /// each comparator placed in the earliest layer after the previous
+    /// comparators on its wires.
+    pub fn layers(&self) -> Vec<Vec<usize>> {
+        let mut next_free = vec![0; self.wires];
+        let mut layers: Vec<Vec<usize>> = vec![];
+
+        for (idx, &(low, high)) in self.comparators.iter().enumerate() {
+            let layer = next_free[low].max(next_free[high]);
+
+            if layer == layers.len() {
+                layers.push(vec![]);
+            }
+
+            layers[layer].push(idx);
+            next_free[low] = layer + 1;
+            next_free[high] = layer + 1;
+        }
+
+        layers
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers().len()
+    }
+
+    /// Runs comparator `idx` on `values`, returning whether it swapped.
+    pub fn apply(&self, idx: usize, values: &mut [u32]) -> bool {
+        let (low, high) = self.comparators[idx];
+
+        if values[low] > values[high] {
+            values.swap(low, high);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The 0-1 principle: a network sorts every input if it sorts every
+    /// input of zeros and ones. Tries all `2^wires` of them, bit `i` of a
+    /// mask being the value on wire `i`, and returns the first one left
+    /// unsorted.
+    pub fn zero_one_counterexample(&self) -> Option<Vec<u32>> {
+        let all = if self.wires == 0 {
+            0
+        } else {
+            u64::MAX >> (64 - self.wires)
+        };
+
+        for input in 0..=all {
+            let mut mask = input;
+
+            for &(low, high) in self.comparators.iter() {
+                if (mask >> low) & 1 == 1 && (mask >> high) & 1 == 0 {
+                    mask ^= (1 << low) | (1 << high);
+                }
+            }
+
+            // Sorted means all zeros on the low wires, then all ones.
+            let zeros = self.wires - mask.count_ones() as usize;
+
+            if mask != all & !((1u64 << zeros) - 1) {
+                return Some(
+                    (0..self.wires)
+                        .map(|wire| ((input >> wire) & 1) as u32)
+                        .collect(),
+                );
+            }
+        }
+
+        None
+    }
+}
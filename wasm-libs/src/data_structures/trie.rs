@@ -0,0 +1,423 @@
+#[derive(Clone, Debug)]
+pub struct TrieNode {
+    /// The characters on the edge from the parent: exactly one in a trie,
+    /// one or more in a radix tree. Empty for the root.
+    pub label: String,
+    /// Sorted by label, so that walking them in order yields sorted words.
+    pub children: Vec<usize>,
+    pub parent: Option<usize>,
+    /// Whether a word ends here.
+    pub terminal: bool,
+}
+
+/// How far a word got walking down from the root.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Descent {
+    /// The nodes whose edges were followed, root first. The last one may
+    /// match only partly.
+    pub path: Vec<usize>,
+    /// Bytes of the word matched by the edges of `path`.
+    pub matched: usize,
+    /// Whether the word stopped in the middle of the last edge.
+    pub inside_edge: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Insertion {
+    pub inserted: bool,
+    /// New nodes, parents before children.
+    pub created: Vec<usize>,
+    /// The node splitting an edge that the word left in the middle, which
+    /// only happens in a radix tree.
+    pub split: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Removal {
+    /// Nodes freed because no word went through them any more, deepest
+    /// first.
+    pub pruned: Vec<usize>,
+    /// In a radix tree, a node left with one child and no word of its own
+    /// absorbs that child: `(node, child)`.
+    pub merged: Option<(usize, usize)>,
+}
+
+/// A trie over strings, or with `compressed` set a radix tree, where chains
+/// of nodes without words or branches are merged into one edge. Nodes live
+/// in slots that keep their index while the node exists; the root is
+/// always slot 0.
+#[derive(Clone, Debug)]
+pub struct Trie {
+    compressed: bool,
+    slots: Vec<Option<TrieNode>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+pub const ROOT: usize = 0;
+
+/// Bytes at the start of `a` and `b` that are the same characters.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
+impl Trie {
+    pub fn new(compressed: bool) -> Trie {
+        Trie {
+            compressed,
+            slots: vec![Some(TrieNode {
+                label: String::new(),
+                children: vec![],
+                parent: None,
+                terminal: false,
+            })],
+            free: vec![],
+            len: 0,
+        }
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// The number of words.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of slots, occupied or not; every node index is below it.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn node(&self, id: usize) -> &TrieNode {
+        self.slots[id].as_ref().expect("no node in this slot")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut TrieNode {
+        self.slots[id].as_mut().expect("no node in this slot")
+    }
+
+    pub fn depth(&self, id: usize) -> usize {
+        let mut depth = 0;
+        let mut current = id;
+
+        while let Some(parent) = self.node(current).parent {
+            depth += 1;
+            current = parent;
+        }
+
+        depth
+    }
+
+    /// The string spelled by the edges from the root down to `id`.
+    pub fn prefix(&self, id: usize) -> String {
+        let mut labels = vec![];
+        let mut current = Some(id);
+
+        while let Some(id) = current {
+            labels.push(self.node(id).label.as_str());
+            current = self.node(id).parent;
+        }
+
+        labels.into_iter().rev().collect()
+    }
+
+    fn child_starting_with(&self, id: usize, first: char) -> Option<usize> {
+        self.node(id)
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.node(child).label.starts_with(first))
+    }
+
+    /// Follows `word` down from the root as far as the edges match it.
+    pub fn descend(&self, word: &str) -> Descent {
+        let mut descent = Descent {
+            path: vec![ROOT],
+            matched: 0,
+            inside_edge: false,
+        };
+        let mut current = ROOT;
+
+        while let Some(first) = word[descent.matched..].chars().next() {
+            let Some(child) = self.child_starting_with(current, first) else {
+                break;
+            };
+
+            let label = &self.node(child).label;
+            let common = common_prefix(label, &word[descent.matched..]);
+
+            descent.path.push(child);
+            descent.matched += common;
+
+            if common < label.len() {
+                descent.inside_edge = true;
+                break;
+            }
+
+            current = child;
+        }
+
+        descent
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let descent = self.descend(word);
+
+        descent.matched == word.len()
+            && !descent.inside_edge
+            && self.node(*descent.path.last().unwrap()).terminal
+    }
+
+    fn alloc(&mut self, parent: usize, label: String) -> usize {
+        let node = TrieNode {
+            label,
+            children: vec![],
+            parent: Some(parent),
+            terminal: false,
+        };
+
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.slots[id] = Some(node);
+                id
+            }
+            None => {
+                self.slots.push(Some(node));
+                self.slots.len() - 1
+            }
+        };
+
+        self.attach(parent, id);
+
+        id
+    }
+
+    /// Adds `child` to the children of `parent`, keeping them sorted.
+    fn attach(&mut self, parent: usize, child: usize) {
+        let label = self.node(child).label.clone();
+        let position = self
+            .node(parent)
+            .children
+            .iter()
+            .position(|&other| self.node(other).label > label)
+            .unwrap_or(self.node(parent).children.len());
+
+        self.node_mut(parent).children.insert(position, child);
+        self.node_mut(child).parent = Some(parent);
+    }
+
+    fn detach(&mut self, parent: usize, child: usize) {
+        self.node_mut(parent)
+            .children
+            .retain(|&other| other != child);
+    }
+
+    pub fn insert(&mut self, word: &str) -> Insertion {
+        let mut insertion = Insertion {
+            inserted: false,
+            created: vec![],
+            split: None,
+        };
+        let mut current = ROOT;
+        let mut rest = word;
+
+        while let Some(first) = rest.chars().next() {
+            let Some(child) = self.child_starting_with(current, first) else {
+                // Hang the rest of the word off `current`, as one edge or
+                // one node per character.
+                if self.compressed {
+                    current = self.alloc(current, rest.to_string());
+                    insertion.created.push(current);
+                } else {
+                    for c in rest.chars() {
+                        current = self.alloc(current, c.to_string());
+                        insertion.created.push(current);
+                    }
+                }
+
+                break;
+            };
+
+            let label = self.node(child).label.clone();
+            let common = common_prefix(&label, rest);
+
+            if common < label.len() {
+                let middle = self.alloc(current, label[..common].to_string());
+
+                self.detach(current, child);
+                self.node_mut(child).label = label[common..].to_string();
+                self.attach(middle, child);
+
+                insertion.created.push(middle);
+                insertion.split = Some(middle);
+                current = middle;
+            } else {
+                current = child;
+            }
+
+            rest = &rest[common..];
+        }
+
+        if !self.node(current).terminal {
+            self.node_mut(current).terminal = true;
+            self.len += 1;
+            insertion.inserted = true;
+        }
+
+        insertion
+    }
+
+    /// Removes `word`, freeing the nodes only it went through and, in a
+    /// radix tree, merging the edges that no longer branch.
+    pub fn remove(&mut self, word: &str) -> Option<Removal> {
+        if !self.contains(word) {
+            return None;
+        }
+
+        let mut removal = Removal {
+            pruned: vec![],
+            merged: None,
+        };
+        let mut current = *self.descend(word).path.last().unwrap();
+
+        self.node_mut(current).terminal = false;
+        self.len -= 1;
+
+        while current != ROOT
+            && !self.node(current).terminal
+            && self.node(current).children.is_empty()
+        {
+            let parent = self.node(current).parent.unwrap();
+
+            self.detach(parent, current);
+            self.slots[current] = None;
+            self.free.push(current);
+            removal.pruned.push(current);
+
+            current = parent;
+        }
+
+        if self.compressed && self.is_mergeable(current) {
+            let child = self.merge(current);
+
+            removal.merged = Some((current, child));
+        }
+
+        Some(removal)
+    }
+
+    /// Whether `id` is a node a radix tree would not have: neither the root
+    /// nor the end of a word, with exactly one child.
+    pub fn is_mergeable(&self, id: usize) -> bool {
+        let node = self.node(id);
+
+        id != ROOT && !node.terminal && node.children.len() == 1
+    }
+
+    /// The first mergeable node in preorder.
+    pub fn next_mergeable(&self) -> Option<usize> {
+        self.preorder(ROOT)
+            .into_iter()
+            .find(|&id| self.is_mergeable(id))
+    }
+
+    /// Appends the label of the only child of `id` to its own and takes
+    /// over the child's word and children. Returns the freed child.
+    pub fn merge(&mut self, id: usize) -> usize {
+        assert!(self.is_mergeable(id), "node {} cannot be merged", id);
+
+        let child = self.node(id).children[0];
+        let absorbed = self.slots[child].take().unwrap();
+
+        self.free.push(child);
+
+        let node = self.node_mut(id);
+
+        node.label += &absorbed.label;
+        node.terminal = absorbed.terminal;
+        node.children = absorbed.children;
+
+        for grandchild in self.node(id).children.clone() {
+            self.node_mut(grandchild).parent = Some(id);
+        }
+
+        child
+    }
+
+    /// Turns a trie into a radix tree by merging every mergeable node.
+    /// Returns how many merges that took.
+    pub fn compress(&mut self) -> usize {
+        let mut merges = 0;
+
+        while let Some(id) = self.next_mergeable() {
+            self.merge(id);
+            merges += 1;
+        }
+
+        self.compressed = true;
+
+        merges
+    }
+
+    /// The subtree of `id`, parents before children and siblings in order.
+    pub fn preorder(&self, id: usize) -> Vec<usize> {
+        let mut order = vec![];
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            stack.extend(self.node(id).children.iter().rev());
+        }
+
+        order
+    }
+
+    /// The words ending in the subtree of `id`, in sorted order.
+    pub fn words_below(&self, id: usize) -> Vec<String> {
+        self.preorder(id)
+            .into_iter()
+            .filter(|&id| self.node(id).terminal)
+            .map(|id| self.prefix(id))
+            .collect()
+    }
+
+    pub fn words(&self) -> Vec<String> {
+        self.words_below(ROOT)
+    }
+
+    /// The descent of `prefix` and, if it matched completely, the node
+    /// whose subtree holds every word starting with it.
+    pub fn prefix_node(&self, prefix: &str) -> (Descent, Option<usize>) {
+        let descent = self.descend(prefix);
+
+        let subtree = if descent.matched == prefix.len() {
+            descent.path.last().copied()
+        } else {
+            None
+        };
+
+        (descent, subtree)
+    }
+
+    /// The words starting with `prefix`, in sorted order.
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        match self.prefix_node(prefix) {
+            (_, Some(id)) => self.words_below(id),
+            (_, None) => vec![],
+        }
+    }
+}
@@ -1,6 +1,9 @@
 pub mod action;
+mod animation;
 pub mod canvas;
 pub mod data_structures;
+mod renderers;
+mod rng;
 mod shapes;
 pub mod use_cases;
 mod utils;
@@ -0,0 +1,11 @@
+pub mod b_tree;
+pub mod bars;
+pub mod fenwick_tree;
+pub mod graph;
+pub mod hash_table;
+pub mod heap;
+pub mod list;
+pub mod matrix;
+pub mod network;
+pub mod skip_list;
+pub mod tree;
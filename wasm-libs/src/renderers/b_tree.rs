@@ -0,0 +1,231 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    animation::Tween,
+    canvas::{self, Coordinate, Drawable},
+    data_structures::b_tree::BTree,
+    renderers::graph::{draw_notes, EDGE_COLOR, NODE_BORDER_COLOR, NODE_COLOR, TEXT_COLOR},
+    shapes::{line::Line, segmented_box::SegmentedBox},
+};
+
+/// Room left above the tree for the notes.
+const NOTES_HEIGHT: f64 = 64.0;
+const MARGIN: f64 = 24.0;
+/// Levels are spread over the height of the canvas up to this spacing.
+const MAX_LEVEL_SPACING: f64 = 80.0;
+const MAX_KEY_WIDTH: f64 = 36.0;
+const BOX_HEIGHT: f64 = 26.0;
+/// Space between neighbouring leaves, in key widths.
+const LEAF_GAP: f64 = 1.0;
+const EDGE_WIDTH: f64 = 1.5;
+const HIGHLIGHTED_EDGE_WIDTH: f64 = 3.5;
+
+/// A node of a `BTreeView`, centered on a grid of key-wide columns and
+/// depth rows that is scaled to the canvas when drawn.
+#[derive(Clone, Debug)]
+pub struct BTreeNodeView {
+    pub keys: Vec<String>,
+    pub key_colors: Vec<&'static str>,
+    pub column: f64,
+    pub row: f64,
+    pub parent: Option<usize>,
+    /// Position among the children of the parent, which decides the border
+    /// between its keys the edge leaves from.
+    pub child_index: usize,
+    /// Color of the edge up to the parent.
+    pub edge_color: &'static str,
+}
+
+/// One frame of a B-tree visualization, every node drawn as a box with a
+/// segment per key. Nodes are indexed by their slot in the tree, `None`
+/// marking empty slots.
+#[derive(Clone, Debug)]
+pub struct BTreeView {
+    pub nodes: Vec<Option<BTreeNodeView>>,
+    /// Size of the grid the nodes are placed on.
+    pub columns: f64,
+    pub rows: f64,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl BTreeView {
+    /// Lays `tree` out with its leaves side by side in key order and every
+    /// other node centered over its children.
+    pub fn new(tree: &BTree) -> BTreeView {
+        let mut view = BTreeView {
+            nodes: vec![None; tree.capacity()],
+            columns: 0.0,
+            rows: tree.height() as f64,
+            notes: vec![],
+        };
+        let mut cursor = 0.0;
+
+        view.place(tree, tree.root(), None, 0, 0, &mut cursor);
+        view.columns = (cursor - LEAF_GAP).max(1.0);
+
+        view
+    }
+
+    /// Places `id` and the nodes below it, leaves from `cursor` on,
+    /// returning its column.
+    fn place(
+        &mut self,
+        tree: &BTree,
+        id: usize,
+        parent: Option<usize>,
+        child_index: usize,
+        depth: usize,
+        cursor: &mut f64,
+    ) -> f64 {
+        let node = tree.node(id);
+        let column = if node.is_leaf() {
+            let width = node.keys.len().max(1) as f64;
+            let column = *cursor + width / 2.0;
+
+            *cursor += width + LEAF_GAP;
+            column
+        } else {
+            let columns: Vec<f64> = node
+                .children
+                .iter()
+                .enumerate()
+                .map(|(index, &child)| self.place(tree, child, Some(id), index, depth + 1, cursor))
+                .collect();
+
+            (columns[0] + columns[columns.len() - 1]) / 2.0
+        };
+
+        self.nodes[id] = Some(BTreeNodeView {
+            keys: node.keys.iter().map(|key| key.to_string()).collect(),
+            key_colors: vec![NODE_COLOR; node.keys.len()],
+            column,
+            row: depth as f64,
+            parent,
+            child_index,
+            edge_color: EDGE_COLOR,
+        });
+
+        column
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> &mut BTreeNodeView {
+        self.nodes[id].as_mut().expect("no node in this slot")
+    }
+
+    /// Colors every key of `id`.
+    pub fn mark(&mut self, id: usize, color: &'static str) {
+        for key_color in &mut self.node_mut(id).key_colors {
+            *key_color = color;
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let key_width =
+                ((canvas.width() as f64 - 2.0 * MARGIN) / self.columns).min(MAX_KEY_WIDTH);
+            let row_height = ((canvas.height() as f64 - NOTES_HEIGHT - MARGIN)
+                / self.rows.max(1.0))
+            .min(MAX_LEVEL_SPACING);
+
+            let boxes: Vec<Option<SegmentedBox>> = self
+                .nodes
+                .iter()
+                .map(|node| {
+                    let node = node.as_ref()?;
+
+                    Some(SegmentedBox::new(
+                        Coordinate::new(
+                            MARGIN + node.column * key_width,
+                            NOTES_HEIGHT + (node.row + 0.5) * row_height,
+                        ),
+                        node.keys
+                            .iter()
+                            .zip(node.key_colors.iter())
+                            .map(|(key, color)| (key.clone(), color.to_string()))
+                            .collect(),
+                        key_width,
+                        BOX_HEIGHT,
+                        NODE_BORDER_COLOR.to_string(),
+                        TEXT_COLOR.to_string(),
+                    ))
+                })
+                .collect();
+
+            for (node, child) in self.nodes.iter().zip(boxes.iter()) {
+                let (Some(node), Some(child)) = (node, child) else {
+                    continue;
+                };
+                let Some(Some(parent)) = node.parent.map(|parent| &boxes[parent]) else {
+                    continue;
+                };
+
+                Line::new(
+                    parent.anchor(node.child_index),
+                    child.top(),
+                    node.edge_color.to_string(),
+                    if node.edge_color == EDGE_COLOR {
+                        EDGE_WIDTH
+                    } else {
+                        HIGHLIGHTED_EDGE_WIDTH
+                    },
+                )
+                .draw(ctx);
+            }
+
+            for segmented_box in boxes.iter().flatten() {
+                segmented_box.draw(ctx);
+            }
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+}
+
+impl Tween for BTreeView {
+    /// The view `t` of the way from `self` to `to`, with every node in
+    /// both views moved along a straight line. Everything else, and nodes
+    /// in only one of them, are taken from `to`.
+    fn interpolate(&self, to: &BTreeView, t: f64) -> BTreeView {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        let nodes = to
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, node)| {
+                let mut node = node.clone()?;
+
+                if let Some(Some(from)) = self.nodes.get(id) {
+                    node.column = lerp(from.column, node.column);
+                    node.row = lerp(from.row, node.row);
+                }
+
+                Some(node)
+            })
+            .collect();
+
+        BTreeView {
+            nodes,
+            columns: lerp(self.columns, to.columns),
+            rows: lerp(self.rows, to.rows),
+            notes: to.notes.clone(),
+        }
+    }
+
+    /// Whether any node sits somewhere else in `other`.
+    fn moves_to(&self, other: &BTreeView) -> bool {
+        self.columns != other.columns
+            || self.rows != other.rows
+            || self
+                .nodes
+                .iter()
+                .zip(other.nodes.iter())
+                .any(|pair| match pair {
+                    (Some(a), Some(b)) => a.column != b.column || a.row != b.row,
+                    _ => false,
+                })
+    }
+}
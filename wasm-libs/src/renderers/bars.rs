@@ -0,0 +1,195 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Coordinate, Drawable},
+    renderers::graph::{draw_notes, TEXT_COLOR},
+    shapes::text::Text,
+};
+
+pub const BAR_COLOR: &str = "#9DBC98";
+pub const COMPARE_COLOR: &str = "#FFA500";
+pub const SWAP_COLOR: &str = "#FF0000";
+pub const PIVOT_COLOR: &str = "#C3ACD0";
+pub const SORTED_COLOR: &str = "#86A7FC";
+
+/// Room left above the bars for the notes.
+const NOTES_HEIGHT: f64 = 64.0;
+const BAR_GAP: f64 = 1.0;
+/// Share of the height below the notes kept for the main array when
+/// auxiliary lanes are drawn.
+const MAIN_SHARE: f64 = 0.5;
+const LANE_LABEL_HEIGHT: f64 = 18.0;
+const LANE_CAPTION_HEIGHT: f64 = 16.0;
+const BUCKET_PADDING: f64 = 3.0;
+const BUCKET_BORDER_COLOR: &str = "#999999";
+const BUCKET_HIGHLIGHT_COLOR: &str = "#FFE08A";
+
+/// A rectangle of the canvas to draw into.
+struct Area {
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+}
+
+/// An array drawn as vertical bars standing on the bottom of the canvas,
+/// one frame of a sorting visualization. Auxiliary arrays, such as the
+/// buckets of a distribution sort, are drawn as lanes beneath it.
+#[derive(Clone, Debug)]
+pub struct BarsView {
+    pub values: Vec<u32>,
+    pub colors: Vec<&'static str>,
+    pub auxiliary: Vec<BucketsView>,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+/// A row of buckets, each holding values drawn as small bars at the scale
+/// of the main array, with a caption under every bucket.
+#[derive(Clone, Debug)]
+pub struct BucketsView {
+    pub label: String,
+    pub buckets: Vec<Vec<u32>>,
+    pub captions: Vec<String>,
+    pub highlighted: Option<usize>,
+}
+
+impl BucketsView {
+    pub fn new(label: &str, count: usize) -> BucketsView {
+        BucketsView {
+            label: label.to_string(),
+            buckets: vec![vec![]; count],
+            captions: vec![String::new(); count],
+            highlighted: None,
+        }
+    }
+
+    fn draw(&self, ctx: &CanvasRenderingContext2d, area: &Area, max: f64) {
+        Text::left_aligned(
+            self.label.clone(),
+            Coordinate::new(area.left + 10.0, area.top + LANE_LABEL_HEIGHT / 2.0),
+            TEXT_COLOR.to_string(),
+        )
+        .draw(ctx);
+
+        let slot_width = area.width / self.buckets.len().max(1) as f64;
+        let bucket_top = area.top + LANE_LABEL_HEIGHT;
+        let bucket_height = (area.height - LANE_LABEL_HEIGHT - LANE_CAPTION_HEIGHT).max(0.0);
+
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            let left = area.left + idx as f64 * slot_width + BUCKET_PADDING;
+            let inner_width = (slot_width - 2.0 * BUCKET_PADDING).max(1.0);
+
+            if self.highlighted == Some(idx) {
+                ctx.set_fill_style(&BUCKET_HIGHLIGHT_COLOR.into());
+                ctx.fill_rect(left, bucket_top, inner_width, bucket_height);
+            }
+
+            ctx.set_stroke_style(&BUCKET_BORDER_COLOR.into());
+            ctx.stroke_rect(left, bucket_top, inner_width, bucket_height);
+
+            draw_bars(
+                ctx,
+                bucket,
+                &vec![BAR_COLOR; bucket.len()],
+                &Area {
+                    left,
+                    top: bucket_top,
+                    width: inner_width,
+                    height: bucket_height,
+                },
+                max,
+            );
+
+            Text::centered(
+                self.captions[idx].clone(),
+                Coordinate::new(
+                    left + inner_width / 2.0,
+                    bucket_top + bucket_height + LANE_CAPTION_HEIGHT / 2.0,
+                ),
+                TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+        }
+    }
+}
+
+impl BarsView {
+    pub fn new(values: &[u32]) -> BarsView {
+        BarsView {
+            values: values.to_vec(),
+            colors: vec![BAR_COLOR; values.len()],
+            auxiliary: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+            let max = self.values.iter().copied().max().unwrap_or(0).max(1) as f64;
+            let main_height = if self.auxiliary.is_empty() {
+                height - NOTES_HEIGHT
+            } else {
+                (height - NOTES_HEIGHT) * MAIN_SHARE
+            };
+
+            draw_bars(
+                ctx,
+                &self.values,
+                &self.colors,
+                &Area {
+                    left: 0.0,
+                    top: NOTES_HEIGHT,
+                    width,
+                    height: main_height,
+                },
+                max,
+            );
+
+            let lane_height =
+                (height - NOTES_HEIGHT - main_height) / self.auxiliary.len().max(1) as f64;
+
+            for (lane, buckets) in self.auxiliary.iter().enumerate() {
+                buckets.draw(
+                    ctx,
+                    &Area {
+                        left: 0.0,
+                        top: NOTES_HEIGHT + main_height + lane as f64 * lane_height,
+                        width,
+                        height: lane_height,
+                    },
+                    max,
+                );
+            }
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+}
+
+/// Bars for `values` side by side, standing on the bottom of `area`, with
+/// `max` reaching its full height.
+fn draw_bars(
+    ctx: &CanvasRenderingContext2d,
+    values: &[u32],
+    colors: &[&'static str],
+    area: &Area,
+    max: f64,
+) {
+    let bar_width = area.width / values.len().max(1) as f64;
+
+    for (idx, &value) in values.iter().enumerate() {
+        let bar_height = area.height * value as f64 / max;
+
+        ctx.set_fill_style(&colors[idx].into());
+        ctx.fill_rect(
+            area.left + idx as f64 * bar_width,
+            area.top + area.height - bar_height,
+            (bar_width - BAR_GAP).max(1.0),
+            bar_height,
+        );
+    }
+}
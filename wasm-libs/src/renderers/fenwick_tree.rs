@@ -0,0 +1,172 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Coordinate, Drawable},
+    data_structures::fenwick_tree::lowbit,
+    renderers::graph::{draw_notes, EDGE_COLOR, NODE_BORDER_COLOR, TEXT_COLOR},
+    shapes::{line::Line, text::Text},
+};
+
+/// Room left above the strips for the notes.
+const NOTES_HEIGHT: f64 = 64.0;
+const MARGIN: f64 = 24.0;
+/// Room left of the strips for their names.
+const CAPTION_WIDTH: f64 = 40.0;
+const MAX_CELL_WIDTH: f64 = 56.0;
+const CELL_HEIGHT: f64 = 30.0;
+const INDEX_CAPTION_HEIGHT: f64 = 16.0;
+/// Space between the two strips, where the jumps arc.
+const JUMP_HEIGHT: f64 = 72.0;
+const BAR_HEIGHT: f64 = 6.0;
+const BAR_SPACING: f64 = 12.0;
+
+/// One frame of a Fenwick tree visualization: the array, and below it the
+/// tree entries with a bar under each spanning the values it sums. Arcs
+/// between the entries show the `lowbit` jumps of the operation.
+#[derive(Clone, Debug)]
+pub struct FenwickView {
+    pub values: Vec<i64>,
+    /// Entries 1 to n.
+    pub entries: Vec<i64>,
+    pub value_colors: Vec<&'static str>,
+    pub entry_colors: Vec<&'static str>,
+    /// Jumps from one entry to another, numbered from 1.
+    pub jumps: Vec<(usize, usize)>,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl FenwickView {
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let left = MARGIN + CAPTION_WIDTH;
+            let cell_width = ((canvas.width() as f64 - left - MARGIN)
+                / self.entries.len().max(1) as f64)
+                .min(MAX_CELL_WIDTH);
+            let values_top = NOTES_HEIGHT;
+            let entries_top = values_top + CELL_HEIGHT + INDEX_CAPTION_HEIGHT + JUMP_HEIGHT;
+
+            // Value `k` sits above entry `k + 1`, the entry ending with it.
+            let center = |entry: usize| left + (entry as f64 - 0.5) * cell_width;
+
+            draw_strip(
+                ctx,
+                "a",
+                &self.values,
+                &self.value_colors,
+                0,
+                values_top,
+                cell_width,
+            );
+            draw_strip(
+                ctx,
+                "tree",
+                &self.entries,
+                &self.entry_colors,
+                1,
+                entries_top,
+                cell_width,
+            );
+
+            // Bars spanning the values each entry sums, one row per lowbit.
+            for entry in 1..=self.entries.len() {
+                let low = lowbit(entry);
+                let row = low.trailing_zeros() as f64;
+                let y = entries_top + CELL_HEIGHT + INDEX_CAPTION_HEIGHT + row * BAR_SPACING;
+
+                ctx.set_fill_style(&self.entry_colors[entry - 1].into());
+                ctx.fill_rect(
+                    center(entry + 1 - low) - cell_width / 2.0 + 2.0,
+                    y,
+                    low as f64 * cell_width - 4.0,
+                    BAR_HEIGHT,
+                );
+                ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+                ctx.stroke_rect(
+                    center(entry + 1 - low) - cell_width / 2.0 + 2.0,
+                    y,
+                    low as f64 * cell_width - 4.0,
+                    BAR_HEIGHT,
+                );
+            }
+
+            for &(from, to) in &self.jumps {
+                draw_jump(ctx, center(from), center(to), entries_top);
+            }
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+}
+
+/// A row of cells named `name`, captioned with indexes from `first`.
+fn draw_strip(
+    ctx: &CanvasRenderingContext2d,
+    name: &str,
+    cells: &[i64],
+    colors: &[&str],
+    first: usize,
+    top: f64,
+    cell_width: f64,
+) {
+    let left = MARGIN + CAPTION_WIDTH;
+
+    Text::left_aligned(
+        name.to_string(),
+        Coordinate::new(MARGIN, top + CELL_HEIGHT / 2.0),
+        TEXT_COLOR.to_string(),
+    )
+    .draw(ctx);
+
+    for (idx, cell) in cells.iter().enumerate() {
+        let x = left + idx as f64 * cell_width;
+
+        ctx.set_fill_style(&colors[idx].into());
+        ctx.fill_rect(x, top, cell_width, CELL_HEIGHT);
+        ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+        ctx.stroke_rect(x, top, cell_width, CELL_HEIGHT);
+
+        Text::centered(
+            cell.to_string(),
+            Coordinate::new(x + cell_width / 2.0, top + CELL_HEIGHT / 2.0),
+            TEXT_COLOR.to_string(),
+        )
+        .draw(ctx);
+        Text::centered(
+            (idx + first).to_string(),
+            Coordinate::new(
+                x + cell_width / 2.0,
+                top + CELL_HEIGHT + INDEX_CAPTION_HEIGHT / 2.0,
+            ),
+            EDGE_COLOR.to_string(),
+        )
+        .draw(ctx);
+    }
+}
+
+/// An arc over the strip starting at `top` from `from` to `to`, higher the
+/// longer the jump, ending in an arrow head.
+fn draw_jump(ctx: &CanvasRenderingContext2d, from: f64, to: f64, top: f64) {
+    let y = top - 2.0;
+    let lift = ((to - from).abs() / 2.0).min(JUMP_HEIGHT - 8.0);
+    let middle = (from + to) / 2.0;
+
+    ctx.set_stroke_style(&TEXT_COLOR.into());
+    ctx.set_line_width(1.5);
+    ctx.begin_path();
+    ctx.move_to(from, y);
+    ctx.quadratic_curve_to(middle, y - 2.0 * lift, to, y);
+    ctx.stroke();
+    ctx.set_line_width(1.0);
+
+    // The arrow head sits on a short chord from near the end of the curve.
+    let t = 0.9;
+    let before = Coordinate::new(
+        (1.0 - t) * (1.0 - t) * from + 2.0 * (1.0 - t) * t * middle + t * t * to,
+        y - 2.0 * (1.0 - t) * t * 2.0 * lift,
+    );
+
+    Line::arrow(before, Coordinate::new(to, y), TEXT_COLOR.to_string(), 1.5).draw(ctx);
+}
@@ -0,0 +1,224 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Boundary, Coordinate, Drawable},
+    data_structures::general_graph::GeneralGraph,
+    shapes::{circle::Circle, line::Line, text::Text},
+};
+
+pub const NODE_RADIUS: f64 = 16.0;
+pub const NODE_COLOR: &str = "#FFFFFF";
+pub const NODE_BORDER_COLOR: &str = "#000000";
+pub const EDGE_COLOR: &str = "#999999";
+pub const TEXT_COLOR: &str = "#000000";
+pub const CURRENT_COLOR: &str = "#FFA500";
+pub const ACTIVE_COLOR: &str = "#FFE08A";
+pub const DONE_COLOR: &str = "#CCCCCC";
+pub const HIGHLIGHT_COLOR: &str = "#FF0000";
+pub const PATH_COLOR: &str = "#1E90FF";
+pub const RELAXED_COLOR: &str = "#00AA00";
+
+/// Distinct fill colors for grouping nodes, e.g. one per component.
+pub const PALETTE: [&str; 10] = [
+    "#7BD3EA", "#A1EEBD", "#F6D6D6", "#F6F7C4", "#9DBC98", "#EBD9B4", "#C3ACD0", "#FFB4B4",
+    "#B0C5A4", "#86A7FC",
+];
+
+const EDGE_WIDTH: f64 = 1.5;
+const HIGHLIGHTED_EDGE_WIDTH: f64 = 3.5;
+const PARALLEL_EDGE_OFFSET: f64 = 5.0;
+const NOTE_LINE_HEIGHT: f64 = 16.0;
+
+pub fn palette_color(index: usize) -> &'static str {
+    PALETTE[index % PALETTE.len()]
+}
+
+/// Everything about a `GeneralGraph` that changes while an algorithm runs.
+/// Visualizations record one `GraphView` per step and play them back.
+#[derive(Clone, Debug)]
+pub struct GraphView {
+    pub node_colors: Vec<&'static str>,
+    /// Drawn under each node, e.g. distances or discovery times.
+    pub node_labels: Vec<String>,
+    pub edge_colors: Vec<&'static str>,
+    /// Drawn at the middle of each edge, e.g. weights.
+    pub edge_labels: Vec<String>,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl GraphView {
+    pub fn new(graph: &GeneralGraph) -> GraphView {
+        GraphView {
+            node_colors: vec![NODE_COLOR; graph.node_count()],
+            node_labels: vec![String::new(); graph.node_count()],
+            edge_colors: vec![EDGE_COLOR; graph.edge_count()],
+            edge_labels: vec![String::new(); graph.edge_count()],
+            notes: vec![],
+        }
+    }
+
+    /// A view labelling every edge with its weight.
+    pub fn with_weights(graph: &GeneralGraph) -> GraphView {
+        GraphView {
+            edge_labels: graph
+                .edges()
+                .iter()
+                .map(|edge| format!("{}", edge.weight))
+                .collect(),
+            ..GraphView::new(graph)
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, graph: &GeneralGraph) {
+        canvas::clear(ctx);
+
+        self.draw_edges(ctx, graph);
+        self.draw_nodes(ctx, graph);
+        draw_notes(ctx, &self.notes);
+    }
+
+    fn draw_edges(&self, ctx: &CanvasRenderingContext2d, graph: &GeneralGraph) {
+        let vertices = graph.vertices();
+
+        for (idx, edge) in graph.edges().iter().enumerate() {
+            let from = &vertices[edge.from].coordinate;
+            let to = &vertices[edge.to].coordinate;
+            let color = self.edge_colors[idx];
+            let width = if color == EDGE_COLOR {
+                EDGE_WIDTH
+            } else {
+                HIGHLIGHTED_EDGE_WIDTH
+            };
+
+            if edge.from == edge.to {
+                draw_self_loop(ctx, from, color, width);
+                continue;
+            }
+
+            // Keep the two directions of a directed pair apart.
+            let offset = if graph.is_directed() && graph.has_edge(edge.to, edge.from) {
+                PARALLEL_EDGE_OFFSET
+            } else {
+                0.0
+            };
+
+            let (start, end) = edge_endpoints(from, to, offset);
+
+            let line = if graph.is_directed() {
+                Line::arrow(start.clone(), end.clone(), color.to_string(), width)
+            } else {
+                Line::new(start.clone(), end.clone(), color.to_string(), width)
+            };
+
+            line.draw(ctx);
+
+            if !self.edge_labels[idx].is_empty() {
+                let middle = Coordinate::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+
+                draw_edge_label(ctx, &self.edge_labels[idx], middle);
+            }
+        }
+    }
+
+    fn draw_nodes(&self, ctx: &CanvasRenderingContext2d, graph: &GeneralGraph) {
+        for (idx, vertex) in graph.vertices().iter().enumerate() {
+            draw_node(
+                ctx,
+                &vertex.coordinate,
+                self.node_colors[idx],
+                &vertex.label,
+            );
+
+            if !self.node_labels[idx].is_empty() {
+                Text::centered(
+                    self.node_labels[idx].clone(),
+                    Coordinate::new(vertex.coordinate.x, vertex.coordinate.y + NODE_RADIUS + 9.0),
+                    TEXT_COLOR.to_string(),
+                )
+                .draw(ctx);
+            }
+        }
+    }
+}
+
+pub fn draw_node(ctx: &CanvasRenderingContext2d, coordinate: &Coordinate, color: &str, name: &str) {
+    draw_node_with_text_color(ctx, coordinate, color, TEXT_COLOR, name);
+}
+
+/// A node whose name is written in `text_color`, for dark fills.
+pub fn draw_node_with_text_color(
+    ctx: &CanvasRenderingContext2d,
+    coordinate: &Coordinate,
+    color: &str,
+    text_color: &str,
+    name: &str,
+) {
+    let circle = Circle::new(
+        color.to_string(),
+        NODE_RADIUS,
+        coordinate.clone(),
+        Boundary::new(0.0, 0.0),
+        None,
+    );
+
+    circle.draw(ctx);
+    ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+    ctx.stroke();
+
+    Text::centered(name.to_string(), coordinate.clone(), text_color.to_string()).draw(ctx);
+}
+
+pub fn draw_notes(ctx: &CanvasRenderingContext2d, notes: &[String]) {
+    for (line, note) in notes.iter().enumerate() {
+        Text::left_aligned(
+            note.clone(),
+            Coordinate::new(10.0, NOTE_LINE_HEIGHT * (line as f64 + 1.0)),
+            TEXT_COLOR.to_string(),
+        )
+        .draw(ctx);
+    }
+}
+
+pub fn draw_edge_label(ctx: &CanvasRenderingContext2d, label: &str, coordinate: Coordinate) {
+    let width = 7.0 * label.len() as f64 + 4.0;
+
+    ctx.set_fill_style(&NODE_COLOR.into());
+    ctx.fill_rect(coordinate.x - width / 2.0, coordinate.y - 7.0, width, 14.0);
+
+    Text::centered(label.to_string(), coordinate, TEXT_COLOR.to_string()).draw(ctx);
+}
+
+fn draw_self_loop(ctx: &CanvasRenderingContext2d, center: &Coordinate, color: &str, width: f64) {
+    ctx.set_stroke_style(&color.into());
+    ctx.set_line_width(width);
+    ctx.begin_path();
+
+    let _ = ctx.arc(
+        center.x,
+        center.y - NODE_RADIUS,
+        NODE_RADIUS * 0.6,
+        0.0,
+        std::f64::consts::PI * 2.0,
+    );
+
+    ctx.stroke();
+    ctx.set_line_width(1.0);
+}
+
+/// Start and end of an edge drawn between the borders of two nodes, shifted
+/// sideways by `offset`.
+fn edge_endpoints(from: &Coordinate, to: &Coordinate, offset: f64) -> (Coordinate, Coordinate) {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let length = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+    let (ux, uy) = (dx / length, dy / length);
+    let (px, py) = (-uy * offset, ux * offset);
+
+    (
+        Coordinate::new(
+            from.x + ux * NODE_RADIUS + px,
+            from.y + uy * NODE_RADIUS + py,
+        ),
+        Coordinate::new(to.x - ux * NODE_RADIUS + px, to.y - uy * NODE_RADIUS + py),
+    )
+}
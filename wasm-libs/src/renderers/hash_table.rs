@@ -0,0 +1,195 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Coordinate, Drawable},
+    data_structures::hash_table::{Collision, HashTable, Location, Slot},
+    renderers::graph::{
+        draw_notes, DONE_COLOR, EDGE_COLOR, NODE_BORDER_COLOR, NODE_COLOR, TEXT_COLOR,
+    },
+    shapes::{line::Line, text::Text},
+};
+
+pub const TOMBSTONE_COLOR: &str = DONE_COLOR;
+const TOMBSTONE_LABEL: &str = "del";
+
+/// Room left above the buckets for the notes.
+const NOTES_HEIGHT: f64 = 80.0;
+const MARGIN: f64 = 24.0;
+const ROW_HEIGHT: f64 = 26.0;
+const ROW_PADDING: f64 = 3.0;
+const INDEX_WIDTH: f64 = 28.0;
+const SLOT_WIDTH: f64 = 44.0;
+const CHAIN_BOX_WIDTH: f64 = 36.0;
+const CHAIN_GAP: f64 = 14.0;
+
+/// A bucket of a `HashTableView`: the key in its slot, or with chaining
+/// the keys of its chain.
+#[derive(Clone, Debug)]
+pub struct BucketView {
+    pub label: String,
+    pub color: &'static str,
+    pub chain: Vec<String>,
+    pub chain_colors: Vec<&'static str>,
+}
+
+/// One frame of a hash table visualization. Buckets are stacked top to
+/// bottom, in several columns when they do not fit, with chains drawn as
+/// linked boxes to the right of their bucket.
+#[derive(Clone, Debug)]
+pub struct HashTableView {
+    pub buckets: Vec<BucketView>,
+    pub chained: bool,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl HashTableView {
+    pub fn new(table: &HashTable) -> HashTableView {
+        let chained = table.collision() == Collision::Chaining;
+
+        let buckets = (0..table.capacity())
+            .map(|bucket| {
+                let chain = table.chain(bucket);
+                let (label, color) = match table.slot(bucket) {
+                    Slot::Occupied(key) if !chained => (key.to_string(), NODE_COLOR),
+                    Slot::Tombstone => (TOMBSTONE_LABEL.to_string(), TOMBSTONE_COLOR),
+                    _ => (String::new(), NODE_COLOR),
+                };
+
+                BucketView {
+                    label,
+                    color,
+                    chain: chain.iter().map(|key| key.to_string()).collect(),
+                    chain_colors: vec![NODE_COLOR; chain.len()],
+                }
+            })
+            .collect();
+
+        HashTableView {
+            buckets,
+            chained,
+            notes: vec![],
+        }
+    }
+
+    /// Colors the slot or chain box at `location`.
+    pub fn highlight(&mut self, location: Location, color: &'static str) {
+        let bucket = &mut self.buckets[location.bucket];
+
+        if self.chained {
+            bucket.chain_colors[location.position] = color;
+        } else {
+            bucket.color = color;
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+            let rows = (((height - NOTES_HEIGHT - MARGIN) / ROW_HEIGHT).floor() as usize).max(1);
+            let columns = self.buckets.len().div_ceil(rows).max(1);
+            let column_width = (width - 2.0 * MARGIN) / columns as f64;
+
+            for (idx, bucket) in self.buckets.iter().enumerate() {
+                let left = MARGIN + (idx / rows) as f64 * column_width;
+                let top = NOTES_HEIGHT + (idx % rows) as f64 * ROW_HEIGHT;
+
+                bucket.draw(ctx, idx, left, top, column_width);
+            }
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+}
+
+impl BucketView {
+    fn draw(&self, ctx: &CanvasRenderingContext2d, idx: usize, left: f64, top: f64, width: f64) {
+        let box_height = ROW_HEIGHT - 2.0 * ROW_PADDING;
+        let middle = top + ROW_HEIGHT / 2.0;
+
+        Text::centered(
+            idx.to_string(),
+            Coordinate::new(left + INDEX_WIDTH / 2.0, middle),
+            EDGE_COLOR.to_string(),
+        )
+        .draw(ctx);
+
+        let slot_left = left + INDEX_WIDTH;
+
+        draw_box(
+            ctx,
+            slot_left,
+            top + ROW_PADDING,
+            SLOT_WIDTH,
+            box_height,
+            self.color,
+        );
+        Text::centered(
+            self.label.clone(),
+            Coordinate::new(slot_left + SLOT_WIDTH / 2.0, middle),
+            TEXT_COLOR.to_string(),
+        )
+        .draw(ctx);
+
+        let mut previous_right = slot_left + SLOT_WIDTH;
+
+        for (position, key) in self.chain.iter().enumerate() {
+            let box_left = previous_right + CHAIN_GAP;
+
+            // Chains longer than the column are cut off with an ellipsis.
+            if box_left + CHAIN_BOX_WIDTH > left + width {
+                Text::left_aligned(
+                    "…".to_string(),
+                    Coordinate::new(previous_right + 2.0, middle),
+                    TEXT_COLOR.to_string(),
+                )
+                .draw(ctx);
+                break;
+            }
+
+            Line::arrow(
+                Coordinate::new(
+                    previous_right - if position == 0 { SLOT_WIDTH / 2.0 } else { 0.0 },
+                    middle,
+                ),
+                Coordinate::new(box_left, middle),
+                EDGE_COLOR.to_string(),
+                1.5,
+            )
+            .draw(ctx);
+
+            draw_box(
+                ctx,
+                box_left,
+                top + ROW_PADDING,
+                CHAIN_BOX_WIDTH,
+                box_height,
+                self.chain_colors[position],
+            );
+            Text::centered(
+                key.clone(),
+                Coordinate::new(box_left + CHAIN_BOX_WIDTH / 2.0, middle),
+                TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+
+            previous_right = box_left + CHAIN_BOX_WIDTH;
+        }
+    }
+}
+
+fn draw_box(
+    ctx: &CanvasRenderingContext2d,
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+    color: &str,
+) {
+    ctx.set_fill_style(&color.into());
+    ctx.fill_rect(left, top, width, height);
+    ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+    ctx.stroke_rect(left, top, width, height);
+}
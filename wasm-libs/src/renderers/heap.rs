@@ -0,0 +1,111 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Coordinate, Drawable},
+    data_structures::binary_heap::parent,
+    renderers::graph::{draw_node, draw_notes, EDGE_COLOR, NODE_BORDER_COLOR, TEXT_COLOR},
+    shapes::{line::Line, text::Text},
+};
+
+/// Room left above the array for the notes.
+const NOTES_HEIGHT: f64 = 64.0;
+const MARGIN: f64 = 24.0;
+const MAX_CELL_WIDTH: f64 = 48.0;
+const CELL_HEIGHT: f64 = 32.0;
+const INDEX_CAPTION_HEIGHT: f64 = 16.0;
+/// Space between the array strip and the tree.
+const STRIP_GAP: f64 = 24.0;
+const MAX_LEVEL_SPACING: f64 = 72.0;
+
+/// A heap drawn twice: as the array it is stored in and as the complete
+/// binary tree that array encodes, every index colored the same in both.
+#[derive(Clone, Debug)]
+pub struct HeapView {
+    pub values: Vec<i32>,
+    pub colors: Vec<&'static str>,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl HeapView {
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+
+            self.draw_strip(ctx, width);
+            self.draw_tree(
+                ctx,
+                width,
+                NOTES_HEIGHT + CELL_HEIGHT + INDEX_CAPTION_HEIGHT + STRIP_GAP,
+                height,
+            );
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+
+    fn draw_strip(&self, ctx: &CanvasRenderingContext2d, width: f64) {
+        let cell_width =
+            ((width - 2.0 * MARGIN) / self.values.len().max(1) as f64).min(MAX_CELL_WIDTH);
+
+        for (idx, value) in self.values.iter().enumerate() {
+            let left = MARGIN + idx as f64 * cell_width;
+
+            ctx.set_fill_style(&self.colors[idx].into());
+            ctx.fill_rect(left, NOTES_HEIGHT, cell_width, CELL_HEIGHT);
+            ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+            ctx.stroke_rect(left, NOTES_HEIGHT, cell_width, CELL_HEIGHT);
+
+            Text::centered(
+                value.to_string(),
+                Coordinate::new(left + cell_width / 2.0, NOTES_HEIGHT + CELL_HEIGHT / 2.0),
+                TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+
+            Text::centered(
+                idx.to_string(),
+                Coordinate::new(
+                    left + cell_width / 2.0,
+                    NOTES_HEIGHT + CELL_HEIGHT + INDEX_CAPTION_HEIGHT / 2.0,
+                ),
+                EDGE_COLOR.to_string(),
+            )
+            .draw(ctx);
+        }
+    }
+
+    /// Index `i` sits on level `⌊log2(i + 1)⌋`, the levels dividing the
+    /// width into ever more equal slots.
+    fn draw_tree(&self, ctx: &CanvasRenderingContext2d, width: f64, top: f64, height: f64) {
+        let levels = (usize::BITS - self.values.len().leading_zeros()) as f64;
+        let row_height = ((height - top - MARGIN) / levels.max(1.0)).min(MAX_LEVEL_SPACING);
+
+        let position = |idx: usize| {
+            let level = (usize::BITS - 1 - (idx + 1).leading_zeros()) as usize;
+            let slot = idx + 1 - (1 << level);
+            let slot_width = (width - 2.0 * MARGIN) / (1u64 << level) as f64;
+
+            Coordinate::new(
+                MARGIN + (slot as f64 + 0.5) * slot_width,
+                top + (level as f64 + 0.5) * row_height,
+            )
+        };
+
+        for idx in 1..self.values.len() {
+            Line::new(
+                position(parent(idx).unwrap()),
+                position(idx),
+                EDGE_COLOR.to_string(),
+                1.5,
+            )
+            .draw(ctx);
+        }
+
+        for (idx, value) in self.values.iter().enumerate() {
+            draw_node(ctx, &position(idx), self.colors[idx], &value.to_string());
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Coordinate, Drawable},
+    data_structures::linked_list::LinkedList,
+    renderers::graph::{draw_notes, EDGE_COLOR, NODE_BORDER_COLOR, NODE_COLOR, TEXT_COLOR},
+    shapes::{line::Line, text::Text},
+};
+
+/// Room left above the nodes for the notes and the head and tail markers.
+const NOTES_HEIGHT: f64 = 96.0;
+const MARGIN: f64 = 24.0;
+const VALUE_WIDTH: f64 = 38.0;
+const POINTER_WIDTH: f64 = 18.0;
+const BOX_HEIGHT: f64 = 30.0;
+const MAX_SPACING: f64 = 110.0;
+/// Vertical distance between the list and the row of unlinked nodes.
+const ROW_SPACING: f64 = 84.0;
+const MARKER_HEIGHT: f64 = 30.0;
+/// How far `next` and `prev` arrows of a doubly linked list sit above and
+/// below the middle of the boxes.
+const POINTER_OFFSET: f64 = 6.0;
+const POINTER_WIDTH_STROKE: f64 = 1.5;
+
+/// A node of a `ListView`, placed at a column of the list and a row: 0 for
+/// the list itself, 1 for nodes not (or no longer) linked into it.
+#[derive(Clone, Debug)]
+pub struct ListNodeView {
+    pub label: String,
+    pub column: f64,
+    pub row: f64,
+    pub color: &'static str,
+    pub next: Option<usize>,
+    pub prev: Option<usize>,
+    pub next_color: &'static str,
+    pub prev_color: &'static str,
+    /// Names of the cursors pointing at the node, written below it.
+    pub cursors: Vec<&'static str>,
+}
+
+/// One frame of a linked list visualization: boxes with a value cell and
+/// pointer cells, arrows for the pointers and a slash for a null pointer.
+/// Nodes are indexed by their slot in the list, so a frame keeps drawing a
+/// node where it was while its pointers are being rewired.
+#[derive(Clone, Debug)]
+pub struct ListView {
+    pub nodes: Vec<Option<ListNodeView>>,
+    pub doubly: bool,
+    pub head: Option<usize>,
+    pub tail: Option<usize>,
+    /// What the head is called, `"head"`, `"top"` or `"front"`.
+    pub head_label: &'static str,
+    /// What the tail is called, if the structure keeps a tail pointer.
+    pub tail_label: Option<&'static str>,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl ListView {
+    /// The nodes of `list` in order in row 0. Nodes not reachable from the
+    /// head go to row 1 below the column they would be appended at.
+    pub fn new(
+        list: &LinkedList,
+        head_label: &'static str,
+        tail_label: Option<&'static str>,
+    ) -> ListView {
+        let mut nodes = vec![None; list.capacity()];
+        let order = list.ids();
+
+        for id in (0..list.capacity()).filter(|&id| list.contains(id)) {
+            let node = list.node(id);
+            let (column, row) = match order.iter().position(|&other| other == id) {
+                Some(column) => (column as f64, 0.0),
+                None => (order.len() as f64, 1.0),
+            };
+
+            nodes[id] = Some(ListNodeView {
+                label: node.value.to_string(),
+                column,
+                row,
+                color: NODE_COLOR,
+                next: node.next,
+                prev: node.prev.filter(|_| list.is_doubly()),
+                next_color: EDGE_COLOR,
+                prev_color: EDGE_COLOR,
+                cursors: vec![],
+            });
+        }
+
+        ListView {
+            nodes,
+            doubly: list.is_doubly(),
+            head: list.head(),
+            tail: list.tail(),
+            head_label,
+            tail_label,
+            notes: vec![],
+        }
+    }
+
+    /// A singly linked chain of `labels`, for showing the contents of other
+    /// sequences, like a `VecDeque`, the way a list would hold them.
+    pub fn sequence(
+        labels: Vec<String>,
+        head_label: &'static str,
+        tail_label: Option<&'static str>,
+    ) -> ListView {
+        let len = labels.len();
+        let nodes = labels
+            .into_iter()
+            .enumerate()
+            .map(|(idx, label)| {
+                Some(ListNodeView {
+                    label,
+                    column: idx as f64,
+                    row: 0.0,
+                    color: NODE_COLOR,
+                    next: Some(idx + 1).filter(|&next| next < len),
+                    prev: None,
+                    next_color: EDGE_COLOR,
+                    prev_color: EDGE_COLOR,
+                    cursors: vec![],
+                })
+            })
+            .collect();
+
+        ListView {
+            nodes,
+            doubly: false,
+            head: Some(0).filter(|_| len > 0),
+            tail: len.checked_sub(1),
+            head_label,
+            tail_label,
+            notes: vec![],
+        }
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> Option<&mut ListNodeView> {
+        self.nodes.get_mut(id).and_then(|node| node.as_mut())
+    }
+
+    fn box_width(&self) -> f64 {
+        VALUE_WIDTH + POINTER_WIDTH * if self.doubly { 2.0 } else { 1.0 }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let columns = self
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| node.column + 1.0)
+                .fold(1.0, f64::max);
+            let spacing = ((canvas.width() as f64 - 2.0 * MARGIN) / columns).min(MAX_SPACING);
+
+            self.draw_with_spacing(ctx, spacing);
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+
+    /// The top left corner of the box of `node`.
+    fn corner(&self, node: &ListNodeView, spacing: f64) -> Coordinate {
+        Coordinate::new(
+            MARGIN + node.column * spacing + (spacing - self.box_width()).max(0.0) / 2.0,
+            NOTES_HEIGHT + node.row * ROW_SPACING,
+        )
+    }
+
+    fn draw_with_spacing(&self, ctx: &CanvasRenderingContext2d, spacing: f64) {
+        let width = self.box_width();
+        let value_left = if self.doubly { POINTER_WIDTH } else { 0.0 };
+
+        for node in self.nodes.iter().flatten() {
+            let corner = self.corner(node, spacing);
+
+            for (left, cell_width, color) in [
+                (value_left, VALUE_WIDTH, node.color),
+                (value_left + VALUE_WIDTH, POINTER_WIDTH, NODE_COLOR),
+                (0.0, value_left, NODE_COLOR),
+            ] {
+                if cell_width > 0.0 {
+                    ctx.set_fill_style(&color.into());
+                    ctx.fill_rect(corner.x + left, corner.y, cell_width, BOX_HEIGHT);
+                    ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+                    ctx.stroke_rect(corner.x + left, corner.y, cell_width, BOX_HEIGHT);
+                }
+            }
+
+            Text::centered(
+                node.label.clone(),
+                Coordinate::new(
+                    corner.x + value_left + VALUE_WIDTH / 2.0,
+                    corner.y + BOX_HEIGHT / 2.0,
+                ),
+                TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+
+            if !node.cursors.is_empty() {
+                Text::centered(
+                    node.cursors.join(", "),
+                    Coordinate::new(corner.x + width / 2.0, corner.y + BOX_HEIGHT + 12.0),
+                    TEXT_COLOR.to_string(),
+                )
+                .draw(ctx);
+            }
+        }
+
+        for node in self.nodes.iter().flatten() {
+            self.draw_pointer(ctx, spacing, node, true);
+
+            if self.doubly {
+                self.draw_pointer(ctx, spacing, node, false);
+            }
+        }
+
+        self.draw_markers(ctx, spacing);
+    }
+
+    /// An arrow from the `next` or `prev` cell of `node` to the near side
+    /// of the box it points to, or a slash through the cell for a null
+    /// pointer.
+    fn draw_pointer(
+        &self,
+        ctx: &CanvasRenderingContext2d,
+        spacing: f64,
+        node: &ListNodeView,
+        next: bool,
+    ) {
+        let corner = self.corner(node, spacing);
+        let offset = if self.doubly { POINTER_OFFSET } else { 0.0 };
+
+        let (cell, y, target, color) = if next {
+            let left = corner.x + self.box_width() - POINTER_WIDTH;
+
+            (
+                (left, left + POINTER_WIDTH),
+                corner.y + BOX_HEIGHT / 2.0 - offset,
+                node.next,
+                node.next_color,
+            )
+        } else {
+            (
+                (corner.x, corner.x + POINTER_WIDTH),
+                corner.y + BOX_HEIGHT / 2.0 + offset,
+                node.prev,
+                node.prev_color,
+            )
+        };
+
+        let Some(target) = target.and_then(|target| self.nodes.get(target)?.as_ref()) else {
+            Line::new(
+                Coordinate::new(cell.0, corner.y + BOX_HEIGHT),
+                Coordinate::new(cell.1, corner.y),
+                color.to_string(),
+                POINTER_WIDTH_STROKE,
+            )
+            .draw(ctx);
+            return;
+        };
+
+        let target_corner = self.corner(target, spacing);
+        let target_y = y - corner.y + target_corner.y;
+        let middle = (cell.0 + cell.1) / 2.0;
+
+        let (from, to) = if target_corner.x > corner.x {
+            (middle, target_corner.x)
+        } else if target_corner.x < corner.x {
+            (middle, target_corner.x + self.box_width())
+        } else {
+            // Straight up or down to a node in the other row.
+            (middle, middle)
+        };
+
+        let to_y = if from == to {
+            if target_corner.y > corner.y {
+                target_corner.y
+            } else {
+                target_corner.y + BOX_HEIGHT
+            }
+        } else {
+            target_y
+        };
+
+        Line::arrow(
+            Coordinate::new(from, y),
+            Coordinate::new(to, to_y),
+            color.to_string(),
+            POINTER_WIDTH_STROKE,
+        )
+        .draw(ctx);
+    }
+
+    /// Labels above the head and tail nodes with arrows down to them.
+    fn draw_markers(&self, ctx: &CanvasRenderingContext2d, spacing: f64) {
+        let mut markers: Vec<(usize, String)> = vec![];
+
+        let targets = [
+            (self.head, Some(self.head_label)),
+            (self.tail, self.tail_label),
+        ];
+
+        for (target, label) in targets {
+            let (Some(target), Some(label)) = (target, label) else {
+                continue;
+            };
+
+            match markers.iter_mut().find(|(id, _)| *id == target) {
+                Some((_, text)) => *text += &format!(", {}", label),
+                None => markers.push((target, label.to_string())),
+            }
+        }
+
+        for (id, text) in markers {
+            let Some(node) = self.nodes.get(id).and_then(|node| node.as_ref()) else {
+                continue;
+            };
+
+            let corner = self.corner(node, spacing);
+            let x = corner.x + self.box_width() / 2.0;
+
+            Text::centered(
+                text,
+                Coordinate::new(x, corner.y - MARKER_HEIGHT),
+                TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+            Line::arrow(
+                Coordinate::new(x, corner.y - MARKER_HEIGHT + 8.0),
+                Coordinate::new(x, corner.y - 2.0),
+                EDGE_COLOR.to_string(),
+                POINTER_WIDTH_STROKE,
+            )
+            .draw(ctx);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{Coordinate, Drawable},
+    shapes::text::Text,
+};
+
+pub const CELL_SIZE: f64 = 36.0;
+pub const CELL_COLOR: &str = "#FFFFFF";
+
+const CELL_BORDER_COLOR: &str = "#999999";
+const TEXT_COLOR: &str = "#000000";
+
+/// A grid of labelled cells, e.g. a distance matrix, drawn with its top left
+/// cell at a given origin and row/column headers around it.
+#[derive(Clone, Debug)]
+pub struct MatrixView {
+    pub cells: Vec<Vec<String>>,
+    pub cell_colors: Vec<Vec<&'static str>>,
+    pub row_labels: Vec<String>,
+    pub column_labels: Vec<String>,
+}
+
+impl MatrixView {
+    pub fn new(rows: usize, columns: usize) -> MatrixView {
+        MatrixView {
+            cells: vec![vec![String::new(); columns]; rows],
+            cell_colors: vec![vec![CELL_COLOR; columns]; rows],
+            row_labels: vec![String::new(); rows],
+            column_labels: vec![String::new(); columns],
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, origin: &Coordinate) {
+        for (column, label) in self.column_labels.iter().enumerate() {
+            Text::centered(
+                label.clone(),
+                Coordinate::new(
+                    origin.x + (column as f64 + 0.5) * CELL_SIZE,
+                    origin.y - CELL_SIZE / 3.0,
+                ),
+                TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+        }
+
+        for (row, cells) in self.cells.iter().enumerate() {
+            let y = origin.y + row as f64 * CELL_SIZE;
+
+            Text::centered(
+                self.row_labels[row].clone(),
+                Coordinate::new(origin.x - CELL_SIZE / 3.0, y + CELL_SIZE / 2.0),
+                TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+
+            for (column, cell) in cells.iter().enumerate() {
+                let x = origin.x + column as f64 * CELL_SIZE;
+
+                ctx.set_fill_style(&self.cell_colors[row][column].into());
+                ctx.fill_rect(x, y, CELL_SIZE, CELL_SIZE);
+                ctx.set_stroke_style(&CELL_BORDER_COLOR.into());
+                ctx.stroke_rect(x, y, CELL_SIZE, CELL_SIZE);
+
+                Text::centered(
+                    cell.clone(),
+                    Coordinate::new(x + CELL_SIZE / 2.0, y + CELL_SIZE / 2.0),
+                    TEXT_COLOR.to_string(),
+                )
+                .draw(ctx);
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Boundary, Coordinate, Drawable},
+    data_structures::sorting_network::SortingNetwork,
+    renderers::graph::{
+        draw_node, draw_notes, ACTIVE_COLOR, CURRENT_COLOR, DONE_COLOR, EDGE_COLOR,
+        HIGHLIGHT_COLOR, NODE_COLOR,
+    },
+    shapes::{circle::Circle, line::Line},
+};
+
+/// Room left above the wires for the notes.
+const NOTES_HEIGHT: f64 = 64.0;
+const MARGIN: f64 = 24.0;
+const COMPARATOR_DOT_RADIUS: f64 = 4.0;
+const COMPARATOR_WIDTH: f64 = 2.0;
+
+/// The values on every wire after `passed` layers of a sorting network.
+#[derive(Clone, Debug)]
+pub struct NetworkView {
+    pub values: Vec<u32>,
+    pub passed: usize,
+    /// Comparators of the layer being applied, and those of them that
+    /// swapped their values.
+    pub active: Vec<usize>,
+    pub swapped: Vec<usize>,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+/// Where every layer goes across the canvas: a gap column holding the
+/// values before it, then one column per group of comparators whose wire
+/// spans do not overlap.
+struct Layout {
+    column_width: f64,
+    wire_spacing: f64,
+    /// Column of the values before each layer, plus one after the last.
+    value_columns: Vec<usize>,
+    comparator_columns: Vec<usize>,
+}
+
+impl Layout {
+    fn new(network: &SortingNetwork, layers: &[Vec<usize>], width: f64, height: f64) -> Layout {
+        let mut comparator_columns = vec![0; network.comparators().len()];
+        let mut value_columns = vec![];
+        let mut column = 0;
+
+        for layer in layers {
+            value_columns.push(column);
+            column += 1;
+
+            // Greedy interval coloring of the comparator spans.
+            let mut slot_ends: Vec<usize> = vec![];
+
+            for &idx in layer {
+                let (low, high) = network.comparators()[idx];
+
+                let slot = match slot_ends.iter().position(|&end| end < low) {
+                    Some(slot) => slot,
+                    None => {
+                        slot_ends.push(0);
+                        slot_ends.len() - 1
+                    }
+                };
+
+                slot_ends[slot] = high;
+                comparator_columns[idx] = column + slot;
+            }
+
+            column += slot_ends.len();
+        }
+
+        value_columns.push(column);
+
+        Layout {
+            column_width: (width - 2.0 * MARGIN) / (column + 1) as f64,
+            wire_spacing: (height - NOTES_HEIGHT - MARGIN) / network.wires().max(1) as f64,
+            value_columns,
+            comparator_columns,
+        }
+    }
+
+    fn x(&self, column: usize) -> f64 {
+        MARGIN + (column as f64 + 0.5) * self.column_width
+    }
+
+    fn y(&self, wire: usize) -> f64 {
+        NOTES_HEIGHT + (wire as f64 + 0.5) * self.wire_spacing
+    }
+}
+
+impl NetworkView {
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, network: &SortingNetwork) {
+        canvas::clear(ctx);
+
+        let Some(canvas) = ctx.canvas() else {
+            return;
+        };
+
+        let layers = network.layers();
+        let layout = Layout::new(
+            network,
+            &layers,
+            canvas.width() as f64,
+            canvas.height() as f64,
+        );
+
+        for wire in 0..network.wires() {
+            Line::new(
+                Coordinate::new(MARGIN, layout.y(wire)),
+                Coordinate::new(canvas.width() as f64 - MARGIN, layout.y(wire)),
+                EDGE_COLOR.to_string(),
+                1.0,
+            )
+            .draw(ctx);
+        }
+
+        for (layer, comparators) in layers.iter().enumerate() {
+            for &idx in comparators {
+                let color = if self.swapped.contains(&idx) {
+                    HIGHLIGHT_COLOR
+                } else if self.active.contains(&idx) {
+                    CURRENT_COLOR
+                } else if layer < self.passed {
+                    DONE_COLOR
+                } else {
+                    EDGE_COLOR
+                };
+
+                draw_comparator(ctx, &layout, idx, network.comparators()[idx], color);
+            }
+        }
+
+        let value_column = layout.value_columns[self.passed.min(layers.len())];
+
+        for (wire, value) in self.values.iter().enumerate() {
+            let moved = self.swapped.iter().any(|&idx| {
+                network.comparators()[idx].0 == wire || network.comparators()[idx].1 == wire
+            });
+
+            draw_node(
+                ctx,
+                &Coordinate::new(layout.x(value_column), layout.y(wire)),
+                if moved { ACTIVE_COLOR } else { NODE_COLOR },
+                &value.to_string(),
+            );
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+}
+
+fn draw_comparator(
+    ctx: &CanvasRenderingContext2d,
+    layout: &Layout,
+    idx: usize,
+    (low, high): (usize, usize),
+    color: &str,
+) {
+    let x = layout.x(layout.comparator_columns[idx]);
+
+    Line::new(
+        Coordinate::new(x, layout.y(low)),
+        Coordinate::new(x, layout.y(high)),
+        color.to_string(),
+        COMPARATOR_WIDTH,
+    )
+    .draw(ctx);
+
+    for wire in [low, high] {
+        Circle::new(
+            color.to_string(),
+            COMPARATOR_DOT_RADIUS,
+            Coordinate::new(x, layout.y(wire)),
+            Boundary::new(0.0, 0.0),
+            None,
+        )
+        .draw(ctx);
+    }
+}
@@ -0,0 +1,202 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    canvas::{self, Coordinate, Drawable},
+    data_structures::skip_list::SkipList,
+    renderers::graph::{draw_notes, EDGE_COLOR, NODE_BORDER_COLOR, NODE_COLOR, TEXT_COLOR},
+    shapes::{line::Line, text::Text},
+};
+
+/// Room left above the lanes for the notes.
+const NOTES_HEIGHT: f64 = 80.0;
+const MARGIN: f64 = 24.0;
+/// Room left of the head for the lane names.
+const CAPTION_WIDTH: f64 = 28.0;
+const MAX_COLUMN_WIDTH: f64 = 64.0;
+const MAX_BOX_WIDTH: f64 = 30.0;
+const MAX_LEVEL_HEIGHT: f64 = 26.0;
+/// Height of the cell under every tower holding its key.
+const KEY_HEIGHT: f64 = 24.0;
+const POINTER_WIDTH: f64 = 1.5;
+const HIGHLIGHTED_POINTER_WIDTH: f64 = 3.0;
+
+/// The head or a node of a `SkipListView`: a tower with a pointer cell for
+/// every lane it reaches, over a cell with its key.
+#[derive(Clone, Debug)]
+pub struct SkipTowerView {
+    pub label: String,
+    pub column: f64,
+    /// Where the pointer on every lane leads, `None` for the end of the
+    /// lane.
+    pub next: Vec<Option<usize>>,
+    pub color: &'static str,
+    pub level_colors: Vec<&'static str>,
+    pub link_colors: Vec<&'static str>,
+}
+
+impl SkipTowerView {
+    fn new(label: String, column: f64, next: Vec<Option<usize>>) -> SkipTowerView {
+        SkipTowerView {
+            label,
+            column,
+            level_colors: vec![NODE_COLOR; next.len()],
+            link_colors: vec![EDGE_COLOR; next.len()],
+            next,
+            color: NODE_COLOR,
+        }
+    }
+}
+
+/// One frame of a skip list visualization: the head and the nodes in key
+/// order as towers, with the lanes drawn as arrows between the cells on
+/// the same level and a slash for the end of a lane. Nodes are indexed by
+/// their slot in the list.
+#[derive(Clone, Debug)]
+pub struct SkipListView {
+    pub head: SkipTowerView,
+    pub nodes: Vec<Option<SkipTowerView>>,
+    pub columns: f64,
+    pub levels: f64,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl SkipListView {
+    /// The head in column 0 and every allocated node in the column after
+    /// its rank among the keys, linked in yet or not.
+    pub fn new(list: &SkipList) -> SkipListView {
+        let mut ids = list.ids();
+        let mut nodes = vec![None; list.capacity()];
+        let mut levels = list.level();
+
+        ids.sort_by_key(|&id| list.node(id).key);
+
+        for (rank, &id) in ids.iter().enumerate() {
+            let node = list.node(id);
+
+            levels = levels.max(node.next.len());
+            nodes[id] = Some(SkipTowerView::new(
+                node.key.to_string(),
+                rank as f64 + 1.0,
+                node.next.clone(),
+            ));
+        }
+
+        SkipListView {
+            head: SkipTowerView::new(
+                "head".to_string(),
+                0.0,
+                (0..list.level())
+                    .map(|level| list.next(None, level))
+                    .collect(),
+            ),
+            nodes,
+            columns: ids.len() as f64 + 1.0,
+            levels: levels as f64,
+            notes: vec![],
+        }
+    }
+
+    /// The tower of the node `at`, or of the head for `None`.
+    pub fn tower_mut(&mut self, at: Option<usize>) -> &mut SkipTowerView {
+        match at {
+            Some(id) => self.nodes[id].as_mut().expect("no node in this slot"),
+            None => &mut self.head,
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let left = MARGIN + CAPTION_WIDTH;
+            let column_width =
+                ((canvas.width() as f64 - left - MARGIN) / self.columns).min(MAX_COLUMN_WIDTH);
+            let box_width = (column_width * 0.6).min(MAX_BOX_WIDTH);
+            let base = canvas.height() as f64 - MARGIN - KEY_HEIGHT;
+            let level_height = ((base - NOTES_HEIGHT) / self.levels.max(1.0)).min(MAX_LEVEL_HEIGHT);
+
+            let center = |tower: &SkipTowerView| left + (tower.column + 0.5) * column_width;
+            let middle = |level: usize| base - (level as f64 + 0.5) * level_height;
+
+            for level in 0..self.levels as usize {
+                Text::left_aligned(
+                    format!("L{}", level),
+                    Coordinate::new(MARGIN, middle(level)),
+                    EDGE_COLOR.to_string(),
+                )
+                .draw(ctx);
+            }
+
+            for tower in std::iter::once(&self.head).chain(self.nodes.iter().flatten()) {
+                let x = center(tower);
+
+                for (level, &color) in tower.level_colors.iter().enumerate() {
+                    let top = base - (level as f64 + 1.0) * level_height;
+
+                    ctx.set_fill_style(&color.into());
+                    ctx.fill_rect(x - box_width / 2.0, top, box_width, level_height);
+                    ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+                    ctx.stroke_rect(x - box_width / 2.0, top, box_width, level_height);
+                }
+
+                ctx.set_fill_style(&tower.color.into());
+                ctx.fill_rect(
+                    x - column_width / 2.0 + 2.0,
+                    base,
+                    column_width - 4.0,
+                    KEY_HEIGHT,
+                );
+                ctx.set_stroke_style(&NODE_BORDER_COLOR.into());
+                ctx.stroke_rect(
+                    x - column_width / 2.0 + 2.0,
+                    base,
+                    column_width - 4.0,
+                    KEY_HEIGHT,
+                );
+
+                Text::centered(
+                    tower.label.clone(),
+                    Coordinate::new(x, base + KEY_HEIGHT / 2.0),
+                    TEXT_COLOR.to_string(),
+                )
+                .draw(ctx);
+            }
+
+            // Pointers go on top of all the boxes so that no tower covers
+            // an arrow head.
+            for tower in std::iter::once(&self.head).chain(self.nodes.iter().flatten()) {
+                let x = center(tower);
+
+                for (level, next) in tower.next.iter().enumerate() {
+                    let y = middle(level);
+                    let color = tower.link_colors[level];
+                    let width = if color == EDGE_COLOR {
+                        POINTER_WIDTH
+                    } else {
+                        HIGHLIGHTED_POINTER_WIDTH
+                    };
+
+                    match next.and_then(|id| self.nodes.get(id)?.as_ref()) {
+                        Some(target) => Line::arrow(
+                            Coordinate::new(x, y),
+                            Coordinate::new(center(target) - box_width / 2.0, y),
+                            color.to_string(),
+                            width,
+                        )
+                        .draw(ctx),
+                        None => Line::new(
+                            Coordinate::new(x - box_width / 2.0, y + level_height / 2.0),
+                            Coordinate::new(x + box_width / 2.0, y - level_height / 2.0),
+                            color.to_string(),
+                            width,
+                        )
+                        .draw(ctx),
+                    }
+                }
+            }
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+}
@@ -0,0 +1,306 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{
+    animation::Tween,
+    canvas::{self, Coordinate, Drawable},
+    data_structures::{
+        binary_search_tree::BinarySearchTree,
+        segment_tree::SegmentTree,
+        trie::{Trie, ROOT},
+    },
+    renderers::graph::{
+        draw_edge_label, draw_node_with_text_color, draw_notes, EDGE_COLOR, NODE_COLOR,
+        NODE_RADIUS, TEXT_COLOR,
+    },
+    shapes::{line::Line, text::Text},
+};
+
+/// Room left above the tree for the notes.
+const NOTES_HEIGHT: f64 = 64.0;
+const MARGIN: f64 = 24.0;
+/// Levels are spread over the height of the canvas up to this spacing.
+const MAX_LEVEL_SPACING: f64 = 72.0;
+const EDGE_WIDTH: f64 = 1.5;
+const HIGHLIGHTED_EDGE_WIDTH: f64 = 3.5;
+
+/// A node of a `TreeView`, placed on a grid of in-order columns and depth
+/// rows that is scaled to the canvas when drawn.
+#[derive(Clone, Debug)]
+pub struct TreeNodeView {
+    pub label: String,
+    pub column: f64,
+    pub row: f64,
+    pub parent: Option<usize>,
+    pub color: &'static str,
+    pub label_color: &'static str,
+    /// Color of the edge up to the parent.
+    pub edge_color: &'static str,
+    /// Drawn at the middle of the edge up to the parent, e.g. the
+    /// characters a trie edge stands for.
+    pub edge_label: String,
+    /// Drawn under the node, e.g. a balance factor.
+    pub annotation: String,
+}
+
+/// One frame of a tree visualization. Nodes are indexed by their
+/// slot in the tree, `None` marking empty slots.
+#[derive(Clone, Debug)]
+pub struct TreeView {
+    pub nodes: Vec<Option<TreeNodeView>>,
+    /// Size of the grid the nodes are placed on.
+    pub columns: f64,
+    pub rows: f64,
+    /// Lines of text in the top left corner of the canvas.
+    pub notes: Vec<String>,
+}
+
+impl TreeView {
+    /// Lays `tree` out with every node in the column of its in-order rank
+    /// and the row of its depth, so keys read left to right in order.
+    pub fn new(tree: &BinarySearchTree) -> TreeView {
+        let mut nodes = vec![None; tree.capacity()];
+        let order = tree.in_order();
+
+        for (column, &id) in order.iter().enumerate() {
+            let node = tree.node(id);
+
+            nodes[id] = Some(TreeNodeView {
+                label: node.key.to_string(),
+                column: column as f64,
+                row: tree.depth(id) as f64,
+                parent: node.parent,
+                color: NODE_COLOR,
+                label_color: TEXT_COLOR,
+                edge_color: EDGE_COLOR,
+                edge_label: String::new(),
+                annotation: String::new(),
+            });
+        }
+
+        TreeView {
+            nodes,
+            columns: order.len() as f64,
+            rows: tree.height() as f64,
+            notes: vec![],
+        }
+    }
+
+    /// Lays `trie` out with its leaves in consecutive columns in sorted
+    /// order and every other node centered over its children. Edges carry
+    /// their characters and nodes ending a word are marked `$`.
+    pub fn trie(trie: &Trie) -> TreeView {
+        let mut nodes: Vec<Option<TreeNodeView>> = vec![None; trie.capacity()];
+        let mut columns = vec![0.0; trie.capacity()];
+        let mut leaves = 0;
+        let mut rows = 0;
+        let order = trie.preorder(ROOT);
+
+        for &id in &order {
+            if trie.node(id).children.is_empty() {
+                columns[id] = leaves as f64;
+                leaves += 1;
+            }
+        }
+
+        // Children come after their parent in preorder, so walking it
+        // backwards places every child before its parent.
+        for &id in order.iter().rev() {
+            let node = trie.node(id);
+            let column = match (node.children.first(), node.children.last()) {
+                (Some(&first), Some(&last)) => (columns[first] + columns[last]) / 2.0,
+                _ => columns[id],
+            };
+
+            columns[id] = column;
+
+            let depth = trie.depth(id);
+
+            rows = rows.max(depth + 1);
+
+            nodes[id] = Some(TreeNodeView {
+                label: if node.terminal { "$" } else { "" }.to_string(),
+                column,
+                row: depth as f64,
+                parent: node.parent,
+                color: NODE_COLOR,
+                label_color: TEXT_COLOR,
+                edge_color: EDGE_COLOR,
+                edge_label: node.label.clone(),
+                annotation: String::new(),
+            });
+        }
+
+        TreeView {
+            nodes,
+            columns: leaves as f64,
+            rows: rows as f64,
+            notes: vec![],
+        }
+    }
+
+    /// Lays `tree` out with its leaves in array order and every node
+    /// centered over its interval. Nodes show their sum, with the interval
+    /// and any addition still pending for the children written below.
+    pub fn segment_tree(tree: &SegmentTree) -> TreeView {
+        let mut nodes = vec![None; tree.capacity()];
+
+        for node in tree.nodes() {
+            let (lo, hi) = tree.interval(node);
+            let mut annotation = if lo == hi {
+                lo.to_string()
+            } else {
+                format!("{}–{}", lo, hi)
+            };
+
+            if tree.pending(node) != 0 {
+                annotation += &format!(" {:+}", tree.pending(node));
+            }
+
+            nodes[node] = Some(TreeNodeView {
+                label: tree.sum(node).to_string(),
+                column: (lo + hi) as f64 / 2.0,
+                row: SegmentTree::depth(node) as f64,
+                parent: Some(node / 2).filter(|&parent| parent > 0),
+                color: NODE_COLOR,
+                label_color: TEXT_COLOR,
+                edge_color: EDGE_COLOR,
+                edge_label: String::new(),
+                annotation,
+            });
+        }
+
+        TreeView {
+            nodes,
+            columns: tree.len() as f64,
+            rows: tree.height() as f64,
+            notes: vec![],
+        }
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> &mut TreeNodeView {
+        self.nodes[id].as_mut().expect("no node in this slot")
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        if let Some(canvas) = ctx.canvas() {
+            let column_width = (canvas.width() as f64 - 2.0 * MARGIN) / self.columns.max(1.0);
+            let row_height = ((canvas.height() as f64 - NOTES_HEIGHT - MARGIN)
+                / self.rows.max(1.0))
+            .min(MAX_LEVEL_SPACING);
+
+            let position = |node: &TreeNodeView| {
+                Coordinate::new(
+                    MARGIN + (node.column + 0.5) * column_width,
+                    NOTES_HEIGHT + (node.row + 0.5) * row_height,
+                )
+            };
+
+            for node in self.nodes.iter().flatten() {
+                let Some(parent) = node.parent.and_then(|parent| self.nodes[parent].as_ref())
+                else {
+                    continue;
+                };
+
+                Line::new(
+                    position(parent),
+                    position(node),
+                    node.edge_color.to_string(),
+                    if node.edge_color == EDGE_COLOR {
+                        EDGE_WIDTH
+                    } else {
+                        HIGHLIGHTED_EDGE_WIDTH
+                    },
+                )
+                .draw(ctx);
+            }
+
+            for node in self.nodes.iter().flatten() {
+                let Some(parent) = node.parent.and_then(|parent| self.nodes[parent].as_ref())
+                else {
+                    continue;
+                };
+
+                if !node.edge_label.is_empty() {
+                    let (from, to) = (position(parent), position(node));
+
+                    draw_edge_label(
+                        ctx,
+                        &node.edge_label,
+                        Coordinate::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0),
+                    );
+                }
+            }
+
+            for node in self.nodes.iter().flatten() {
+                let coordinate = position(node);
+
+                draw_node_with_text_color(
+                    ctx,
+                    &coordinate,
+                    node.color,
+                    node.label_color,
+                    &node.label,
+                );
+
+                if !node.annotation.is_empty() {
+                    Text::centered(
+                        node.annotation.clone(),
+                        Coordinate::new(coordinate.x, coordinate.y + NODE_RADIUS + 9.0),
+                        TEXT_COLOR.to_string(),
+                    )
+                    .draw(ctx);
+                }
+            }
+        }
+
+        draw_notes(ctx, &self.notes);
+    }
+}
+
+impl Tween for TreeView {
+    /// The view `t` of the way from `self` to `to`, with every node in
+    /// both views moved along a straight line. Everything else, and nodes
+    /// in only one of them, are taken from `to`.
+    fn interpolate(&self, to: &TreeView, t: f64) -> TreeView {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        let nodes = to
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, node)| {
+                let mut node = node.clone()?;
+
+                if let Some(Some(from)) = self.nodes.get(id) {
+                    node.column = lerp(from.column, node.column);
+                    node.row = lerp(from.row, node.row);
+                }
+
+                Some(node)
+            })
+            .collect();
+
+        TreeView {
+            nodes,
+            columns: lerp(self.columns, to.columns),
+            rows: lerp(self.rows, to.rows),
+            notes: to.notes.clone(),
+        }
+    }
+
+    /// Whether any node sits somewhere else in `other`.
+    fn moves_to(&self, other: &TreeView) -> bool {
+        self.columns != other.columns
+            || self.rows != other.rows
+            || self
+                .nodes
+                .iter()
+                .zip(other.nodes.iter())
+                .any(|pair| match pair {
+                    (Some(a), Some(b)) => a.column != b.column || a.row != b.row,
+                    _ => false,
+                })
+    }
+}
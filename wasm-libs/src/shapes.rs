@@ -1,2 +1,5 @@
 pub mod circle;
+pub mod line;
+pub mod segmented_box;
 pub mod square;
+pub mod text;
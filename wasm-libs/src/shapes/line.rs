@@ -0,0 +1,69 @@
+use crate::canvas::{Coordinate, Drawable};
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+const ARROW_HEAD_LENGTH: f64 = 10.0;
+const ARROW_HEAD_ANGLE: f64 = std::f64::consts::PI / 7.0;
+
+#[derive(Clone, Debug)]
+pub struct Line {
+    from: Coordinate,
+    to: Coordinate,
+    color: String,
+    width: f64,
+    arrow_head: bool,
+}
+
+impl Line {
+    pub fn new(from: Coordinate, to: Coordinate, color: String, width: f64) -> Line {
+        Line {
+            from,
+            to,
+            color,
+            width,
+            arrow_head: false,
+        }
+    }
+
+    pub fn arrow(from: Coordinate, to: Coordinate, color: String, width: f64) -> Line {
+        Line {
+            arrow_head: true,
+            ..Line::new(from, to, color, width)
+        }
+    }
+}
+
+impl Drawable for Line {
+    fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        let color = JsValue::from_str(self.color.as_str());
+
+        ctx.set_global_alpha(1.0);
+        ctx.set_stroke_style(&color);
+        ctx.set_line_width(self.width);
+
+        ctx.begin_path();
+        ctx.move_to(self.from.x, self.from.y);
+        ctx.line_to(self.to.x, self.to.y);
+        ctx.stroke();
+
+        if self.arrow_head {
+            let angle = (self.to.y - self.from.y).atan2(self.to.x - self.from.x);
+
+            ctx.set_fill_style(&color);
+            ctx.begin_path();
+            ctx.move_to(self.to.x, self.to.y);
+            ctx.line_to(
+                self.to.x - ARROW_HEAD_LENGTH * (angle - ARROW_HEAD_ANGLE).cos(),
+                self.to.y - ARROW_HEAD_LENGTH * (angle - ARROW_HEAD_ANGLE).sin(),
+            );
+            ctx.line_to(
+                self.to.x - ARROW_HEAD_LENGTH * (angle + ARROW_HEAD_ANGLE).cos(),
+                self.to.y - ARROW_HEAD_LENGTH * (angle + ARROW_HEAD_ANGLE).sin(),
+            );
+            ctx.close_path();
+            ctx.fill();
+        }
+
+        ctx.set_line_width(1.0);
+    }
+}
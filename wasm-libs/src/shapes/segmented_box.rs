@@ -0,0 +1,105 @@
+use crate::canvas::{Coordinate, Drawable};
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+const FONT: &str = "12px sans-serif";
+
+/// A row of boxes sharing their sides, each with its own text and fill,
+/// e.g. the keys of a B-tree node. A box without segments is drawn as one
+/// empty segment.
+#[derive(Clone, Debug)]
+pub struct SegmentedBox {
+    center: Coordinate,
+    /// Text and fill color of every segment, left to right.
+    segments: Vec<(String, String)>,
+    segment_width: f64,
+    height: f64,
+    border_color: String,
+    text_color: String,
+}
+
+impl SegmentedBox {
+    pub fn new(
+        center: Coordinate,
+        segments: Vec<(String, String)>,
+        segment_width: f64,
+        height: f64,
+        border_color: String,
+        text_color: String,
+    ) -> SegmentedBox {
+        SegmentedBox {
+            center,
+            segments,
+            segment_width,
+            height,
+            border_color,
+            text_color,
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.segments.len().max(1) as f64 * self.segment_width
+    }
+
+    fn left(&self) -> f64 {
+        self.center.x - self.width() / 2.0
+    }
+
+    /// The middle of the top side, where an edge from above ends.
+    pub fn top(&self) -> Coordinate {
+        Coordinate::new(self.center.x, self.center.y - self.height / 2.0)
+    }
+
+    /// The point on the bottom side at the border between the segments
+    /// `boundary - 1` and `boundary`, from the left corner at 0 to the
+    /// right corner at the number of segments. Edges to the children of a
+    /// B-tree node leave from these. A box without segments has only its
+    /// middle.
+    pub fn anchor(&self, boundary: usize) -> Coordinate {
+        let y = self.center.y + self.height / 2.0;
+
+        if self.segments.is_empty() {
+            Coordinate::new(self.center.x, y)
+        } else {
+            Coordinate::new(self.left() + boundary as f64 * self.segment_width, y)
+        }
+    }
+}
+
+impl Drawable for SegmentedBox {
+    fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        let top = self.center.y - self.height / 2.0;
+        let left = self.left();
+
+        ctx.set_global_alpha(1.0);
+        ctx.set_font(FONT);
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+
+        for (index, (content, color)) in self.segments.iter().enumerate() {
+            let x = left + index as f64 * self.segment_width;
+
+            ctx.set_fill_style(&JsValue::from_str(color.as_str()));
+            ctx.fill_rect(x, top, self.segment_width, self.height);
+            ctx.set_fill_style(&JsValue::from_str(self.text_color.as_str()));
+
+            let _ = ctx.fill_text(content, x + self.segment_width / 2.0, self.center.y);
+        }
+
+        ctx.set_stroke_style(&JsValue::from_str(self.border_color.as_str()));
+        ctx.set_line_width(1.5);
+        ctx.stroke_rect(left, top, self.width(), self.height);
+        ctx.set_line_width(1.0);
+
+        ctx.begin_path();
+
+        for index in 1..self.segments.len() {
+            let x = left + index as f64 * self.segment_width;
+
+            ctx.move_to(x, top);
+            ctx.line_to(x, top + self.height);
+        }
+
+        ctx.stroke();
+    }
+}
@@ -0,0 +1,47 @@
+use crate::canvas::{Coordinate, Drawable};
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+const FONT: &str = "12px sans-serif";
+
+#[derive(Clone, Debug)]
+pub struct Text {
+    content: String,
+    coordinate: Coordinate,
+    color: String,
+    align: &'static str,
+}
+
+impl Text {
+    /// Text centered horizontally and vertically on `coordinate`.
+    pub fn centered(content: String, coordinate: Coordinate, color: String) -> Text {
+        Text {
+            content,
+            coordinate,
+            color,
+            align: "center",
+        }
+    }
+
+    /// Text starting at `coordinate`, vertically centered on it.
+    pub fn left_aligned(content: String, coordinate: Coordinate, color: String) -> Text {
+        Text {
+            content,
+            coordinate,
+            color,
+            align: "left",
+        }
+    }
+}
+
+impl Drawable for Text {
+    fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        ctx.set_global_alpha(1.0);
+        ctx.set_font(FONT);
+        ctx.set_text_align(self.align);
+        ctx.set_text_baseline("middle");
+        ctx.set_fill_style(&JsValue::from_str(self.color.as_str()));
+
+        let _ = ctx.fill_text(&self.content, self.coordinate.x, self.coordinate.y);
+    }
+}
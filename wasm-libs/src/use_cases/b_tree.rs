@@ -0,0 +1,411 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::{Animation, TweenRecorder, STEP_TICKS},
+    canvas,
+    data_structures::b_tree::{BTree, BTreeStep},
+    renderers::{
+        b_tree::BTreeView,
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR},
+    },
+    utils::set_panic_hook,
+};
+
+/// Wider nodes no longer fit next to each other on the canvas.
+const MAX_MIN_DEGREE: usize = 8;
+
+/// A B-tree with the frames recorded while operating on it. Operations run
+/// on a copy first and their steps are replayed here one frame each.
+struct Recorder {
+    tree: BTree,
+    /// Nodes gone down through by the current operation.
+    path: Vec<usize>,
+    splits: usize,
+    borrows: usize,
+    merges: usize,
+    /// A line of notes describing the whole operation.
+    summary: String,
+    frames: TweenRecorder<BTreeView>,
+}
+
+impl Recorder {
+    fn new(tree: BTree) -> Recorder {
+        Recorder {
+            tree,
+            path: vec![],
+            splits: 0,
+            borrows: 0,
+            merges: 0,
+            summary: String::new(),
+            frames: TweenRecorder::new(),
+        }
+    }
+
+    /// The keys of `id`, e.g. `[3 7 9]`.
+    fn keys(&self, id: usize) -> String {
+        let keys: Vec<String> = self
+            .tree
+            .node(id)
+            .keys
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+
+        format!("[{}]", keys.join(" "))
+    }
+
+    /// Runs `operation` on a copy of the tree and replays its steps,
+    /// returning what it returned.
+    fn operate(
+        &mut self,
+        summary: String,
+        operation: impl FnOnce(&mut BTree) -> (bool, Vec<BTreeStep>),
+    ) -> bool {
+        let mut tree = self.tree.clone();
+        let (changed, steps) = operation(&mut tree);
+
+        self.summary = summary;
+        self.path.clear();
+
+        for step in steps {
+            self.run(step);
+        }
+
+        self.path.clear();
+
+        changed
+    }
+
+    fn run(&mut self, step: BTreeStep) {
+        let t = self.tree.min_degree();
+        let message = match step {
+            BTreeStep::Visit(id) => format!("look through {}", self.keys(id)),
+            BTreeStep::Found(id, index) => {
+                format!(
+                    "found {} in {}",
+                    self.tree.node(id).keys[index],
+                    self.keys(id)
+                )
+            }
+            BTreeStep::Grow(_) => format!(
+                "the root {} is full: put a new empty root above it",
+                self.keys(self.tree.root())
+            ),
+            BTreeStep::Split { parent, index, .. } => {
+                let child = self.tree.node(parent).children[index];
+
+                format!(
+                    "{} is full: split it and move its median {} up",
+                    self.keys(child),
+                    self.tree.node(child).keys[t - 1]
+                )
+            }
+            BTreeStep::InsertKey(id, _, key) => {
+                format!("insert {} into the leaf {}", key, self.keys(id))
+            }
+            BTreeStep::RemoveKey(id, index) => format!(
+                "remove {} from the leaf {}",
+                self.tree.node(id).keys[index],
+                self.keys(id)
+            ),
+            BTreeStep::ReplaceKey(id, index, key) => {
+                let old = self.tree.node(id).keys[index];
+                let (name, side) = if key < old {
+                    ("predecessor", "left")
+                } else {
+                    ("successor", "right")
+                };
+
+                format!(
+                    "{} is in an inner node: overwrite it with its {} {}, then remove {} from the {} subtree",
+                    old, name, key, key, side
+                )
+            }
+            BTreeStep::BorrowLeft { parent, index } => {
+                let node = self.tree.node(parent);
+                let left = node.children[index - 1];
+
+                format!(
+                    "{} has only {} keys: move {} down into it and {} up from its left sibling",
+                    self.keys(node.children[index]),
+                    t - 1,
+                    node.keys[index - 1],
+                    self.tree.node(left).keys.last().unwrap()
+                )
+            }
+            BTreeStep::BorrowRight { parent, index } => {
+                let node = self.tree.node(parent);
+                let right = node.children[index + 1];
+
+                format!(
+                    "{} has only {} keys: move {} down into it and {} up from its right sibling",
+                    self.keys(node.children[index]),
+                    t - 1,
+                    node.keys[index],
+                    self.tree.node(right).keys[0]
+                )
+            }
+            BTreeStep::Merge { parent, index } => {
+                let node = self.tree.node(parent);
+
+                format!(
+                    "{} and {} have {} keys each: merge them around {}",
+                    self.keys(node.children[index]),
+                    self.keys(node.children[index + 1]),
+                    t - 1,
+                    node.keys[index]
+                )
+            }
+            BTreeStep::Shrink => format!(
+                "the root ran out of keys: its only child {} becomes the root",
+                self.keys(self.tree.node(self.tree.root()).children[0])
+            ),
+        };
+
+        self.tree.apply(step);
+
+        match step {
+            BTreeStep::Visit(id) => self.path.push(id),
+            BTreeStep::Split { .. } => self.splits += 1,
+            BTreeStep::BorrowLeft { .. } | BTreeStep::BorrowRight { .. } => self.borrows += 1,
+            BTreeStep::Merge { .. } => self.merges += 1,
+            _ => {}
+        }
+
+        let mut view = self.view(message);
+
+        match step {
+            BTreeStep::Visit(id) => view.mark(id, CURRENT_COLOR),
+            BTreeStep::Found(id, index) => view.node_mut(id).key_colors[index] = PATH_COLOR,
+            BTreeStep::Grow(id) => {
+                view.node_mut(self.tree.node(id).children[0]).edge_color = CURRENT_COLOR
+            }
+            BTreeStep::Split {
+                parent,
+                index,
+                sibling,
+            } => {
+                view.mark(self.tree.node(parent).children[index], ACTIVE_COLOR);
+                view.mark(sibling, ACTIVE_COLOR);
+                view.node_mut(parent).key_colors[index] = HIGHLIGHT_COLOR;
+            }
+            BTreeStep::InsertKey(id, index, _) => {
+                view.node_mut(id).key_colors[index] = RELAXED_COLOR
+            }
+            BTreeStep::RemoveKey(id, _) => view.mark(id, ACTIVE_COLOR),
+            BTreeStep::ReplaceKey(id, index, _) => {
+                view.node_mut(id).key_colors[index] = HIGHLIGHT_COLOR
+            }
+            BTreeStep::BorrowLeft { parent, index } => {
+                let child = self.tree.node(parent).children[index];
+
+                view.mark(self.tree.node(parent).children[index - 1], ACTIVE_COLOR);
+                view.node_mut(parent).key_colors[index - 1] = HIGHLIGHT_COLOR;
+                view.node_mut(child).key_colors[0] = RELAXED_COLOR;
+            }
+            BTreeStep::BorrowRight { parent, index } => {
+                let child = self.tree.node(parent).children[index];
+                let last = self.tree.node(child).keys.len() - 1;
+
+                view.mark(self.tree.node(parent).children[index + 1], ACTIVE_COLOR);
+                view.node_mut(parent).key_colors[index] = HIGHLIGHT_COLOR;
+                view.node_mut(child).key_colors[last] = RELAXED_COLOR;
+            }
+            BTreeStep::Merge { parent, index } => {
+                view.mark(self.tree.node(parent).children[index], RELAXED_COLOR)
+            }
+            BTreeStep::Shrink => view.mark(self.tree.root(), ACTIVE_COLOR),
+        }
+
+        self.record(view);
+    }
+
+    /// The tree with `message`, its shape and the nodes gone through so
+    /// far in the notes.
+    fn view(&self, message: String) -> BTreeView {
+        let t = self.tree.min_degree();
+        let mut view = BTreeView::new(&self.tree);
+
+        for &id in &self.path {
+            if let Some(node) = view.nodes[id].as_mut() {
+                node.edge_color = PATH_COLOR;
+            }
+        }
+
+        view.notes = vec![
+            message,
+            format!(
+                "minimum degree {}: {} to {} keys per node; {} keys, height {}",
+                t,
+                t - 1,
+                2 * t - 1,
+                self.tree.len(),
+                self.tree.height()
+            ),
+            format!(
+                "splits: {}, borrows: {}, merges: {}",
+                self.splits, self.borrows, self.merges
+            ),
+            self.summary.clone(),
+        ];
+
+        view
+    }
+
+    /// Records `view`, tweening node moves since the last frame.
+    fn record(&mut self, view: BTreeView) {
+        self.frames.record(view);
+    }
+
+    fn insert(&mut self, key: i32) -> bool {
+        let inserted = self.operate(format!("insert {}", key), |tree| tree.insert(key));
+        let view = self.view(if inserted {
+            format!("done: inserted {}", key)
+        } else {
+            format!("{} is in the tree already", key)
+        });
+
+        self.record(view);
+
+        inserted
+    }
+
+    fn remove(&mut self, key: i32) -> bool {
+        let removed = self.operate(format!("remove {}", key), |tree| tree.remove(key));
+        let view = self.view(if removed {
+            format!("done: removed {}", key)
+        } else {
+            format!("{} is not in the tree", key)
+        });
+
+        self.record(view);
+
+        removed
+    }
+
+    fn search(&mut self, key: i32) -> bool {
+        let found = self.operate(format!("search {}", key), |tree| tree.search(key));
+
+        if !found {
+            let view = self.view(format!("{} is not in the tree", key));
+
+            self.record(view);
+        }
+
+        found
+    }
+}
+
+/// A B-tree of a configurable minimum degree with every node drawn as a
+/// box of keys, animating the splits of inserts and the borrows and merges
+/// of removals.
+#[wasm_bindgen]
+pub struct BTreeVisualization {
+    recorder: Recorder,
+    animation: Animation<BTreeView>,
+}
+
+#[wasm_bindgen]
+impl BTreeVisualization {
+    /// Inserts `key`, returning `false` if it was already there.
+    pub fn insert(&mut self, key: i32) -> bool {
+        let inserted = self.recorder.insert(key);
+        self.flush();
+
+        inserted
+    }
+
+    /// Removes `key`, returning `false` if it was not there.
+    pub fn remove(&mut self, key: i32) -> bool {
+        let removed = self.recorder.remove(key);
+        self.flush();
+
+        removed
+    }
+
+    pub fn search(&mut self, key: i32) -> bool {
+        let found = self.recorder.search(key);
+        self.flush();
+
+        found
+    }
+
+    /// The keys in order.
+    pub fn keys(&self) -> Vec<i32> {
+        self.recorder.tree.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.recorder.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recorder.tree.is_empty()
+    }
+
+    pub fn height(&self) -> usize {
+        self.recorder.tree.height()
+    }
+
+    pub fn min_degree(&self) -> usize {
+        self.recorder.tree.min_degree()
+    }
+
+    /// Node splits made since the tree was created.
+    pub fn splits(&self) -> usize {
+        self.recorder.splits
+    }
+
+    /// Keys borrowed from siblings since the tree was created.
+    pub fn borrows(&self) -> usize {
+        self.recorder.borrows
+    }
+
+    /// Node merges made since the tree was created.
+    pub fn merges(&self) -> usize {
+        self.recorder.merges
+    }
+
+    fn flush(&mut self) {
+        self.animation.push_held(self.recorder.frames.take());
+    }
+}
+
+/// Starts a visualization of a B-tree with minimum degree `min_degree`,
+/// animating the insertion of `keys` one after another.
+#[wasm_bindgen]
+pub fn run_b_tree(
+    document_id: &str,
+    min_degree: usize,
+    keys: Vec<i32>,
+) -> Result<BTreeVisualization, JsValue> {
+    set_panic_hook();
+
+    if !(2..=MAX_MIN_DEGREE).contains(&min_degree) {
+        return Err(JsValue::from_str(&format!(
+            "minimum degree {} is not within [2, {}]",
+            min_degree, MAX_MIN_DEGREE
+        )));
+    }
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(STEP_TICKS, move |view: &BTreeView| view.draw(&ctx));
+
+    let mut visualization = BTreeVisualization {
+        recorder: Recorder::new(BTree::new(min_degree)),
+        animation,
+    };
+    let view = visualization
+        .recorder
+        .view(format!("empty B-tree with minimum degree {}", min_degree));
+
+    visualization.recorder.record(view);
+
+    for key in keys {
+        visualization.recorder.insert(key);
+    }
+
+    visualization.flush();
+
+    Ok(visualization)
+}
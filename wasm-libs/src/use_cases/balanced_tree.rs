@@ -0,0 +1,509 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::{Animation, TweenRecorder, STEP_TICKS},
+    canvas,
+    data_structures::binary_search_tree::BinarySearchTree,
+    renderers::{
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, PATH_COLOR},
+        tree::TreeView,
+    },
+    utils::set_panic_hook,
+};
+
+mod avl;
+mod red_black;
+
+const RED_NODE_COLOR: &str = "#FF6B6B";
+const BLACK_NODE_COLOR: &str = "#333333";
+const BLACK_NODE_TEXT_COLOR: &str = "#FFFFFF";
+
+const KINDS: [&str; 2] = ["avl", "red-black"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Avl,
+    RedBlack,
+}
+
+/// A self-balancing search tree together with the frames recorded while
+/// operating on it. The balancing rules of each kind live in their own
+/// module, on top of the rotations of `BinarySearchTree`.
+struct BalancedTree {
+    kind: Kind,
+    tree: BinarySearchTree,
+    /// Height of the subtree of every slot, for AVL trees.
+    heights: Vec<i32>,
+    /// Color of every slot, for red-black trees.
+    red: Vec<bool>,
+    rotations: usize,
+    /// The fix-up cases applied by the last operation.
+    cases: Vec<String>,
+    frames: TweenRecorder<TreeView>,
+}
+
+impl BalancedTree {
+    fn new(kind: Kind) -> BalancedTree {
+        BalancedTree {
+            kind,
+            tree: BinarySearchTree::new(),
+            heights: vec![],
+            red: vec![],
+            rotations: 0,
+            cases: vec![],
+            frames: TweenRecorder::new(),
+        }
+    }
+
+    fn height(&self, id: Option<usize>) -> i32 {
+        id.map_or(0, |id| self.heights[id])
+    }
+
+    fn is_red(&self, id: Option<usize>) -> bool {
+        id.is_some_and(|id| self.red[id])
+    }
+
+    /// Keeps the per slot attributes as long as the slots of the tree.
+    fn grow(&mut self) {
+        self.heights.resize(self.tree.capacity(), 1);
+        self.red.resize(self.tree.capacity(), false);
+    }
+
+    /// Left height minus right height.
+    fn balance_factor(&self, id: usize) -> i32 {
+        let node = self.tree.node(id);
+
+        self.height(node.left) - self.height(node.right)
+    }
+
+    fn rotate_left(&mut self, id: usize) -> usize {
+        self.rotations += 1;
+        self.tree.rotate_left(id)
+    }
+
+    fn rotate_right(&mut self, id: usize) -> usize {
+        self.rotations += 1;
+        self.tree.rotate_right(id)
+    }
+
+    /// Notes a fix-up case and records a frame announcing it.
+    fn apply_case(&mut self, roles: &[(usize, &str)], case: String) {
+        self.cases.push(case.clone());
+        self.record(&[], roles, case);
+    }
+
+    /// Records the tree with the edges down `path` highlighted and the
+    /// nodes of `roles` marked, red-black nodes by name and AVL nodes by
+    /// color too. Node movements since the last frame are tweened.
+    fn record(&mut self, path: &[usize], roles: &[(usize, &str)], message: String) {
+        let mut view = TreeView::new(&self.tree);
+
+        for id in self.tree.in_order() {
+            let node = view.node_mut(id);
+
+            match self.kind {
+                Kind::Avl => {
+                    node.annotation = format!("{:+}", self.balance_factor(id));
+                }
+                Kind::RedBlack if self.red[id] => node.color = RED_NODE_COLOR,
+                Kind::RedBlack => {
+                    node.color = BLACK_NODE_COLOR;
+                    node.label_color = BLACK_NODE_TEXT_COLOR;
+                }
+            }
+        }
+
+        for (step, &id) in path.iter().enumerate() {
+            if step > 0 {
+                view.node_mut(id).edge_color = PATH_COLOR;
+            }
+
+            if self.kind == Kind::Avl {
+                view.node_mut(id).color = ACTIVE_COLOR;
+            }
+        }
+
+        for &(id, role) in roles {
+            let node = view.node_mut(id);
+
+            node.annotation = if node.annotation.is_empty() {
+                role.to_string()
+            } else {
+                format!("{} {}", role, node.annotation)
+            };
+
+            if self.kind == Kind::Avl {
+                node.color = CURRENT_COLOR;
+            }
+        }
+
+        view.notes = vec![
+            message,
+            format!(
+                "size: {}, height: {}, rotations: {}",
+                self.tree.len(),
+                self.tree.height(),
+                self.rotations
+            ),
+        ];
+
+        self.frames.record(view);
+    }
+
+    /// Records the comparisons on the way down to `key` and returns the
+    /// nodes compared.
+    fn descend(&mut self, key: i32) -> Vec<usize> {
+        let path = self.tree.search_path(key);
+
+        for step in 0..path.len() {
+            let id = path[step];
+            let other = self.tree.key(id);
+
+            let message = match key.cmp(&other) {
+                std::cmp::Ordering::Less => format!("{} < {}: go left", key, other),
+                std::cmp::Ordering::Greater => format!("{} > {}: go right", key, other),
+                std::cmp::Ordering::Equal => format!("{} = {}", key, other),
+            };
+
+            self.record(&path[..=step], &[(id, "?")], message);
+        }
+
+        path
+    }
+
+    fn insert(&mut self, key: i32) -> bool {
+        self.cases.clear();
+
+        let path = self.descend(key);
+
+        let Some(id) = self.tree.insert(key) else {
+            self.record(&path, &[], format!("{} is already in the tree", key));
+            return false;
+        };
+
+        self.grow();
+
+        match self.kind {
+            Kind::Avl => self.avl_insert_fix_up(id),
+            Kind::RedBlack => self.red_black_insert_fix_up(id),
+        }
+
+        self.record(&[], &[], format!("inserted {}", key));
+
+        true
+    }
+
+    fn remove(&mut self, key: i32) -> bool {
+        self.cases.clear();
+
+        let path = self.descend(key);
+
+        let Some(id) = self.tree.find(key) else {
+            self.record(&path, &[], format!("{} is not in the tree", key));
+            return false;
+        };
+
+        // The node that actually leaves the tree has at most one child.
+        let removed = match self.tree.successor_in_subtree(id) {
+            Some(successor) if self.tree.node(id).left.is_some() => {
+                self.record(
+                    &path,
+                    &[(id, "d"), (successor, "s")],
+                    format!(
+                        "{} has two children: take the key of its successor {}",
+                        key,
+                        self.tree.key(successor)
+                    ),
+                );
+                successor
+            }
+            _ => id,
+        };
+
+        let node = self.tree.node(removed);
+        let (parent, child) = (node.parent, node.left.or(node.right));
+        let removed_red = self.red[removed];
+        let message = if removed == id {
+            format!("unlink {}", key)
+        } else {
+            format!("move {} up and unlink its old node", self.tree.key(removed))
+        };
+
+        self.tree.remove_node(id);
+        self.record(&[], &[], message);
+
+        match self.kind {
+            Kind::Avl => {
+                if let Some(parent) = parent {
+                    self.avl_rebalance(parent);
+                }
+            }
+            Kind::RedBlack => self.red_black_remove_fix_up(child, parent, removed_red),
+        }
+
+        self.record(&[], &[], format!("removed {}", key));
+
+        true
+    }
+
+    fn search(&mut self, key: i32) -> bool {
+        let path = self.descend(key);
+        let found = self.tree.find(key).is_some();
+
+        self.record(
+            &path,
+            &[],
+            if found {
+                format!("found {} after {} comparisons", key, path.len())
+            } else {
+                format!("{} is not in the tree", key)
+            },
+        );
+
+        found
+    }
+}
+
+/// An AVL or red-black tree drawn on a canvas, with rotations animated as
+/// node movements.
+#[wasm_bindgen]
+pub struct BalancedTreeVisualization {
+    tree: BalancedTree,
+    animation: Animation<TreeView>,
+}
+
+#[wasm_bindgen]
+impl BalancedTreeVisualization {
+    /// Inserts `key` and rebalances, returning `false` if it was already
+    /// there.
+    pub fn insert(&mut self, key: i32) -> bool {
+        let inserted = self.tree.insert(key);
+        self.flush();
+
+        inserted
+    }
+
+    /// Removes `key` and rebalances, returning `false` if it was not there.
+    pub fn remove(&mut self, key: i32) -> bool {
+        let removed = self.tree.remove(key);
+        self.flush();
+
+        removed
+    }
+
+    pub fn search(&mut self, key: i32) -> bool {
+        let found = self.tree.search(key);
+        self.flush();
+
+        found
+    }
+
+    /// The keys in order.
+    pub fn keys(&self) -> Vec<i32> {
+        self.tree
+            .tree
+            .in_order()
+            .into_iter()
+            .map(|id| self.tree.tree.key(id))
+            .collect()
+    }
+
+    pub fn height(&self) -> usize {
+        self.tree.tree.height()
+    }
+
+    /// Rotations made since the tree was created.
+    pub fn rotations(&self) -> usize {
+        self.tree.rotations
+    }
+
+    /// The fix-up cases applied by the last insert or remove, in order.
+    pub fn last_cases(&self) -> js_sys::Array {
+        self.tree
+            .cases
+            .iter()
+            .map(|case| JsValue::from_str(case))
+            .collect()
+    }
+
+    fn flush(&mut self) {
+        self.animation.push_held(self.tree.frames.take());
+    }
+}
+
+/// Starts an `"avl"` or `"red-black"` tree visualization, animating the
+/// insertion of `keys` one after another.
+#[wasm_bindgen]
+pub fn run_balanced_tree(
+    document_id: &str,
+    kind: &str,
+    keys: Vec<i32>,
+) -> Result<BalancedTreeVisualization, JsValue> {
+    set_panic_hook();
+
+    let kind = match kind {
+        "avl" => Kind::Avl,
+        "red-black" => Kind::RedBlack,
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown balanced tree `{}`, expected one of {}",
+                kind,
+                KINDS.join(", ")
+            )))
+        }
+    };
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(STEP_TICKS, move |view: &TreeView| view.draw(&ctx));
+
+    let mut visualization = BalancedTreeVisualization {
+        tree: BalancedTree::new(kind),
+        animation,
+    };
+
+    visualization
+        .tree
+        .record(&[], &[], format!("empty {} tree", KINDS[kind as usize]));
+
+    for key in keys {
+        visualization.tree.insert(key);
+    }
+
+    visualization.flush();
+
+    Ok(visualization)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::rng::Rng;
+
+    /// Checks the parent links, the search order and the balancing rules of
+    /// the subtree at `id`, returning its height and black height.
+    fn check_subtree(
+        tree: &BalancedTree,
+        id: Option<usize>,
+        parent: Option<usize>,
+        above: Option<i32>,
+        below: Option<i32>,
+    ) -> (i32, usize) {
+        let Some(id) = id else {
+            return (0, 1);
+        };
+        let node = tree.tree.node(id);
+
+        assert_eq!(node.parent, parent, "parent of {}", node.key);
+        assert!(
+            above.is_none_or(|above| node.key > above),
+            "{} out of order",
+            node.key
+        );
+        assert!(
+            below.is_none_or(|below| node.key < below),
+            "{} out of order",
+            node.key
+        );
+
+        let (left_height, left_black) =
+            check_subtree(tree, node.left, Some(id), above, Some(node.key));
+        let (right_height, right_black) =
+            check_subtree(tree, node.right, Some(id), Some(node.key), below);
+        let height = 1 + left_height.max(right_height);
+
+        match tree.kind {
+            Kind::Avl => {
+                assert_eq!(tree.heights[id], height, "height of {}", node.key);
+                assert!(
+                    (left_height - right_height).abs() <= 1,
+                    "{} is out of balance",
+                    node.key
+                );
+            }
+            Kind::RedBlack => {
+                assert_eq!(left_black, right_black, "black heights under {}", node.key);
+
+                if tree.red[id] {
+                    assert!(
+                        !tree.is_red(node.left) && !tree.is_red(node.right),
+                        "red {} has a red child",
+                        node.key
+                    );
+                }
+            }
+        }
+
+        (height, left_black + usize::from(!tree.red[id]))
+    }
+
+    fn check(tree: &BalancedTree, expected: &BTreeSet<i32>) {
+        let root = tree.tree.root();
+
+        check_subtree(tree, root, None, None, None);
+
+        if tree.kind == Kind::RedBlack {
+            assert!(!tree.is_red(root), "the root is red");
+        }
+
+        let keys = tree
+            .tree
+            .in_order()
+            .into_iter()
+            .map(|id| tree.tree.key(id))
+            .collect::<Vec<_>>();
+
+        assert_eq!(keys, expected.iter().copied().collect::<Vec<_>>());
+    }
+
+    fn random_operations(kind: Kind) {
+        for seed in 0..6 {
+            let mut rng = Rng::new(seed);
+            let mut tree = BalancedTree::new(kind);
+            let mut expected = BTreeSet::new();
+
+            for _ in 0..300 {
+                let key = rng.below(48) as i32;
+
+                if rng.chance(0.55) {
+                    assert_eq!(tree.insert(key), expected.insert(key));
+                } else {
+                    assert_eq!(tree.remove(key), expected.remove(&key));
+                }
+
+                tree.frames.take();
+                check(&tree, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn avl_stays_balanced() {
+        random_operations(Kind::Avl);
+    }
+
+    #[test]
+    fn red_black_keeps_its_colors() {
+        random_operations(Kind::RedBlack);
+    }
+
+    #[test]
+    fn sorted_inserts_stay_logarithmic() {
+        for kind in [Kind::Avl, Kind::RedBlack] {
+            let mut tree = BalancedTree::new(kind);
+
+            for key in 0..127 {
+                tree.insert(key);
+            }
+
+            check(&tree, &(0..127).collect());
+            assert!(
+                tree.tree.height() <= 12,
+                "{:?} height {}",
+                kind,
+                tree.tree.height()
+            );
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::BalancedTree;
+
+impl BalancedTree {
+    pub(super) fn avl_insert_fix_up(&mut self, id: usize) {
+        self.heights[id] = 1;
+        self.record(
+            &[],
+            &[(id, "new")],
+            format!("insert {} as a leaf", self.tree.key(id)),
+        );
+
+        if let Some(parent) = self.tree.node(id).parent {
+            self.avl_rebalance(parent);
+        }
+    }
+
+    /// Walks up from `id` to the root, updating heights and rotating every
+    /// node whose balance factor reached ±2 back into balance.
+    pub(super) fn avl_rebalance(&mut self, id: usize) {
+        let mut current = Some(id);
+
+        while let Some(id) = current {
+            self.update_height(id);
+
+            let key = self.tree.key(id);
+            let balance = self.balance_factor(id);
+
+            let top = if balance > 1 {
+                let left = self.tree.node(id).left.unwrap();
+
+                if self.balance_factor(left) >= 0 {
+                    self.apply_case(
+                        &[(id, "z"), (left, "y")],
+                        format!(
+                            "{} has balance factor {:+}: left-left case, rotate right at {}",
+                            key, balance, key
+                        ),
+                    );
+                } else {
+                    self.apply_case(
+                        &[(id, "z"), (left, "y")],
+                        format!(
+                            "{} has balance factor {:+}: left-right case, rotate left at {} then right at {}",
+                            key,
+                            balance,
+                            self.tree.key(left),
+                            key
+                        ),
+                    );
+                    self.avl_rotate_left(left);
+                    self.record(
+                        &[],
+                        &[(id, "z")],
+                        format!("rotated left at {}", self.tree.key(left)),
+                    );
+                }
+
+                self.avl_rotate_right(id)
+            } else if balance < -1 {
+                let right = self.tree.node(id).right.unwrap();
+
+                if self.balance_factor(right) <= 0 {
+                    self.apply_case(
+                        &[(id, "z"), (right, "y")],
+                        format!(
+                            "{} has balance factor {:+}: right-right case, rotate left at {}",
+                            key, balance, key
+                        ),
+                    );
+                } else {
+                    self.apply_case(
+                        &[(id, "z"), (right, "y")],
+                        format!(
+                            "{} has balance factor {:+}: right-left case, rotate right at {} then left at {}",
+                            key,
+                            balance,
+                            self.tree.key(right),
+                            key
+                        ),
+                    );
+                    self.avl_rotate_right(right);
+                    self.record(
+                        &[],
+                        &[(id, "z")],
+                        format!("rotated right at {}", self.tree.key(right)),
+                    );
+                }
+
+                self.avl_rotate_left(id)
+            } else {
+                id
+            };
+
+            if top != id {
+                self.record(
+                    &[],
+                    &[(top, "y")],
+                    format!("rotated: {} is balanced again", self.tree.key(top)),
+                );
+            }
+
+            current = self.tree.node(top).parent;
+        }
+    }
+
+    fn avl_rotate_left(&mut self, id: usize) -> usize {
+        let top = self.rotate_left(id);
+
+        self.update_height(id);
+        self.update_height(top);
+
+        top
+    }
+
+    fn avl_rotate_right(&mut self, id: usize) -> usize {
+        let top = self.rotate_right(id);
+
+        self.update_height(id);
+        self.update_height(top);
+
+        top
+    }
+
+    fn update_height(&mut self, id: usize) {
+        let node = self.tree.node(id);
+
+        self.heights[id] = 1 + self.height(node.left).max(self.height(node.right));
+    }
+}
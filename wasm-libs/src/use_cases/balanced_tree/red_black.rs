@@ -0,0 +1,287 @@
+use super::BalancedTree;
+
+impl BalancedTree {
+    /// Colors the new leaf red, then pushes red-red violations up the tree
+    /// by recoloring (case 1) or ends them with rotations (cases 2 and 3).
+    pub(super) fn red_black_insert_fix_up(&mut self, id: usize) {
+        self.red[id] = true;
+        self.record(
+            &[],
+            &[(id, "z")],
+            format!("insert {} as a red leaf", self.tree.key(id)),
+        );
+
+        let mut z = id;
+
+        while let Some(mut parent) = self.tree.node(z).parent.filter(|&parent| self.red[parent]) {
+            let grandparent = self
+                .tree
+                .node(parent)
+                .parent
+                .expect("a red node is never the root");
+            let parent_is_left = self.tree.node(grandparent).left == Some(parent);
+            let uncle = if parent_is_left {
+                self.tree.node(grandparent).right
+            } else {
+                self.tree.node(grandparent).left
+            };
+
+            if let Some(uncle) = uncle.filter(|&uncle| self.red[uncle]) {
+                self.apply_case(
+                    &[(z, "z"), (parent, "p"), (uncle, "u"), (grandparent, "g")],
+                    format!(
+                        "case 1: uncle {} is red, color {} and {} black and {} red",
+                        self.tree.key(uncle),
+                        self.tree.key(parent),
+                        self.tree.key(uncle),
+                        self.tree.key(grandparent)
+                    ),
+                );
+
+                self.red[parent] = false;
+                self.red[uncle] = false;
+                self.red[grandparent] = true;
+                self.record(
+                    &[],
+                    &[(grandparent, "z")],
+                    format!("continue from {}", self.tree.key(grandparent)),
+                );
+
+                z = grandparent;
+                continue;
+            }
+
+            let inner = if parent_is_left {
+                self.tree.node(parent).right == Some(z)
+            } else {
+                self.tree.node(parent).left == Some(z)
+            };
+
+            if inner {
+                self.apply_case(
+                    &[(z, "z"), (parent, "p"), (grandparent, "g")],
+                    format!(
+                        "case 2: uncle is black and {} is an inner child, rotate {} at {}",
+                        self.tree.key(z),
+                        if parent_is_left { "left" } else { "right" },
+                        self.tree.key(parent)
+                    ),
+                );
+
+                if parent_is_left {
+                    self.rotate_left(parent);
+                } else {
+                    self.rotate_right(parent);
+                }
+
+                self.record(
+                    &[],
+                    &[(parent, "z"), (z, "p"), (grandparent, "g")],
+                    format!("rotated: {} is now an outer child", self.tree.key(parent)),
+                );
+
+                (z, parent) = (parent, z);
+            }
+
+            self.apply_case(
+                &[(z, "z"), (parent, "p"), (grandparent, "g")],
+                format!(
+                    "case 3: uncle is black and {} is an outer child, color {} black and {} red, rotate {} at {}",
+                    self.tree.key(z),
+                    self.tree.key(parent),
+                    self.tree.key(grandparent),
+                    if parent_is_left { "right" } else { "left" },
+                    self.tree.key(grandparent)
+                ),
+            );
+
+            self.red[parent] = false;
+            self.red[grandparent] = true;
+
+            if parent_is_left {
+                self.rotate_right(grandparent);
+            } else {
+                self.rotate_left(grandparent);
+            }
+
+            self.record(&[], &[], "rotated".to_string());
+            break;
+        }
+
+        if let Some(root) = self.tree.root().filter(|&root| self.red[root]) {
+            self.apply_case(
+                &[(root, "root")],
+                format!("the root {} is red, color it black", self.tree.key(root)),
+            );
+            self.red[root] = false;
+        }
+    }
+
+    /// Restores the black heights after unlinking a node whose place was
+    /// taken by `child` under `parent`. A black node leaves an extra black
+    /// on `child`, which sibling cases 1 to 4 either push up the tree or
+    /// absorb with rotations.
+    pub(super) fn red_black_remove_fix_up(
+        &mut self,
+        child: Option<usize>,
+        parent: Option<usize>,
+        removed_red: bool,
+    ) {
+        if removed_red {
+            self.cases
+                .push("the unlinked node was red: no fix-up needed".to_string());
+            return;
+        }
+
+        let (mut x, mut parent) = (child, parent);
+
+        while x != self.tree.root() && !self.is_red(x) {
+            let p = parent.expect("a node below the root has a parent");
+            let x_is_left = self.tree.node(p).left == x;
+            let mut w = self
+                .sibling(p, x_is_left)
+                .expect("a node with an extra black has a sibling");
+            let name = x.map_or("the empty spot".to_string(), |x| {
+                self.tree.key(x).to_string()
+            });
+            let with_x = |roles: Vec<(usize, &'static str)>| {
+                roles
+                    .into_iter()
+                    .chain(x.map(|x| (x, "x")))
+                    .collect::<Vec<_>>()
+            };
+
+            if self.red[w] {
+                self.apply_case(
+                    &with_x(vec![(w, "w"), (p, "p")]),
+                    format!(
+                        "case 1: sibling {} of {} is red, color it black and {} red, rotate {} at {}",
+                        self.tree.key(w),
+                        name,
+                        self.tree.key(p),
+                        if x_is_left { "left" } else { "right" },
+                        self.tree.key(p)
+                    ),
+                );
+
+                self.red[w] = false;
+                self.red[p] = true;
+                self.rotate_toward(p, x_is_left);
+                self.record(&[], &with_x(vec![(p, "p")]), "rotated".to_string());
+
+                w = self.sibling(p, x_is_left).unwrap();
+            }
+
+            let (near, far) = self.nephews(w, x_is_left);
+
+            if !self.is_red(near) && !self.is_red(far) {
+                self.apply_case(
+                    &with_x(vec![(w, "w"), (p, "p")]),
+                    format!(
+                        "case 2: sibling {} of {} has two black children, color it red and move the extra black up to {}",
+                        self.tree.key(w),
+                        name,
+                        self.tree.key(p)
+                    ),
+                );
+
+                self.red[w] = true;
+                self.record(
+                    &[],
+                    &[(p, "x")],
+                    format!("continue from {}", self.tree.key(p)),
+                );
+
+                x = Some(p);
+                parent = self.tree.node(p).parent;
+                continue;
+            }
+
+            if !self.is_red(far) {
+                let near = near.unwrap();
+
+                self.apply_case(
+                    &with_x(vec![(w, "w"), (p, "p"), (near, "n")]),
+                    format!(
+                        "case 3: sibling {} has a red near child {}, swap their colors and rotate {} at {}",
+                        self.tree.key(w),
+                        self.tree.key(near),
+                        if x_is_left { "right" } else { "left" },
+                        self.tree.key(w)
+                    ),
+                );
+
+                self.red[near] = false;
+                self.red[w] = true;
+                self.rotate_toward(w, !x_is_left);
+                self.record(&[], &with_x(vec![(p, "p")]), "rotated".to_string());
+
+                w = self.sibling(p, x_is_left).unwrap();
+            }
+
+            let far = self.nephews(w, x_is_left).1.unwrap();
+
+            self.apply_case(
+                &with_x(vec![(w, "w"), (p, "p"), (far, "f")]),
+                format!(
+                    "case 4: sibling {} has a red far child {}, give it the color of {}, color {} and {} black, rotate {} at {}",
+                    self.tree.key(w),
+                    self.tree.key(far),
+                    self.tree.key(p),
+                    self.tree.key(p),
+                    self.tree.key(far),
+                    if x_is_left { "left" } else { "right" },
+                    self.tree.key(p)
+                ),
+            );
+
+            self.red[w] = self.red[p];
+            self.red[p] = false;
+            self.red[far] = false;
+            self.rotate_toward(p, x_is_left);
+            self.record(&[], &[], "rotated: the extra black is absorbed".to_string());
+
+            x = self.tree.root();
+        }
+
+        if let Some(x) = x.filter(|&x| self.red[x]) {
+            self.apply_case(
+                &[(x, "x")],
+                format!("{} is red, color it black", self.tree.key(x)),
+            );
+            self.red[x] = false;
+        }
+    }
+
+    /// The child of `parent` on the other side than `left`.
+    fn sibling(&self, parent: usize, left: bool) -> Option<usize> {
+        let node = self.tree.node(parent);
+
+        if left {
+            node.right
+        } else {
+            node.left
+        }
+    }
+
+    /// The children of `w` nearest to and farthest from its sibling on
+    /// side `left`.
+    fn nephews(&self, w: usize, left: bool) -> (Option<usize>, Option<usize>) {
+        let node = self.tree.node(w);
+
+        if left {
+            (node.left, node.right)
+        } else {
+            (node.right, node.left)
+        }
+    }
+
+    /// Rotates at `id` so that it moves down on side `left`.
+    fn rotate_toward(&mut self, id: usize, left: bool) {
+        if left {
+            self.rotate_left(id);
+        } else {
+            self.rotate_right(id);
+        }
+    }
+}
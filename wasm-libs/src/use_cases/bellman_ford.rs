@@ -0,0 +1,311 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::general_graph::GeneralGraph,
+    renderers::graph::{
+        GraphView, ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 20;
+
+#[wasm_bindgen]
+pub struct BellmanFordResult {
+    distances: Vec<f64>,
+    negative_cycle: Vec<usize>,
+}
+
+#[wasm_bindgen]
+impl BellmanFordResult {
+    /// Distance of every node from the source, `Infinity` when unreachable.
+    /// Meaningless for nodes downstream of a negative cycle.
+    pub fn distances(&self) -> Vec<f64> {
+        self.distances.clone()
+    }
+
+    /// Nodes of a negative cycle reachable from the source, in cycle order.
+    pub fn negative_cycle(&self) -> Vec<usize> {
+        self.negative_cycle.clone()
+    }
+
+    pub fn has_negative_cycle(&self) -> bool {
+        !self.negative_cycle.is_empty()
+    }
+}
+
+struct BellmanFord<'a> {
+    graph: &'a GeneralGraph,
+    source: usize,
+    distances: Vec<f64>,
+    predecessor_edges: Vec<Option<usize>>,
+    relaxations: usize,
+    frames: Vec<GraphView>,
+}
+
+impl<'a> BellmanFord<'a> {
+    fn new(graph: &'a GeneralGraph, source: usize) -> BellmanFord<'a> {
+        let mut distances = vec![f64::INFINITY; graph.node_count()];
+        distances[source] = 0.0;
+
+        BellmanFord {
+            graph,
+            source,
+            distances,
+            predecessor_edges: vec![None; graph.node_count()],
+            relaxations: 0,
+            frames: vec![],
+        }
+    }
+
+    /// Every edge as `(edge index, from, to)`; undirected edges go both ways.
+    fn arcs(&self) -> Vec<(usize, usize, usize)> {
+        let mut arcs = vec![];
+
+        for (idx, edge) in self.graph.edges().iter().enumerate() {
+            arcs.push((idx, edge.from, edge.to));
+
+            if !self.graph.is_directed() {
+                arcs.push((idx, edge.to, edge.from));
+            }
+        }
+
+        arcs
+    }
+
+    fn run(mut self) -> (BellmanFordResult, Vec<GraphView>) {
+        let rounds = self.graph.node_count().saturating_sub(1);
+        let arcs = self.arcs();
+
+        self.record(None, &[], "initialize: d(source) = 0".to_string());
+
+        for round in 1..=rounds {
+            let mut changed = false;
+
+            for &(edge, from, to) in arcs.iter() {
+                let weight = self.graph.edges()[edge].weight;
+                let candidate = self.distances[from] + weight;
+                let relaxed = candidate < self.distances[to];
+
+                let message = if relaxed {
+                    format!(
+                        "round {}/{}: d({}) + {} = {} < {}, relax",
+                        round,
+                        rounds,
+                        self.name(from),
+                        weight,
+                        candidate,
+                        format_distance(self.distances[to])
+                    )
+                } else {
+                    format!(
+                        "round {}/{}: {} -> {} does not improve d({})",
+                        round,
+                        rounds,
+                        self.name(from),
+                        self.name(to),
+                        self.name(to)
+                    )
+                };
+
+                if relaxed {
+                    self.distances[to] = candidate;
+                    self.predecessor_edges[to] = Some(edge);
+                    self.relaxations += 1;
+                    changed = true;
+                }
+
+                let color = if relaxed {
+                    RELAXED_COLOR
+                } else {
+                    CURRENT_COLOR
+                };
+                self.record(Some(to), &[(edge, color)], message);
+            }
+
+            if !changed {
+                self.record(
+                    None,
+                    &[],
+                    format!("round {}: nothing changed, stop early", round),
+                );
+                break;
+            }
+        }
+
+        let negative_cycle = self.find_negative_cycle(&arcs);
+
+        if negative_cycle.is_empty() {
+            self.record(None, &[], "final pass: no negative cycle".to_string());
+        } else {
+            let names = negative_cycle
+                .iter()
+                .map(|&node| self.name(node).to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            self.record_negative_cycle(&negative_cycle, format!("negative cycle: {}", names));
+        }
+
+        let result = BellmanFordResult {
+            distances: self.distances,
+            negative_cycle,
+        };
+
+        (result, self.frames)
+    }
+
+    /// Runs the extra pass over all edges. An edge that still relaxes lies on
+    /// or behind a negative cycle; walking predecessors `n` times from it is
+    /// guaranteed to land on the cycle itself.
+    fn find_negative_cycle(&mut self, arcs: &[(usize, usize, usize)]) -> Vec<usize> {
+        for &(edge, from, to) in arcs.iter() {
+            let weight = self.graph.edges()[edge].weight;
+
+            if self.distances[from] + weight >= self.distances[to] {
+                continue;
+            }
+
+            self.record(
+                Some(to),
+                &[(edge, HIGHLIGHT_COLOR)],
+                format!(
+                    "final pass: {} -> {} still relaxes",
+                    self.name(from),
+                    self.name(to)
+                ),
+            );
+
+            self.predecessor_edges[to] = Some(edge);
+
+            let mut node = to;
+            for _ in 0..self.graph.node_count() {
+                node = self.predecessor(node);
+            }
+
+            let mut cycle = vec![node];
+            let mut current = self.predecessor(node);
+
+            while current != node {
+                cycle.push(current);
+                current = self.predecessor(current);
+            }
+
+            cycle.reverse();
+
+            return cycle;
+        }
+
+        vec![]
+    }
+
+    fn predecessor(&self, node: usize) -> usize {
+        let edge = self.predecessor_edges[node].expect("node on a relaxed path has a predecessor");
+
+        self.graph.edges()[edge].other(node)
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.graph.vertices()[node].label
+    }
+
+    fn base_view(&self) -> GraphView {
+        let mut view = GraphView::with_weights(self.graph);
+
+        for node in 0..self.graph.node_count() {
+            view.node_labels[node] = format_distance(self.distances[node]);
+
+            if self.distances[node].is_finite() {
+                view.node_colors[node] = ACTIVE_COLOR;
+            }
+
+            if let Some(edge) = self.predecessor_edges[node] {
+                view.edge_colors[edge] = PATH_COLOR;
+            }
+        }
+
+        view.node_colors[self.source] = RELAXED_COLOR;
+
+        view
+    }
+
+    fn record(&mut self, current: Option<usize>, edges: &[(usize, &'static str)], message: String) {
+        let mut view = self.base_view();
+
+        if let Some(node) = current {
+            view.node_colors[node] = CURRENT_COLOR;
+        }
+
+        for &(edge, color) in edges {
+            view.edge_colors[edge] = color;
+        }
+
+        view.notes = vec![message, format!("relaxations: {}", self.relaxations)];
+
+        self.frames.push(view);
+    }
+
+    fn record_negative_cycle(&mut self, cycle: &[usize], message: String) {
+        let mut view = self.base_view();
+
+        for (position, &node) in cycle.iter().enumerate() {
+            let next = cycle[(position + 1) % cycle.len()];
+
+            view.node_colors[node] = HIGHLIGHT_COLOR;
+
+            if let Some(edge) = self.predecessor_edges[next] {
+                view.edge_colors[edge] = HIGHLIGHT_COLOR;
+            }
+        }
+
+        view.notes = vec![message, format!("relaxations: {}", self.relaxations)];
+
+        self.frames.push(view);
+    }
+}
+
+fn format_distance(distance: f64) -> String {
+    if distance.is_finite() {
+        format!("{}", distance)
+    } else {
+        "∞".to_string()
+    }
+}
+
+pub fn bellman_ford(graph: &GeneralGraph, source: usize) -> (BellmanFordResult, Vec<GraphView>) {
+    BellmanFord::new(graph, source).run()
+}
+
+/// Animates Bellman-Ford from `source`, one edge relaxation per step, and
+/// highlights a reachable negative cycle if the final pass finds one.
+#[wasm_bindgen]
+pub fn run_bellman_ford(
+    document_id: &str,
+    graph: &GeneralGraph,
+    source: usize,
+) -> Result<BellmanFordResult, JsValue> {
+    set_panic_hook();
+
+    if source >= graph.node_count() {
+        return Err(JsValue::from_str(&format!(
+            "source node {} out of range, graph has {} nodes",
+            source,
+            graph.node_count()
+        )));
+    }
+
+    let (result, frames) = bellman_ford(graph, source);
+
+    let ctx = canvas::context(document_id);
+    let graph = graph.clone();
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &GraphView| {
+        view.draw(&ctx, &graph)
+    });
+
+    animation.push(frames);
+
+    Ok(result)
+}
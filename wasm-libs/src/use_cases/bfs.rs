@@ -2,8 +2,10 @@ use std::{cell::RefCell, rc::Rc};
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    canvas::{self, request_animation_frame},
+    canvas::{self, request_animation_frame, Coordinate, Drawable},
     data_structures::graph::{Graph, Type},
+    renderers::list::ListView,
+    shapes::text::Text,
 };
 
 const NODE_SIZE: f64 = 20.0;
@@ -14,6 +16,15 @@ const AVAILABLE_COLOR: &str = "#FFFFFF";
 const WALL_COLOR: &str = "#000000";
 const START_COLOR: &str = "#00FF00";
 const END_COLOR: &str = "#0000FF";
+/// Cells waiting in the queue: discovered, but not searched from yet.
+const FRONTIER_COLOR: &str = "#FFA500";
+const PANEL_TEXT_COLOR: &str = "#000000";
+const PANEL_MARGIN: f64 = 10.0;
+const PANEL_LINE_HEIGHT: f64 = 18.0;
+/// Room left above the frontier entries for the panel header.
+const PANEL_HEADER_HEIGHT: f64 = 48.0;
+/// Queue entries drawn before the rest is summed up.
+const QUEUE_SHOWN: usize = 10;
 
 #[wasm_bindgen]
 extern "C" {
@@ -119,6 +130,7 @@ impl Bfs for Graph {
 trait GraphDrawable {
     fn draw_grid(&self, ctx: &web_sys::CanvasRenderingContext2d);
     fn draw_node(&self, ctx: &web_sys::CanvasRenderingContext2d);
+    fn draw_frontier(&self, ctx: &web_sys::CanvasRenderingContext2d);
 }
 
 impl GraphDrawable for Graph {
@@ -154,6 +166,8 @@ impl GraphDrawable for Graph {
             std::slice::from_raw_parts(nodes_ptr, (self.width() * self.height()) as usize)
         };
 
+        let frontier = self.frontier();
+
         ctx.begin_path();
 
         for row in 0..self.height() {
@@ -162,17 +176,21 @@ impl GraphDrawable for Graph {
                 let x = column as f64 * (NODE_SIZE + 1.0) + 1.0;
                 let y = row as f64 * (NODE_SIZE + 1.0) + 1.0;
 
-                if nodes[index as usize].node_type() == Type::Available {
+                // Queued cells are marked visited already but keep their
+                // type until they are dequeued.
+                if frontier[index] && nodes[index].node_type() == Type::Available {
+                    ctx.set_fill_style(&FRONTIER_COLOR.into());
+                } else if nodes[index].node_type() == Type::Available {
                     ctx.set_fill_style(&AVAILABLE_COLOR.into());
-                } else if nodes[index as usize].node_type() == Type::Path {
+                } else if nodes[index].node_type() == Type::Path {
                     ctx.set_fill_style(&PATH_COLOR.into());
-                } else if nodes[index as usize].node_type() == Type::Start {
+                } else if nodes[index].node_type() == Type::Start {
                     ctx.set_fill_style(&START_COLOR.into());
-                } else if nodes[index as usize].node_type() == Type::End {
+                } else if nodes[index].node_type() == Type::End {
                     ctx.set_fill_style(&END_COLOR.into());
-                } else if nodes[index as usize].node_type() == Type::Wall {
+                } else if nodes[index].node_type() == Type::Wall {
                     ctx.set_fill_style(&WALL_COLOR.into());
-                } else if nodes[index as usize].node_type() == Type::Visited {
+                } else if nodes[index].node_type() == Type::Visited {
                     ctx.set_fill_style(&VISITED.into());
                 }
 
@@ -182,10 +200,134 @@ impl GraphDrawable for Graph {
 
         ctx.stroke();
     }
+
+    /// Lists the queue front first as `(row, col)` entries with their
+    /// distance from the start, as many as fit the height of the canvas.
+    fn draw_frontier(&self, ctx: &web_sys::CanvasRenderingContext2d) {
+        canvas::clear(ctx);
+
+        let header = if self.is_backtracking {
+            vec![
+                "frontier: none".to_string(),
+                "tracing the path back".to_string(),
+            ]
+        } else {
+            vec![
+                format!("frontier: {} cells", self.queue.len()),
+                "dequeued from the top".to_string(),
+            ]
+        };
+
+        for (line, text) in header.into_iter().enumerate() {
+            Text::left_aligned(
+                text,
+                Coordinate::new(PANEL_MARGIN, PANEL_LINE_HEIGHT * (line as f64 + 1.0)),
+                PANEL_TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+        }
+
+        let Some(canvas) = ctx.canvas() else {
+            return;
+        };
+
+        if self.is_backtracking {
+            return;
+        }
+
+        let rows = ((canvas.height() as f64 - PANEL_HEADER_HEIGHT) / PANEL_LINE_HEIGHT).floor();
+        let rows = (rows.max(1.0) as usize).min(self.queue.len());
+        // Keep the last row for the count of entries left out.
+        let shown = if rows < self.queue.len() {
+            rows - 1
+        } else {
+            rows
+        };
+
+        for (line, &idx) in self.queue.iter().take(shown).enumerate() {
+            let y = PANEL_HEADER_HEIGHT + (line as f64 + 0.5) * PANEL_LINE_HEIGHT;
+            let (row, column) = (idx as u32 / self.width(), idx as u32 % self.width());
+
+            ctx.set_fill_style(&FRONTIER_COLOR.into());
+            ctx.fill_rect(PANEL_MARGIN, y - 6.0, 12.0, 12.0);
+
+            Text::left_aligned(
+                format!("({}, {})  d = {}", row, column, self.nodes[idx].weight),
+                Coordinate::new(PANEL_MARGIN + 18.0, y),
+                PANEL_TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+        }
+
+        if shown < self.queue.len() {
+            Text::left_aligned(
+                format!("… {} more", self.queue.len() - shown),
+                Coordinate::new(
+                    PANEL_MARGIN + 18.0,
+                    PANEL_HEADER_HEIGHT + (shown as f64 + 0.5) * PANEL_LINE_HEIGHT,
+                ),
+                PANEL_TEXT_COLOR.to_string(),
+            )
+            .draw(ctx);
+        }
+    }
 }
 
 #[wasm_bindgen]
 pub fn run_bfs(document_id: &str, width: u32, height: u32) {
+    start_bfs(document_id, width, height, |_| {});
+}
+
+/// Runs the same search as `run_bfs` and draws the live contents of its
+/// queue, front first, as a linked list on the canvas `queue_document_id`.
+#[wasm_bindgen]
+pub fn run_bfs_with_queue(document_id: &str, queue_document_id: &str, width: u32, height: u32) {
+    let queue_ctx = canvas::context(queue_document_id);
+
+    start_bfs(document_id, width, height, move |graph| {
+        queue_view(graph).draw(&queue_ctx)
+    });
+}
+
+/// Runs the same search as `run_bfs` with a side panel on the canvas
+/// `panel_document_id` listing the frontier as `(row, col)` cells.
+#[wasm_bindgen]
+pub fn run_bfs_with_frontier(document_id: &str, panel_document_id: &str, width: u32, height: u32) {
+    let panel_ctx = canvas::context(panel_document_id);
+
+    start_bfs(document_id, width, height, move |graph| {
+        graph.draw_frontier(&panel_ctx)
+    });
+}
+
+/// The node indexes waiting in the queue.
+fn queue_view(graph: &Graph) -> ListView {
+    let labels = graph
+        .queue
+        .iter()
+        .take(QUEUE_SHOWN)
+        .map(|idx| idx.to_string())
+        .collect();
+
+    let mut view = ListView::sequence(labels, "front", Some("back"));
+
+    view.notes = vec![format!("queue: {} nodes", graph.queue.len())];
+
+    if graph.queue.len() > QUEUE_SHOWN {
+        view.notes.push(format!(
+            "showing the first {}, the back is {} further",
+            QUEUE_SHOWN,
+            graph.queue.len() - QUEUE_SHOWN
+        ));
+        view.tail = None;
+    }
+
+    view
+}
+
+/// Sets up the grid and searches one step per animation frame, calling
+/// `on_step` with the graph after drawing it.
+fn start_bfs(document_id: &str, width: u32, height: u32, on_step: impl Fn(&Graph) + 'static) {
     let canvas = canvas::canvas(document_id);
 
     let ctx = canvas
@@ -251,6 +393,8 @@ pub fn run_bfs(document_id: &str, width: u32, height: u32) {
         graph.bfs();
         graph.draw_node(&ctx);
 
+        on_step(&graph);
+
         request_animation_frame(f.borrow().as_ref().unwrap());
     }));
 
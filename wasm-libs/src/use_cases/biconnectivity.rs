@@ -0,0 +1,343 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::general_graph::GeneralGraph,
+    renderers::graph::{
+        palette_color, GraphView, ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, NODE_COLOR,
+        PATH_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+struct Biconnectivity<'a> {
+    graph: &'a GeneralGraph,
+    discovery: Vec<Option<usize>>,
+    low: Vec<usize>,
+    time: usize,
+    tree_edges: Vec<bool>,
+    edge_stack: Vec<usize>,
+    is_articulation: Vec<bool>,
+    is_bridge: Vec<bool>,
+    component_of: Vec<Option<usize>>,
+    components: Vec<Vec<usize>>,
+    frames: Vec<GraphView>,
+}
+
+impl<'a> Biconnectivity<'a> {
+    fn new(graph: &'a GeneralGraph) -> Biconnectivity<'a> {
+        let (n, m) = (graph.node_count(), graph.edge_count());
+
+        Biconnectivity {
+            graph,
+            discovery: vec![None; n],
+            low: vec![0; n],
+            time: 0,
+            tree_edges: vec![false; m],
+            edge_stack: vec![],
+            is_articulation: vec![false; n],
+            is_bridge: vec![false; m],
+            component_of: vec![None; m],
+            components: vec![],
+            frames: vec![],
+        }
+    }
+
+    fn run(&mut self) {
+        for root in 0..self.graph.node_count() {
+            if self.discovery[root].is_some() {
+                continue;
+            }
+
+            self.record(
+                Some(root),
+                None,
+                format!("start DFS at {}", self.name(root)),
+            );
+
+            let children = self.visit(root, None);
+
+            if children > 1 {
+                self.is_articulation[root] = true;
+
+                self.record(
+                    Some(root),
+                    None,
+                    format!(
+                        "root {} has {} DFS children: articulation point",
+                        self.name(root),
+                        children
+                    ),
+                );
+            }
+        }
+
+        self.record(
+            None,
+            None,
+            format!(
+                "done: {} articulation points, {} bridges, {} biconnected components",
+                self.is_articulation.iter().filter(|&&is| is).count(),
+                self.is_bridge.iter().filter(|&&is| is).count(),
+                self.components.len()
+            ),
+        );
+    }
+
+    /// DFS from `node`, entered through `parent_edge`. Returns the number of
+    /// DFS children, which decides whether a root is an articulation point.
+    fn visit(&mut self, node: usize, parent_edge: Option<usize>) -> usize {
+        let graph = self.graph;
+        let mut children = 0;
+
+        self.discovery[node] = Some(self.time);
+        self.low[node] = self.time;
+        self.time += 1;
+
+        self.record(
+            Some(node),
+            parent_edge,
+            format!(
+                "discover {}: disc = low = {}",
+                self.name(node),
+                self.low[node]
+            ),
+        );
+
+        for (edge, neighbor) in graph.neighbors(node) {
+            // Skipping the edge itself rather than the parent node keeps
+            // parallel edges as back edges.
+            if Some(edge) == parent_edge {
+                continue;
+            }
+
+            match self.discovery[neighbor] {
+                None => {
+                    children += 1;
+                    self.tree_edges[edge] = true;
+                    self.edge_stack.push(edge);
+
+                    self.visit(neighbor, Some(edge));
+
+                    let node_discovery = self.discovery[node].unwrap();
+
+                    self.low[node] = self.low[node].min(self.low[neighbor]);
+
+                    self.record(
+                        Some(node),
+                        Some(edge),
+                        format!(
+                            "back at {}: low = min(low, low[{}]) = {}",
+                            self.name(node),
+                            self.name(neighbor),
+                            self.low[node]
+                        ),
+                    );
+
+                    if self.low[neighbor] > node_discovery {
+                        self.is_bridge[edge] = true;
+
+                        self.record(
+                            Some(node),
+                            Some(edge),
+                            format!(
+                                "low[{}] = {} > disc[{}] = {}: bridge",
+                                self.name(neighbor),
+                                self.low[neighbor],
+                                self.name(node),
+                                node_discovery
+                            ),
+                        );
+                    }
+
+                    if self.low[neighbor] >= node_discovery {
+                        if parent_edge.is_some() {
+                            self.is_articulation[node] = true;
+                        }
+
+                        self.pop_component(edge);
+
+                        self.record(
+                            Some(node),
+                            None,
+                            format!(
+                                "low[{}] >= disc[{}]: biconnected component #{}{}",
+                                self.name(neighbor),
+                                self.name(node),
+                                self.components.len(),
+                                if parent_edge.is_some() {
+                                    format!(", {} is an articulation point", self.name(node))
+                                } else {
+                                    String::new()
+                                }
+                            ),
+                        );
+                    }
+                }
+                Some(neighbor_discovery) if neighbor_discovery < self.discovery[node].unwrap() => {
+                    self.edge_stack.push(edge);
+                    self.low[node] = self.low[node].min(neighbor_discovery);
+
+                    self.record(
+                        Some(node),
+                        Some(edge),
+                        format!(
+                            "back edge {} - {}: low = min(low, disc[{}]) = {}",
+                            self.name(node),
+                            self.name(neighbor),
+                            self.name(neighbor),
+                            self.low[node]
+                        ),
+                    );
+                }
+                // A back edge seen again from the ancestor's side.
+                Some(_) => {}
+            }
+        }
+
+        children
+    }
+
+    /// Pops the edges above and including the tree edge `edge` as one
+    /// biconnected component.
+    fn pop_component(&mut self, edge: usize) {
+        let component_index = self.components.len();
+        let mut component = vec![];
+
+        while let Some(top) = self.edge_stack.pop() {
+            self.component_of[top] = Some(component_index);
+            component.push(top);
+
+            if top == edge {
+                break;
+            }
+        }
+
+        self.components.push(component);
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.graph.vertices()[node].label
+    }
+
+    fn record(&mut self, current: Option<usize>, edge: Option<usize>, message: String) {
+        let mut view = GraphView::new(self.graph);
+
+        for node in 0..self.graph.node_count() {
+            if let Some(discovery) = self.discovery[node] {
+                view.node_labels[node] = format!("{}/{}", discovery, self.low[node]);
+            }
+
+            view.node_colors[node] = if self.is_articulation[node] {
+                HIGHLIGHT_COLOR
+            } else if current == Some(node) {
+                CURRENT_COLOR
+            } else if self.discovery[node].is_some() {
+                ACTIVE_COLOR
+            } else {
+                NODE_COLOR
+            };
+        }
+
+        for idx in 0..self.graph.edge_count() {
+            if let Some(component) = self.component_of[idx] {
+                view.edge_colors[idx] = palette_color(component);
+            } else if self.tree_edges[idx] {
+                view.edge_colors[idx] = PATH_COLOR;
+            }
+
+            if self.is_bridge[idx] {
+                view.edge_colors[idx] = HIGHLIGHT_COLOR;
+            }
+        }
+
+        if let Some(edge) = edge {
+            if !self.is_bridge[edge] {
+                view.edge_colors[edge] = CURRENT_COLOR;
+            }
+        }
+
+        view.notes = vec![
+            message,
+            "labels: disc/low, red: articulation points and bridges".to_string(),
+        ];
+
+        self.frames.push(view);
+    }
+}
+
+#[wasm_bindgen]
+pub struct BiconnectivityResult {
+    articulation_points: Vec<usize>,
+    bridges: Vec<usize>,
+    components: Vec<Vec<usize>>,
+}
+
+#[wasm_bindgen]
+impl BiconnectivityResult {
+    pub fn articulation_points(&self) -> Vec<usize> {
+        self.articulation_points.clone()
+    }
+
+    /// Edge indexes of the bridges.
+    pub fn bridges(&self) -> Vec<usize> {
+        self.bridges.clone()
+    }
+
+    /// The biconnected components as arrays of edge indexes.
+    pub fn components(&self) -> js_sys::Array {
+        self.components
+            .iter()
+            .map(|component| {
+                component
+                    .iter()
+                    .map(|&edge| JsValue::from(edge as u32))
+                    .collect::<js_sys::Array>()
+            })
+            .collect()
+    }
+}
+
+/// Animates the DFS computing discovery times and low-links on an undirected
+/// graph, then highlights articulation points, bridges and biconnected
+/// components.
+#[wasm_bindgen]
+pub fn run_biconnectivity(
+    document_id: &str,
+    graph: &GeneralGraph,
+) -> Result<BiconnectivityResult, JsValue> {
+    set_panic_hook();
+
+    if graph.is_directed() {
+        return Err(JsValue::from_str(
+            "articulation points and bridges need an undirected graph",
+        ));
+    }
+
+    let mut biconnectivity = Biconnectivity::new(graph);
+    biconnectivity.run();
+
+    let result = BiconnectivityResult {
+        articulation_points: (0..graph.node_count())
+            .filter(|&node| biconnectivity.is_articulation[node])
+            .collect(),
+        bridges: (0..graph.edge_count())
+            .filter(|&edge| biconnectivity.is_bridge[edge])
+            .collect(),
+        components: biconnectivity.components,
+    };
+
+    let ctx = canvas::context(document_id);
+    let graph = graph.clone();
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &GraphView| {
+        view.draw(&ctx, &graph)
+    });
+
+    animation.push(biconnectivity.frames);
+
+    Ok(result)
+}
@@ -0,0 +1,287 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::binary_search_tree::BinarySearchTree,
+    renderers::{
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR},
+        tree::TreeView,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+/// The tree with the nodes of `path` and the edges between them
+/// highlighted, `current` standing out as the node being looked at.
+fn path_view(
+    tree: &BinarySearchTree,
+    path: &[usize],
+    current: Option<usize>,
+    message: String,
+) -> TreeView {
+    let mut view = TreeView::new(tree);
+
+    for (step, &id) in path.iter().enumerate() {
+        let node = view.node_mut(id);
+
+        node.color = ACTIVE_COLOR;
+
+        if step > 0 {
+            node.edge_color = PATH_COLOR;
+        }
+    }
+
+    if let Some(current) = current {
+        view.node_mut(current).color = CURRENT_COLOR;
+    }
+
+    view.notes = vec![
+        message,
+        format!("size: {}, height: {}", tree.len(), tree.height()),
+    ];
+
+    view
+}
+
+/// One frame per node compared on the way down to `key`.
+fn comparison_frames(tree: &BinarySearchTree, key: i32, path: &[usize]) -> Vec<TreeView> {
+    (0..path.len())
+        .map(|step| {
+            let id = path[step];
+            let other = tree.key(id);
+
+            let message = match key.cmp(&other) {
+                std::cmp::Ordering::Less => format!("{} < {}: go left", key, other),
+                std::cmp::Ordering::Greater => format!("{} > {}: go right", key, other),
+                std::cmp::Ordering::Equal => format!("{} = {}", key, other),
+            };
+
+            path_view(tree, &path[..=step], Some(id), message)
+        })
+        .collect()
+}
+
+/// A binary search tree drawn on a canvas. Every operation animates the
+/// comparisons it makes on top of the operations before it.
+#[wasm_bindgen]
+pub struct BinarySearchTreeVisualization {
+    tree: BinarySearchTree,
+    animation: Animation<TreeView>,
+}
+
+#[wasm_bindgen]
+impl BinarySearchTreeVisualization {
+    /// Adds `key` as a leaf, returning `false` if it was already there.
+    pub fn insert(&mut self, key: i32) -> bool {
+        let path = self.tree.search_path(key);
+        let mut frames = comparison_frames(&self.tree, key, &path);
+
+        let inserted = match self.tree.insert(key) {
+            Some(id) => {
+                let message = match path.last() {
+                    Some(&parent) => format!(
+                        "insert {} as the {} child of {}",
+                        key,
+                        if key < self.tree.key(parent) {
+                            "left"
+                        } else {
+                            "right"
+                        },
+                        self.tree.key(parent)
+                    ),
+                    None => format!("insert {} as the root", key),
+                };
+
+                let mut view = path_view(&self.tree, &path, None, message);
+                view.node_mut(id).color = PATH_COLOR;
+                frames.push(view);
+
+                true
+            }
+            None => {
+                frames.push(path_view(
+                    &self.tree,
+                    &path,
+                    path.last().copied(),
+                    format!("{} is already in the tree", key),
+                ));
+
+                false
+            }
+        };
+
+        self.animation.push(frames);
+
+        inserted
+    }
+
+    pub fn search(&self, key: i32) -> bool {
+        let path = self.tree.search_path(key);
+        let mut frames = comparison_frames(&self.tree, key, &path);
+        let found = self.tree.find(key);
+
+        let mut view = path_view(
+            &self.tree,
+            &path,
+            None,
+            match found {
+                Some(_) => format!("found {} after {} comparisons", key, path.len()),
+                None => format!("{} is not in the tree", key),
+            },
+        );
+
+        if let Some(id) = found {
+            view.node_mut(id).color = PATH_COLOR;
+        }
+
+        frames.push(view);
+        self.animation.push(frames);
+
+        found.is_some()
+    }
+
+    /// Removes `key`, swapping it with its in-order successor first when
+    /// its node has two children. Returns `false` if it was not there.
+    pub fn remove(&mut self, key: i32) -> bool {
+        let path = self.tree.search_path(key);
+        let mut frames = comparison_frames(&self.tree, key, &path);
+
+        let Some(id) = self.tree.find(key) else {
+            frames.push(path_view(
+                &self.tree,
+                &path,
+                None,
+                format!("{} is not in the tree", key),
+            ));
+            self.animation.push(frames);
+
+            return false;
+        };
+
+        let node = self.tree.node(id);
+
+        match (node.left, node.right) {
+            (Some(_), Some(right)) => {
+                let successor_path = self.tree.min_path(right);
+                let successor = *successor_path.last().unwrap();
+                let mut descent = path.clone();
+
+                for (step, &next) in successor_path.iter().enumerate() {
+                    descent.push(next);
+
+                    let message = if step == 0 {
+                        format!(
+                            "{} has two children: find its successor, go right to {}",
+                            key,
+                            self.tree.key(next)
+                        )
+                    } else {
+                        format!("go left to {}", self.tree.key(next))
+                    };
+
+                    frames.push(path_view(&self.tree, &descent, Some(next), message));
+                }
+
+                let mut view = path_view(
+                    &self.tree,
+                    &descent,
+                    None,
+                    format!(
+                        "swap {} with its successor {}",
+                        key,
+                        self.tree.key(successor)
+                    ),
+                );
+
+                view.node_mut(id).label = self.tree.key(successor).to_string();
+                view.node_mut(id).color = HIGHLIGHT_COLOR;
+                view.node_mut(successor).label = key.to_string();
+                view.node_mut(successor).color = HIGHLIGHT_COLOR;
+                frames.push(view);
+
+                self.tree.remove_node(id);
+
+                let mut view = path_view(
+                    &self.tree,
+                    &[],
+                    None,
+                    format!("remove {} from the old place of {}", key, self.tree.key(id)),
+                );
+
+                view.node_mut(id).color = PATH_COLOR;
+                frames.push(view);
+            }
+            (left, right) => {
+                let child = left.or(right);
+
+                let mut view = path_view(
+                    &self.tree,
+                    &path,
+                    None,
+                    match child {
+                        Some(child) => format!(
+                            "{} has one child: link {} to its parent",
+                            key,
+                            self.tree.key(child)
+                        ),
+                        None => format!("{} is a leaf: unlink it", key),
+                    },
+                );
+
+                view.node_mut(id).color = HIGHLIGHT_COLOR;
+                frames.push(view);
+
+                self.tree.remove_node(id);
+
+                frames.push(path_view(&self.tree, &[], None, format!("removed {}", key)));
+            }
+        }
+
+        self.animation.push(frames);
+
+        true
+    }
+
+    /// The keys in order.
+    pub fn keys(&self) -> Vec<i32> {
+        self.tree
+            .in_order()
+            .into_iter()
+            .map(|id| self.tree.key(id))
+            .collect()
+    }
+
+    pub fn height(&self) -> usize {
+        self.tree.height()
+    }
+}
+
+/// Starts a binary search tree visualization, animating the insertion of
+/// `keys` one after another.
+#[wasm_bindgen]
+pub fn run_binary_search_tree(document_id: &str, keys: Vec<i32>) -> BinarySearchTreeVisualization {
+    set_panic_hook();
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &TreeView| view.draw(&ctx));
+
+    let mut visualization = BinarySearchTreeVisualization {
+        tree: BinarySearchTree::new(),
+        animation,
+    };
+
+    visualization.animation.push([path_view(
+        &visualization.tree,
+        &[],
+        None,
+        "empty tree".to_string(),
+    )]);
+
+    for key in keys {
+        visualization.insert(key);
+    }
+
+    visualization
+}
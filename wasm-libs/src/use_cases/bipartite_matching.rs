@@ -0,0 +1,406 @@
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas::{self, Boundary},
+    data_structures::{bipartite_graph::BipartiteGraph, general_graph::GeneralGraph},
+    renderers::graph::{
+        GraphView, ACTIVE_COLOR, CURRENT_COLOR, NODE_COLOR, PATH_COLOR, RELAXED_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+struct Matching<'a> {
+    graph: &'a BipartiteGraph,
+    layout: GeneralGraph,
+    /// Matched edge of every left node.
+    match_left: Vec<Option<usize>>,
+    /// Matched edge of every right node.
+    match_right: Vec<Option<usize>>,
+    size: usize,
+    frames: Vec<GraphView>,
+}
+
+impl<'a> Matching<'a> {
+    fn new(graph: &'a BipartiteGraph, boundary: &Boundary) -> Matching<'a> {
+        Matching {
+            graph,
+            layout: graph.to_general_graph(boundary),
+            match_left: vec![None; graph.left_count()],
+            match_right: vec![None; graph.right_count()],
+            size: 0,
+            frames: vec![],
+        }
+    }
+
+    fn kuhn(&mut self) {
+        for left in 0..self.graph.left_count() {
+            let mut visited = vec![false; self.graph.right_count()];
+            let mut path = vec![];
+
+            self.record(
+                Some(left),
+                &path,
+                vec![],
+                format!("Kuhn: search an augmenting path from L{}", left),
+            );
+
+            if self.try_kuhn(left, &mut visited, &mut path) {
+                self.augment(&path);
+            } else {
+                self.record(
+                    Some(left),
+                    &[],
+                    vec![],
+                    format!("no augmenting path from L{}", left),
+                );
+            }
+        }
+    }
+
+    /// DFS along alternating paths from `left`. On success `path` holds the
+    /// edges of the augmenting path, alternately unmatched and matched.
+    fn try_kuhn(&mut self, left: usize, visited: &mut [bool], path: &mut Vec<usize>) -> bool {
+        let graph = self.graph;
+
+        for &edge in graph.incident_edges(left) {
+            let right = graph.edges()[edge].1;
+
+            if visited[right] {
+                continue;
+            }
+
+            visited[right] = true;
+            path.push(edge);
+
+            match self.match_right[right] {
+                None => {
+                    self.record(
+                        Some(left),
+                        path,
+                        vec![],
+                        format!("R{} is free: augmenting path found", right),
+                    );
+
+                    return true;
+                }
+                Some(matched) => {
+                    let partner = graph.edges()[matched].0;
+
+                    path.push(matched);
+                    self.record(
+                        Some(partner),
+                        path,
+                        vec![],
+                        format!(
+                            "R{} is matched to L{}: try to rematch L{}",
+                            right, partner, partner
+                        ),
+                    );
+
+                    if self.try_kuhn(partner, visited, path) {
+                        return true;
+                    }
+
+                    path.pop();
+                }
+            }
+
+            path.pop();
+        }
+
+        false
+    }
+
+    fn hopcroft_karp(&mut self) {
+        let mut phase = 1;
+
+        while let Some((layers, free_layer)) = self.layers() {
+            let labels = layers
+                .iter()
+                .map(|layer| layer.map_or(String::new(), |layer| format!("d={}", layer)))
+                .collect::<Vec<_>>();
+
+            self.record(
+                None,
+                &[],
+                labels.clone(),
+                format!(
+                    "Hopcroft-Karp phase {}: BFS layers, shortest augmenting paths have {} edges",
+                    phase,
+                    free_layer * 2 + 1
+                ),
+            );
+
+            let mut layers = layers;
+
+            for left in 0..self.graph.left_count() {
+                if self.match_left[left].is_some() {
+                    continue;
+                }
+
+                let mut path = vec![];
+
+                if self.layered_path(left, &mut layers, free_layer, &mut path) {
+                    self.record(
+                        None,
+                        &path,
+                        labels.clone(),
+                        format!("phase {}: disjoint shortest path from L{}", phase, left),
+                    );
+                    self.augment(&path);
+                }
+            }
+
+            phase += 1;
+        }
+    }
+
+    /// BFS from every free left node over alternating paths. Returns each
+    /// left node's layer and the layer at which a free right node is first
+    /// reachable, or `None` when no augmenting path is left.
+    fn layers(&self) -> Option<(Vec<Option<usize>>, usize)> {
+        let mut layers = vec![None; self.graph.left_count()];
+        let mut queue = VecDeque::new();
+        let mut free_layer = None;
+
+        for (left, matched) in self.match_left.iter().enumerate() {
+            if matched.is_none() {
+                layers[left] = Some(0);
+                queue.push_back(left);
+            }
+        }
+
+        while let Some(left) = queue.pop_front() {
+            let layer = layers[left].unwrap();
+
+            if free_layer.is_some_and(|free_layer| layer >= free_layer) {
+                continue;
+            }
+
+            for &edge in self.graph.incident_edges(left) {
+                let right = self.graph.edges()[edge].1;
+
+                match self.match_right[right] {
+                    None => free_layer = Some(free_layer.unwrap_or(layer)),
+                    Some(matched) => {
+                        let partner = self.graph.edges()[matched].0;
+
+                        if layers[partner].is_none() {
+                            layers[partner] = Some(layer + 1);
+                            queue.push_back(partner);
+                        }
+                    }
+                }
+            }
+        }
+
+        free_layer.map(|free_layer| (layers, free_layer))
+    }
+
+    /// DFS restricted to edges going one layer deeper. Nodes that lead
+    /// nowhere are removed from the layering so the paths found in one phase
+    /// stay vertex-disjoint and no dead end is explored twice.
+    fn layered_path(
+        &self,
+        left: usize,
+        layers: &mut [Option<usize>],
+        free_layer: usize,
+        path: &mut Vec<usize>,
+    ) -> bool {
+        let layer = match layers[left] {
+            Some(layer) => layer,
+            None => return false,
+        };
+
+        for &edge in self.graph.incident_edges(left) {
+            let right = self.graph.edges()[edge].1;
+
+            match self.match_right[right] {
+                None if layer == free_layer => {
+                    path.push(edge);
+                    return true;
+                }
+                None => {}
+                Some(matched) => {
+                    let partner = self.graph.edges()[matched].0;
+
+                    if layers[partner] == Some(layer + 1) {
+                        path.push(edge);
+                        path.push(matched);
+
+                        if self.layered_path(partner, layers, free_layer, path) {
+                            return true;
+                        }
+
+                        path.pop();
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        layers[left] = None;
+
+        false
+    }
+
+    /// Flips the alternating path: its unmatched edges, at even positions,
+    /// replace the matched ones.
+    fn augment(&mut self, path: &[usize]) {
+        for &edge in path.iter().step_by(2) {
+            let (left, right) = self.graph.edges()[edge];
+
+            self.match_left[left] = Some(edge);
+            self.match_right[right] = Some(edge);
+        }
+
+        self.size += 1;
+
+        self.record(
+            None,
+            &[],
+            vec![],
+            format!("augmented: matching size is now {}", self.size),
+        );
+    }
+
+    fn is_perfect(&self) -> bool {
+        self.graph.left_count() == self.graph.right_count() && self.size == self.graph.left_count()
+    }
+
+    fn record(
+        &mut self,
+        current: Option<usize>,
+        path: &[usize],
+        labels: Vec<String>,
+        message: String,
+    ) {
+        let left_count = self.graph.left_count();
+        let mut view = GraphView::new(&self.layout);
+
+        for (left, matched) in self.match_left.iter().enumerate() {
+            if let Some(edge) = matched {
+                view.edge_colors[*edge] = RELAXED_COLOR;
+                view.node_colors[left] = ACTIVE_COLOR;
+                view.node_colors[left_count + self.graph.edges()[*edge].1] = ACTIVE_COLOR;
+            }
+        }
+
+        for &edge in path {
+            let (left, right) = self.graph.edges()[edge];
+
+            view.edge_colors[edge] = if self.match_left[left] == Some(edge) {
+                PATH_COLOR
+            } else {
+                CURRENT_COLOR
+            };
+
+            if view.node_colors[left_count + right] == NODE_COLOR {
+                view.node_colors[left_count + right] = CURRENT_COLOR;
+            }
+        }
+
+        if let Some(left) = current {
+            view.node_colors[left] = CURRENT_COLOR;
+        }
+
+        for (left, label) in labels.into_iter().enumerate() {
+            view.node_labels[left] = label;
+        }
+
+        view.notes = vec![
+            message,
+            format!("matching size: {}", self.size),
+            "green: matched, orange/blue: alternating path".to_string(),
+        ];
+
+        self.frames.push(view);
+    }
+}
+
+#[wasm_bindgen]
+pub struct MatchingResult {
+    pairs: Vec<(usize, usize)>,
+    perfect: bool,
+}
+
+#[wasm_bindgen]
+impl MatchingResult {
+    pub fn size(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Whether every node on both sides is matched.
+    pub fn is_perfect(&self) -> bool {
+        self.perfect
+    }
+
+    /// `[left, right]` pairs of the matched edges.
+    pub fn pairs(&self) -> js_sys::Array {
+        self.pairs
+            .iter()
+            .map(|&(left, right)| {
+                js_sys::Array::of2(&JsValue::from(left as u32), &JsValue::from(right as u32))
+            })
+            .collect()
+    }
+}
+
+/// Lays `graph` out in two columns and animates a maximum matching with
+/// Kuhn's algorithm (`"kuhn"`) or Hopcroft-Karp (`"hopcroft-karp"`).
+#[wasm_bindgen]
+pub fn run_bipartite_matching(
+    document_id: &str,
+    graph: &BipartiteGraph,
+    algorithm: &str,
+    boundary: Boundary,
+) -> Result<MatchingResult, JsValue> {
+    set_panic_hook();
+
+    let mut matching = Matching::new(graph, &boundary);
+
+    match algorithm {
+        "kuhn" => matching.kuhn(),
+        "hopcroft-karp" => matching.hopcroft_karp(),
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown matching algorithm `{}`, expected `kuhn` or `hopcroft-karp`",
+                algorithm
+            )))
+        }
+    }
+
+    let perfect = matching.is_perfect();
+
+    matching.record(
+        None,
+        &[],
+        vec![],
+        format!(
+            "maximum matching: {} ({})",
+            matching.size,
+            if perfect { "perfect" } else { "not perfect" }
+        ),
+    );
+
+    let pairs = matching
+        .match_left
+        .iter()
+        .filter_map(|edge| edge.map(|edge| graph.edges()[edge]))
+        .collect();
+
+    let ctx = canvas::context(document_id);
+    let layout = matching.layout;
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &GraphView| {
+        view.draw(&ctx, &layout)
+    });
+
+    animation.push(matching.frames);
+
+    Ok(MatchingResult { pairs, perfect })
+}
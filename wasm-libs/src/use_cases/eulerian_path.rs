@@ -0,0 +1,440 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::general_graph::GeneralGraph,
+    renderers::graph::{
+        GraphView, ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TrailKind {
+    Circuit,
+    Path,
+    None,
+}
+
+/// Outcome of the degree and connectivity checks: where a trail has to
+/// start, or why there is none.
+struct DegreeCheck {
+    kind: TrailKind,
+    start: usize,
+    violating: Vec<usize>,
+    problems: Vec<String>,
+}
+
+fn check_degrees(graph: &GeneralGraph) -> DegreeCheck {
+    let n = graph.node_count();
+
+    if n == 0 {
+        return DegreeCheck {
+            kind: TrailKind::None,
+            start: 0,
+            violating: vec![],
+            problems: vec!["the graph has no nodes to start a trail from".to_string()],
+        };
+    }
+
+    let mut out_degree = vec![0i64; n];
+    let mut in_degree = vec![0i64; n];
+
+    for edge in graph.edges() {
+        out_degree[edge.from] += 1;
+        in_degree[edge.to] += 1;
+    }
+
+    let name = |node: usize| graph.vertices()[node].label.clone();
+    let has_edges = |node: usize| out_degree[node] + in_degree[node] > 0;
+    let first_with_edges = (0..n).find(|&node| has_edges(node)).unwrap_or(0);
+
+    let mut violating = vec![];
+    let mut problems = vec![];
+    let mut start = first_with_edges;
+
+    let kind = if graph.is_directed() {
+        let sources = (0..n)
+            .filter(|&node| out_degree[node] - in_degree[node] == 1)
+            .collect::<Vec<_>>();
+        let sinks = (0..n)
+            .filter(|&node| in_degree[node] - out_degree[node] == 1)
+            .collect::<Vec<_>>();
+        let unbalanced = (0..n)
+            .filter(|&node| out_degree[node] != in_degree[node])
+            .collect::<Vec<_>>();
+
+        if unbalanced.is_empty() {
+            TrailKind::Circuit
+        } else if sources.len() == 1 && sinks.len() == 1 && unbalanced.len() == 2 {
+            start = sources[0];
+            TrailKind::Path
+        } else {
+            for &node in unbalanced.iter() {
+                problems.push(format!(
+                    "{}: out-degree {} but in-degree {}",
+                    name(node),
+                    out_degree[node],
+                    in_degree[node]
+                ));
+            }
+            problems.push(
+                "a directed trail needs all nodes balanced, except at most one with one extra \
+                 outgoing edge and one with one extra incoming edge"
+                    .to_string(),
+            );
+            violating = unbalanced;
+            TrailKind::None
+        }
+    } else {
+        // A self-loop counts twice, once in each degree array.
+        let odd = (0..n)
+            .filter(|&node| (out_degree[node] + in_degree[node]) % 2 == 1)
+            .collect::<Vec<_>>();
+
+        match odd.len() {
+            0 => TrailKind::Circuit,
+            2 => {
+                start = odd[0];
+                TrailKind::Path
+            }
+            count => {
+                for &node in odd.iter() {
+                    problems.push(format!(
+                        "{} has odd degree {}",
+                        name(node),
+                        out_degree[node] + in_degree[node]
+                    ));
+                }
+                problems.push(format!(
+                    "{} nodes have odd degree, an undirected trail allows 0 or 2",
+                    count
+                ));
+                violating = odd;
+                TrailKind::None
+            }
+        }
+    };
+
+    // Every edge has to be reachable from the start, ignoring directions.
+    let mut reached = vec![false; n];
+    let mut stack = vec![start];
+
+    reached[start] = true;
+    let mut undirected = vec![vec![]; n];
+
+    for edge in graph.edges() {
+        undirected[edge.from].push(edge.to);
+        undirected[edge.to].push(edge.from);
+    }
+
+    while let Some(node) = stack.pop() {
+        for &neighbor in undirected[node].iter() {
+            if !reached[neighbor] {
+                reached[neighbor] = true;
+                stack.push(neighbor);
+            }
+        }
+    }
+
+    let unreached = (0..n)
+        .filter(|&node| has_edges(node) && !reached[node])
+        .collect::<Vec<_>>();
+
+    if unreached.is_empty() {
+        return DegreeCheck {
+            kind,
+            start,
+            violating,
+            problems,
+        };
+    }
+
+    problems.push(format!(
+        "the edges are not connected: {} cannot be reached from {}",
+        unreached
+            .iter()
+            .map(|&node| name(node))
+            .collect::<Vec<_>>()
+            .join(", "),
+        name(start)
+    ));
+    violating.extend(unreached);
+
+    DegreeCheck {
+        kind: TrailKind::None,
+        start,
+        violating,
+        problems,
+    }
+}
+
+struct Hierholzer<'a> {
+    graph: &'a GeneralGraph,
+    used: Vec<bool>,
+    next_edge: Vec<usize>,
+    stack: Vec<usize>,
+    stack_edges: Vec<usize>,
+    trail: Vec<usize>,
+    trail_edges: Vec<usize>,
+    frames: Vec<GraphView>,
+}
+
+impl<'a> Hierholzer<'a> {
+    fn new(graph: &'a GeneralGraph) -> Hierholzer<'a> {
+        Hierholzer {
+            graph,
+            used: vec![false; graph.edge_count()],
+            next_edge: vec![0; graph.node_count()],
+            stack: vec![],
+            stack_edges: vec![],
+            trail: vec![],
+            trail_edges: vec![],
+            frames: vec![],
+        }
+    }
+
+    /// Walks unused edges from the top of the stack until stuck, then
+    /// backtracks, prepending stuck nodes to the trail. Walking on again from
+    /// a node reached by backtracking starts a sub-circuit that ends up
+    /// spliced into the trail at that node.
+    fn run(&mut self, start: usize) {
+        let graph = self.graph;
+        let mut backtracked = false;
+
+        self.stack.push(start);
+        self.record(
+            Some(start),
+            None,
+            format!("start the walk at {}", self.name(start)),
+        );
+
+        while let Some(&node) = self.stack.last() {
+            let incident = graph.incident_edges(node);
+
+            while self.next_edge[node] < incident.len() && self.used[incident[self.next_edge[node]]]
+            {
+                self.next_edge[node] += 1;
+            }
+
+            if let Some(&edge) = incident.get(self.next_edge[node]) {
+                let neighbor = graph.edges()[edge].other(node);
+
+                if backtracked {
+                    self.record(
+                        Some(node),
+                        None,
+                        format!(
+                            "{} still has unused edges: start a sub-circuit to splice in here",
+                            self.name(node)
+                        ),
+                    );
+                    backtracked = false;
+                }
+
+                self.used[edge] = true;
+                self.stack.push(neighbor);
+                self.stack_edges.push(edge);
+
+                self.record(
+                    Some(neighbor),
+                    Some(edge),
+                    format!("walk {} -> {}", self.name(node), self.name(neighbor)),
+                );
+            } else {
+                self.stack.pop();
+                self.trail.push(node);
+
+                if let Some(edge) = self.stack_edges.pop() {
+                    self.trail_edges.push(edge);
+                }
+
+                backtracked = true;
+
+                self.record(
+                    self.stack.last().copied(),
+                    None,
+                    format!(
+                        "{} is stuck: prepend it to the trail and backtrack",
+                        self.name(node)
+                    ),
+                );
+            }
+        }
+
+        self.record(
+            None,
+            None,
+            "every edge is used: the trail is complete".to_string(),
+        );
+
+        self.trail.reverse();
+        self.trail_edges.reverse();
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.graph.vertices()[node].label
+    }
+
+    fn record(&mut self, current: Option<usize>, edge: Option<usize>, message: String) {
+        let mut view = GraphView::new(self.graph);
+        let edge_count = self.graph.edge_count();
+
+        for &node in self.stack.iter() {
+            view.node_colors[node] = ACTIVE_COLOR;
+        }
+
+        for &edge in self.stack_edges.iter() {
+            view.edge_colors[edge] = CURRENT_COLOR;
+        }
+
+        // Edges are fixed in the trail back to front, so their final number
+        // is already known.
+        for (popped, &edge) in self.trail_edges.iter().enumerate() {
+            view.edge_colors[edge] = PATH_COLOR;
+            view.edge_labels[edge] = (edge_count - popped).to_string();
+        }
+
+        if let Some(edge) = edge {
+            view.edge_colors[edge] = RELAXED_COLOR;
+        }
+
+        if let Some(node) = current {
+            view.node_colors[node] = CURRENT_COLOR;
+        }
+
+        view.notes = vec![
+            message,
+            format!("trail: {} of {} edges", self.trail_edges.len(), edge_count),
+        ];
+
+        self.frames.push(view);
+    }
+}
+
+#[wasm_bindgen]
+pub struct EulerianResult {
+    kind: TrailKind,
+    trail: Vec<usize>,
+    trail_edges: Vec<usize>,
+    problems: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl EulerianResult {
+    /// `"circuit"`, `"path"` or `"none"`.
+    pub fn kind(&self) -> String {
+        match self.kind {
+            TrailKind::Circuit => "circuit",
+            TrailKind::Path => "path",
+            TrailKind::None => "none",
+        }
+        .to_string()
+    }
+
+    /// Nodes in trail order, empty when there is no trail.
+    pub fn trail(&self) -> Vec<usize> {
+        self.trail.clone()
+    }
+
+    /// Edge indexes in trail order.
+    pub fn trail_edges(&self) -> Vec<usize> {
+        self.trail_edges.clone()
+    }
+
+    /// Why no trail exists, one sentence per violated condition.
+    pub fn problems(&self) -> js_sys::Array {
+        self.problems
+            .iter()
+            .map(|problem| JsValue::from_str(problem))
+            .collect()
+    }
+}
+
+/// Checks the degree conditions and, if an Eulerian trail exists, animates
+/// Hierholzer's algorithm building it. Otherwise the offending nodes are
+/// highlighted with the reasons.
+#[wasm_bindgen]
+pub fn run_eulerian_path(document_id: &str, graph: &GeneralGraph) -> EulerianResult {
+    set_panic_hook();
+
+    let check = check_degrees(graph);
+
+    let (result, frames) = if check.kind == TrailKind::None {
+        let mut view = GraphView::new(graph);
+
+        for &node in check.violating.iter() {
+            view.node_colors[node] = HIGHLIGHT_COLOR;
+        }
+
+        view.notes = vec!["no Eulerian trail:".to_string()];
+        view.notes.extend(check.problems.iter().cloned());
+
+        let result = EulerianResult {
+            kind: TrailKind::None,
+            trail: vec![],
+            trail_edges: vec![],
+            problems: check.problems,
+        };
+
+        (result, vec![view])
+    } else {
+        let mut hierholzer = Hierholzer::new(graph);
+        hierholzer.run(check.start);
+
+        let result = EulerianResult {
+            kind: check.kind,
+            trail: hierholzer.trail,
+            trail_edges: hierholzer.trail_edges,
+            problems: vec![],
+        };
+
+        (result, hierholzer.frames)
+    };
+
+    let ctx = canvas::context(document_id);
+    let graph = graph.clone();
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &GraphView| {
+        view.draw(&ctx, &graph)
+    });
+
+    animation.push(frames);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_graph_has_no_trail() {
+        let check = check_degrees(&GeneralGraph::new(false));
+
+        assert_eq!(check.kind, TrailKind::None);
+        assert!(check.violating.is_empty());
+        assert_eq!(check.problems.len(), 1);
+    }
+
+    #[test]
+    fn triangle_is_a_circuit() {
+        let mut graph = GeneralGraph::new(false);
+
+        for _ in 0..3 {
+            graph.add_node(0.0, 0.0);
+        }
+
+        graph.push_edge(0, 1, 1.0);
+        graph.push_edge(1, 2, 1.0);
+        graph.push_edge(2, 0, 1.0);
+
+        let check = check_degrees(&graph);
+
+        assert_eq!(check.kind, TrailKind::Circuit);
+        assert!(check.problems.is_empty());
+    }
+}
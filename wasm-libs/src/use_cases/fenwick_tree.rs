@@ -0,0 +1,278 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::fenwick_tree::{lowbit, FenwickTree},
+    renderers::{
+        fenwick_tree::FenwickView,
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, NODE_COLOR, PATH_COLOR, RELAXED_COLOR},
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+/// How a path moves from an entry to the next.
+#[derive(Clone, Copy)]
+enum Walk {
+    /// Updates add the lowbit, reaching every entry covering an index.
+    Up,
+    /// Queries subtract it, reaching the entries that make up a prefix.
+    Down,
+}
+
+/// The frames of one Fenwick tree operation, counting the entries it
+/// touched.
+struct Recorder<'a> {
+    tree: &'a FenwickTree,
+    entry_colors: Vec<&'static str>,
+    value_colors: Vec<&'static str>,
+    jumps: Vec<(usize, usize)>,
+    touched: usize,
+    summary: String,
+    frames: Vec<FenwickView>,
+}
+
+impl<'a> Recorder<'a> {
+    fn new(tree: &'a FenwickTree, summary: String) -> Recorder<'a> {
+        Recorder {
+            tree,
+            entry_colors: vec![NODE_COLOR; tree.len()],
+            value_colors: vec![NODE_COLOR; tree.len()],
+            jumps: vec![],
+            touched: 0,
+            summary,
+            frames: vec![],
+        }
+    }
+
+    /// Marks the values of `lo..=hi` as the ones the operation is about.
+    fn mark_values(&mut self, lo: usize, hi: usize) {
+        for color in &mut self.value_colors[lo..=hi] {
+            *color = ACTIVE_COLOR;
+        }
+    }
+
+    /// Records every entry of `path` in turn with the jump to it, colored
+    /// `color` once passed, walking it as `walk` says.
+    fn record_path(&mut self, path: &[usize], color: &'static str, walk: Walk) {
+        for (position, &entry) in path.iter().enumerate() {
+            let low = lowbit(entry);
+            let (step, next) = match walk {
+                Walk::Up => ('+', entry + low),
+                Walk::Down => ('-', entry - low),
+            };
+
+            self.touched += 1;
+
+            if position > 0 {
+                self.jumps.push((path[position - 1], entry));
+            }
+
+            self.entry_colors[entry - 1] = CURRENT_COLOR;
+            self.record(format!(
+                "tree[{}] sums a[{}..={}]; {} = {:b}, lowbit {}: next {} {} {} = {}",
+                entry,
+                entry - low,
+                entry - 1,
+                entry,
+                entry,
+                low,
+                entry,
+                step,
+                low,
+                next
+            ));
+            self.entry_colors[entry - 1] = color;
+        }
+    }
+
+    fn record(&mut self, message: String) {
+        let log = (usize::BITS - self.tree.len().leading_zeros()) as usize;
+
+        self.frames.push(FenwickView {
+            values: self.tree.values().to_vec(),
+            entries: (1..=self.tree.len()).map(|i| self.tree.entry(i)).collect(),
+            value_colors: self.value_colors.clone(),
+            entry_colors: self.entry_colors.clone(),
+            jumps: self.jumps.clone(),
+            notes: vec![
+                message,
+                format!(
+                    "entries touched: {} (n = {}, ⌊log2 n⌋ + 1 = {} per prefix)",
+                    self.touched,
+                    self.tree.len(),
+                    log
+                ),
+                self.summary.clone(),
+            ],
+        });
+    }
+}
+
+/// A Fenwick tree of sums over an array, drawn as the array above the tree
+/// entries and the ranges they sum, animating the `lowbit` jumps of every
+/// prefix query and update.
+#[wasm_bindgen]
+pub struct FenwickTreeVisualization {
+    tree: FenwickTree,
+    touched: usize,
+    animation: Animation<FenwickView>,
+}
+
+#[wasm_bindgen]
+impl FenwickTreeVisualization {
+    /// The sum of the values at `lo..=hi`, as the difference of two prefix
+    /// sums.
+    pub fn query(&mut self, lo: usize, hi: usize) -> Result<f64, JsValue> {
+        self.check_range(lo, hi)?;
+
+        let (upper, upper_path) = self.tree.prefix_sum(hi + 1);
+        let (lower, lower_path) = self.tree.prefix_sum(lo);
+        let sum = upper - lower;
+
+        let mut recorder = Recorder::new(&self.tree, format!("sum of a[{}..={}]", lo, hi));
+
+        recorder.mark_values(lo, hi);
+        recorder.record(format!(
+            "sum of a[{}..={}] = prefix({}) - prefix({})",
+            lo,
+            hi,
+            hi + 1,
+            lo
+        ));
+        recorder.record_path(&upper_path, PATH_COLOR, Walk::Down);
+        recorder.record(format!("prefix({}) = {}", hi + 1, upper));
+        recorder.jumps.clear();
+        recorder.record_path(&lower_path, RELAXED_COLOR, Walk::Down);
+        recorder.record(format!("done: {} - {} = {}", upper, lower, sum));
+
+        let touched = recorder.touched;
+        let frames = recorder.frames;
+
+        self.touched = touched;
+        self.animation.push(frames);
+
+        Ok(sum as f64)
+    }
+
+    /// Adds `amount` to the value at `index`.
+    pub fn add(&mut self, index: usize, amount: i32) -> Result<(), JsValue> {
+        self.check_range(index, index)?;
+
+        self.update(
+            index,
+            amount as i64,
+            format!("add {:+} to a[{}]", amount, index),
+        );
+
+        Ok(())
+    }
+
+    /// Sets the value at `index`, adding the difference.
+    pub fn set(&mut self, index: usize, value: i32) -> Result<(), JsValue> {
+        self.check_range(index, index)?;
+
+        let amount = value as i64 - self.tree.values()[index];
+
+        self.update(
+            index,
+            amount,
+            format!("set a[{}] = {}: add {:+}", index, value, amount),
+        );
+
+        Ok(())
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        self.tree
+            .values()
+            .iter()
+            .map(|&value| value as f64)
+            .collect()
+    }
+
+    /// Entries touched by the last operation.
+    pub fn touched(&self) -> usize {
+        self.touched
+    }
+
+    fn check_range(&self, lo: usize, hi: usize) -> Result<(), JsValue> {
+        if lo <= hi && hi < self.tree.len() {
+            Ok(())
+        } else {
+            Err(JsValue::from_str(&format!(
+                "range [{}, {}] is not within [0, {}]",
+                lo,
+                hi,
+                self.tree.len() - 1
+            )))
+        }
+    }
+
+    /// Records the jumps of adding `amount` at `index` on the tree before
+    /// it, then the tree after it.
+    fn update(&mut self, index: usize, amount: i64, summary: String) {
+        let before = self.tree.clone();
+        let path = self.tree.add(index, amount);
+
+        let mut recorder = Recorder::new(&before, summary.clone());
+
+        recorder.mark_values(index, index);
+        recorder.record(format!(
+            "every entry covering a[{}] changes, from tree[{}] up",
+            index,
+            index + 1
+        ));
+        recorder.record_path(&path, RELAXED_COLOR, Walk::Up);
+
+        let mut frames = recorder.frames;
+        let touched = recorder.touched;
+        let mut recorder = Recorder::new(&self.tree, summary);
+
+        recorder.touched = touched;
+
+        for &entry in &path {
+            recorder.entry_colors[entry - 1] = RELAXED_COLOR;
+        }
+
+        recorder.mark_values(index, index);
+        recorder.record(format!("done: {} entries updated", path.len()));
+        frames.extend(recorder.frames);
+
+        self.touched = touched;
+        self.animation.push(frames);
+    }
+}
+
+/// Starts a visualization of a Fenwick tree over `values`.
+#[wasm_bindgen]
+pub fn run_fenwick_tree(
+    document_id: &str,
+    values: Vec<i32>,
+) -> Result<FenwickTreeVisualization, JsValue> {
+    set_panic_hook();
+
+    if values.is_empty() {
+        return Err(JsValue::from_str("a Fenwick tree needs at least one value"));
+    }
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &FenwickView| view.draw(&ctx));
+
+    let tree = FenwickTree::new(&values);
+    let mut recorder = Recorder::new(&tree, String::new());
+
+    recorder.record(format!(
+        "Fenwick tree over {} values: tree[i] sums the lowbit(i) values ending at a[i - 1]",
+        tree.len()
+    ));
+    animation.push(recorder.frames);
+
+    Ok(FenwickTreeVisualization {
+        tree,
+        touched: 0,
+        animation,
+    })
+}
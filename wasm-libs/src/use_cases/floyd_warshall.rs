@@ -0,0 +1,396 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas::{self, Coordinate},
+    data_structures::general_graph::GeneralGraph,
+    renderers::{
+        graph::{GraphView, ACTIVE_COLOR, CURRENT_COLOR, NODE_RADIUS, PATH_COLOR, RELAXED_COLOR},
+        matrix::{MatrixView, CELL_SIZE},
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 8;
+/// Every (k, i, j) step records the whole graph and matrix, n³ frames of n²
+/// cells, and larger matrices no longer fit next to the graph anyway.
+const NODE_LIMIT: usize = 16;
+
+const PIVOT_CELL_COLOR: &str = "#FFF4CC";
+const COMPARED_CELL_COLOR: &str = "#FFD699";
+const UPDATED_CELL_COLOR: &str = "#A1EEBD";
+const UNCHANGED_CELL_COLOR: &str = "#F6D6D6";
+const PATH_CELL_COLOR: &str = "#7BD3EA";
+
+type Frame = (GraphView, MatrixView);
+
+/// Distances between every pair of nodes and the first hop of each shortest
+/// path.
+pub struct AllPairsShortestPaths {
+    pub distances: Vec<Vec<f64>>,
+    pub next: Vec<Vec<Option<usize>>>,
+}
+
+impl AllPairsShortestPaths {
+    /// Any negative distance on the diagonal means a negative cycle.
+    pub fn has_negative_cycle(&self) -> bool {
+        (0..self.distances.len()).any(|node| self.distances[node][node] < 0.0)
+    }
+}
+
+struct FloydWarshall<'a> {
+    graph: &'a GeneralGraph,
+    distances: Vec<Vec<f64>>,
+    next: Vec<Vec<Option<usize>>>,
+    updates: usize,
+    frames: Vec<Frame>,
+}
+
+impl<'a> FloydWarshall<'a> {
+    fn new(graph: &'a GeneralGraph) -> FloydWarshall<'a> {
+        let n = graph.node_count();
+        let mut distances = vec![vec![f64::INFINITY; n]; n];
+        let mut next = vec![vec![None; n]; n];
+
+        for node in 0..n {
+            distances[node][node] = 0.0;
+            next[node][node] = Some(node);
+        }
+
+        for from in 0..n {
+            for (edge, to) in graph.neighbors(from) {
+                let weight = graph.edges()[edge].weight;
+
+                if weight < distances[from][to] {
+                    distances[from][to] = weight;
+                    next[from][to] = Some(to);
+                }
+            }
+        }
+
+        FloydWarshall {
+            graph,
+            distances,
+            next,
+            updates: 0,
+            frames: vec![],
+        }
+    }
+
+    fn run(mut self) -> (AllPairsShortestPaths, Vec<Frame>) {
+        let n = self.graph.node_count();
+
+        let view = self.matrix_view();
+        self.push_frame(
+            GraphView::with_weights(self.graph),
+            view,
+            "initialize with edge weights".to_string(),
+        );
+
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    if i == k || j == k {
+                        continue;
+                    }
+
+                    let candidate = self.distances[i][k] + self.distances[k][j];
+                    let updated = candidate < self.distances[i][j];
+
+                    let message = format!(
+                        "k = {}, i = {}, j = {}: d[i][k] + d[k][j] = {} {} {}",
+                        self.name(k),
+                        self.name(i),
+                        self.name(j),
+                        format_distance(candidate),
+                        if updated { "<" } else { ">=" },
+                        format_distance(self.distances[i][j])
+                    );
+
+                    if updated {
+                        self.distances[i][j] = candidate;
+                        self.next[i][j] = self.next[i][k];
+                        self.updates += 1;
+                    }
+
+                    self.record(k, i, j, updated, message);
+                }
+            }
+        }
+
+        let view = self.matrix_view();
+        self.push_frame(
+            GraphView::with_weights(self.graph),
+            view,
+            "Floyd-Warshall finished".to_string(),
+        );
+
+        let paths = AllPairsShortestPaths {
+            distances: self.distances,
+            next: self.next,
+        };
+
+        (paths, self.frames)
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.graph.vertices()[node].label
+    }
+
+    fn matrix_view(&self) -> MatrixView {
+        distance_matrix(self.graph, &self.distances)
+    }
+
+    fn record(&mut self, k: usize, i: usize, j: usize, updated: bool, message: String) {
+        let n = self.graph.node_count();
+        let mut matrix = self.matrix_view();
+
+        for other in 0..n {
+            matrix.cell_colors[k][other] = PIVOT_CELL_COLOR;
+            matrix.cell_colors[other][k] = PIVOT_CELL_COLOR;
+        }
+
+        matrix.cell_colors[i][k] = COMPARED_CELL_COLOR;
+        matrix.cell_colors[k][j] = COMPARED_CELL_COLOR;
+        matrix.cell_colors[i][j] = if updated {
+            UPDATED_CELL_COLOR
+        } else {
+            UNCHANGED_CELL_COLOR
+        };
+
+        let mut view = GraphView::with_weights(self.graph);
+
+        view.node_colors[k] = ACTIVE_COLOR;
+        view.node_colors[i] = CURRENT_COLOR;
+        view.node_colors[j] = RELAXED_COLOR;
+
+        for (from, to) in [(i, k), (k, j)] {
+            highlight_path(&mut view, self.graph, &path(&self.next, from, to));
+        }
+
+        self.push_frame(view, matrix, message);
+    }
+
+    fn push_frame(&mut self, mut view: GraphView, matrix: MatrixView, message: String) {
+        view.notes = vec![message, format!("updates: {}", self.updates)];
+
+        self.frames.push((view, matrix));
+    }
+}
+
+fn distance_matrix(graph: &GeneralGraph, distances: &[Vec<f64>]) -> MatrixView {
+    let n = graph.node_count();
+    let mut view = MatrixView::new(n, n);
+
+    for (i, row) in distances.iter().enumerate() {
+        view.row_labels[i] = graph.vertices()[i].label.clone();
+        view.column_labels[i] = graph.vertices()[i].label.clone();
+
+        for (j, &distance) in row.iter().enumerate() {
+            view.cells[i][j] = format_distance(distance);
+        }
+    }
+
+    view
+}
+
+fn highlight_path(view: &mut GraphView, graph: &GeneralGraph, nodes: &[usize]) {
+    for pair in nodes.windows(2) {
+        if let Some(edge) = graph.edge_between(pair[0], pair[1]) {
+            view.edge_colors[edge] = PATH_COLOR;
+        }
+    }
+}
+
+fn format_distance(distance: f64) -> String {
+    if distance.is_finite() {
+        format!("{}", distance)
+    } else {
+        "∞".to_string()
+    }
+}
+
+/// Reconstructs the path from `from` to `to` by following the next-hop
+/// matrix. Empty when `to` is unreachable; the walk is capped at `n` hops so
+/// negative cycles cannot loop forever.
+fn path(next: &[Vec<Option<usize>>], from: usize, to: usize) -> Vec<usize> {
+    if next[from][to].is_none() {
+        return vec![];
+    }
+
+    let mut nodes = vec![from];
+    let mut current = from;
+
+    while current != to && nodes.len() <= next.len() {
+        match next[current][to] {
+            Some(hop) => {
+                current = hop;
+                nodes.push(hop);
+            }
+            None => return vec![],
+        }
+    }
+
+    nodes
+}
+
+pub fn floyd_warshall(graph: &GeneralGraph) -> (AllPairsShortestPaths, Vec<Frame>) {
+    FloydWarshall::new(graph).run()
+}
+
+/// The computed all-pairs distances. Paths queried from it are highlighted on
+/// the canvas the animation was drawn on.
+#[wasm_bindgen]
+pub struct FloydWarshallVisualization {
+    graph: GeneralGraph,
+    paths: AllPairsShortestPaths,
+    animation: Animation<Frame>,
+}
+
+#[wasm_bindgen]
+impl FloydWarshallVisualization {
+    pub fn distance(&self, from: usize, to: usize) -> Result<f64, JsValue> {
+        self.check_nodes(from, to)?;
+
+        Ok(self.paths.distances[from][to])
+    }
+
+    /// Nodes on the shortest path from `from` to `to`, empty when `to` is
+    /// unreachable.
+    pub fn path(&self, from: usize, to: usize) -> Result<Vec<usize>, JsValue> {
+        self.check_nodes(from, to)?;
+
+        let nodes = path(&self.paths.next, from, to);
+
+        let mut view = GraphView::with_weights(&self.graph);
+        let mut matrix = distance_matrix(&self.graph, &self.paths.distances);
+
+        matrix.cell_colors[from][to] = PATH_CELL_COLOR;
+
+        for &node in nodes.iter() {
+            view.node_colors[node] = ACTIVE_COLOR;
+        }
+
+        highlight_path(&mut view, &self.graph, &nodes);
+
+        let names = nodes
+            .iter()
+            .map(|&node| self.graph.vertices()[node].label.as_str())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        view.notes = vec![if nodes.is_empty() {
+            "no path".to_string()
+        } else {
+            format!(
+                "path: {} (distance {})",
+                names,
+                format_distance(self.paths.distances[from][to])
+            )
+        }];
+
+        self.animation.push([(view, matrix)]);
+
+        Ok(nodes)
+    }
+
+    /// Any negative distance on the diagonal means a negative cycle.
+    pub fn has_negative_cycle(&self) -> bool {
+        self.paths.has_negative_cycle()
+    }
+
+    fn check_nodes(&self, from: usize, to: usize) -> Result<(), JsValue> {
+        for node in [from, to] {
+            if node >= self.graph.node_count() {
+                return Err(JsValue::from_str(&format!(
+                    "node {} out of range, graph has {} nodes",
+                    node,
+                    self.graph.node_count()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Animates Floyd-Warshall's triple loop with the distance matrix drawn to
+/// the right of the graph, for graphs of at most 16 nodes.
+#[wasm_bindgen]
+pub fn run_floyd_warshall(
+    document_id: &str,
+    graph: &GeneralGraph,
+) -> Result<FloydWarshallVisualization, JsValue> {
+    set_panic_hook();
+
+    if graph.node_count() > NODE_LIMIT {
+        return Err(JsValue::from_str(&format!(
+            "Floyd-Warshall is limited to {} nodes, the graph has {}",
+            NODE_LIMIT,
+            graph.node_count()
+        )));
+    }
+
+    let (paths, frames) = floyd_warshall(graph);
+
+    let ctx = canvas::context(document_id);
+    let drawn_graph = graph.clone();
+    let origin = Coordinate::new(
+        graph.max_x() + NODE_RADIUS + CELL_SIZE * 2.0,
+        CELL_SIZE * 2.0,
+    );
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |(view, matrix): &Frame| {
+        view.draw(&ctx, &drawn_graph);
+        matrix.draw(&ctx, &origin);
+    });
+
+    animation.push(frames);
+
+    Ok(FloydWarshallVisualization {
+        graph: graph.clone(),
+        paths,
+        animation,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_negative_cycle_on_the_diagonal() {
+        let mut graph = GeneralGraph::new(true);
+
+        for _ in 0..3 {
+            graph.add_node(0.0, 0.0);
+        }
+
+        graph.push_edge(0, 1, 1.0);
+        graph.push_edge(1, 2, -3.0);
+        graph.push_edge(2, 0, 1.0);
+
+        let (paths, _) = floyd_warshall(&graph);
+
+        assert!((0..3).all(|node| paths.distances[node][node] < 0.0));
+        assert!(paths.has_negative_cycle());
+    }
+
+    #[test]
+    fn no_negative_cycle_with_non_negative_weights() {
+        let mut graph = GeneralGraph::new(true);
+
+        for _ in 0..3 {
+            graph.add_node(0.0, 0.0);
+        }
+
+        graph.push_edge(0, 1, 1.0);
+        graph.push_edge(1, 2, 2.0);
+        graph.push_edge(2, 0, 1.0);
+
+        let (paths, _) = floyd_warshall(&graph);
+
+        assert_eq!(paths.distances[0][2], 3.0);
+        assert!(!paths.has_negative_cycle());
+    }
+}
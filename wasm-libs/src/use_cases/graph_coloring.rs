@@ -0,0 +1,416 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::general_graph::GeneralGraph,
+    renderers::graph::{palette_color, GraphView, CURRENT_COLOR, HIGHLIGHT_COLOR, PALETTE},
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+/// The exact solver is exponential, so it only runs on graphs this small.
+const EXACT_NODE_LIMIT: usize = 12;
+/// The exact solver stops recording frames after this many, but keeps
+/// searching.
+const MAX_EXACT_FRAMES: usize = 400;
+
+const ALGORITHMS: [&str; 4] = ["greedy", "welsh-powell", "dsatur", "exact"];
+
+struct Coloring<'a> {
+    graph: &'a GeneralGraph,
+    /// Neighbors ignoring edge direction and self-loops.
+    adjacency: Vec<Vec<usize>>,
+    colors: Vec<Option<usize>>,
+    frames: Vec<GraphView>,
+}
+
+impl<'a> Coloring<'a> {
+    fn new(graph: &'a GeneralGraph) -> Coloring<'a> {
+        let mut adjacency = vec![vec![]; graph.node_count()];
+
+        for edge in graph.edges() {
+            if edge.from != edge.to && !adjacency[edge.from].contains(&edge.to) {
+                adjacency[edge.from].push(edge.to);
+                adjacency[edge.to].push(edge.from);
+            }
+        }
+
+        Coloring {
+            graph,
+            adjacency,
+            colors: vec![None; graph.node_count()],
+            frames: vec![],
+        }
+    }
+
+    fn run(&mut self, algorithm: &str) {
+        match algorithm {
+            "greedy" => self.greedy(),
+            "welsh-powell" => self.welsh_powell(),
+            "dsatur" => self.dsatur(),
+            "exact" => self.exact(),
+            _ => unreachable!("algorithm names are checked by the caller"),
+        }
+
+        self.record(
+            None,
+            format!("{}: {} colors", algorithm, self.color_count()),
+        );
+    }
+
+    /// Colors nodes in index order with the smallest color not used by a
+    /// neighbor.
+    fn greedy(&mut self) {
+        for node in 0..self.graph.node_count() {
+            self.color_smallest_available(node, "greedy");
+        }
+    }
+
+    /// Sweeps the nodes by decreasing degree once per color, giving the
+    /// color to every node not adjacent to one that already has it.
+    fn welsh_powell(&mut self) {
+        let mut order = (0..self.graph.node_count()).collect::<Vec<_>>();
+        order.sort_by_key(|&node| std::cmp::Reverse(self.adjacency[node].len()));
+
+        let degrees = (0..self.graph.node_count())
+            .map(|node| format!("deg {}", self.adjacency[node].len()))
+            .collect();
+
+        self.record_with_labels(
+            None,
+            "Welsh-Powell: order nodes by decreasing degree".to_string(),
+            degrees,
+        );
+
+        let mut color = 0;
+
+        while self.colors.iter().any(|color| color.is_none()) {
+            self.record(None, format!("Welsh-Powell: sweep for color {}", color));
+
+            for &node in order.iter() {
+                if self.colors[node].is_some() {
+                    continue;
+                }
+
+                let conflict = self.adjacency[node]
+                    .iter()
+                    .find(|&&neighbor| self.colors[neighbor] == Some(color));
+
+                let message = match conflict {
+                    Some(&neighbor) => format!(
+                        "color {}: {} skipped, neighbor {} has it",
+                        color,
+                        self.name(node),
+                        self.name(neighbor)
+                    ),
+                    None => {
+                        self.colors[node] = Some(color);
+                        format!("color {}: {} takes it", color, self.name(node))
+                    }
+                };
+
+                self.record(Some(node), message);
+            }
+
+            color += 1;
+        }
+    }
+
+    /// Repeatedly colors the node whose neighbors already use the most
+    /// distinct colors (its saturation), breaking ties by degree.
+    fn dsatur(&mut self) {
+        while let Some(node) = (0..self.graph.node_count())
+            .filter(|&node| self.colors[node].is_none())
+            .max_by_key(|&node| (self.saturation(node), self.adjacency[node].len()))
+        {
+            let saturations = (0..self.graph.node_count())
+                .map(|other| {
+                    if self.colors[other].is_none() {
+                        format!("sat {}", self.saturation(other))
+                    } else {
+                        String::new()
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            self.record_with_labels(
+                Some(node),
+                format!(
+                    "DSatur: {} has the highest saturation {}",
+                    self.name(node),
+                    self.saturation(node)
+                ),
+                saturations,
+            );
+
+            self.color_smallest_available(node, "DSatur");
+        }
+    }
+
+    /// Branch and bound over colorings, seeded with the DSatur result as the
+    /// best known solution.
+    fn exact(&mut self) {
+        let mut seed = Coloring::new(self.graph);
+        seed.dsatur();
+
+        let mut best = seed.colors.clone();
+        let mut best_count = seed.color_count();
+
+        self.record(
+            None,
+            format!("exact: DSatur gives {} colors, try to beat it", best_count),
+        );
+
+        let mut order = (0..self.graph.node_count()).collect::<Vec<_>>();
+        order.sort_by_key(|&node| std::cmp::Reverse(self.adjacency[node].len()));
+
+        self.branch(&order, 0, 0, &mut best, &mut best_count);
+
+        self.colors = best;
+        self.record(None, format!("exact: optimum is {} colors", best_count));
+    }
+
+    fn branch(
+        &mut self,
+        order: &[usize],
+        position: usize,
+        used: usize,
+        best: &mut Vec<Option<usize>>,
+        best_count: &mut usize,
+    ) {
+        if used >= *best_count {
+            return;
+        }
+
+        if position == order.len() {
+            *best = self.colors.clone();
+            *best_count = used;
+
+            self.record_capped(
+                None,
+                format!("exact: found a coloring with {} colors", used),
+            );
+            return;
+        }
+
+        let node = order[position];
+
+        // Trying one new color beyond the ones in use is enough, since
+        // colors are interchangeable.
+        for color in 0..(used + 1).min(*best_count - 1) {
+            if self.adjacency[node]
+                .iter()
+                .any(|&neighbor| self.colors[neighbor] == Some(color))
+            {
+                continue;
+            }
+
+            self.colors[node] = Some(color);
+            self.record_capped(
+                Some(node),
+                format!("exact: try color {} for {}", color, self.name(node)),
+            );
+
+            self.branch(order, position + 1, used.max(color + 1), best, best_count);
+
+            self.colors[node] = None;
+        }
+    }
+
+    fn color_smallest_available(&mut self, node: usize, algorithm: &str) {
+        let mut taken = self.adjacency[node]
+            .iter()
+            .filter_map(|&neighbor| self.colors[neighbor])
+            .collect::<Vec<_>>();
+
+        taken.sort_unstable();
+        taken.dedup();
+
+        let color = (0..).find(|color| !taken.contains(color)).unwrap();
+        self.colors[node] = Some(color);
+
+        self.record(
+            Some(node),
+            format!(
+                "{}: neighbors of {} use {:?}, pick {}",
+                algorithm,
+                self.name(node),
+                taken,
+                color
+            ),
+        );
+    }
+
+    fn saturation(&self, node: usize) -> usize {
+        let mut colors = self.adjacency[node]
+            .iter()
+            .filter_map(|&neighbor| self.colors[neighbor])
+            .collect::<Vec<_>>();
+
+        colors.sort_unstable();
+        colors.dedup();
+
+        colors.len()
+    }
+
+    fn color_count(&self) -> usize {
+        self.colors
+            .iter()
+            .filter_map(|&color| color)
+            .max()
+            .map_or(0, |max| max + 1)
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.graph.vertices()[node].label
+    }
+
+    fn record_capped(&mut self, current: Option<usize>, message: String) {
+        if self.frames.len() < MAX_EXACT_FRAMES {
+            self.record(current, message);
+        }
+    }
+
+    /// Records a frame keeping the node labels of the previous one.
+    fn record(&mut self, current: Option<usize>, message: String) {
+        let labels = self
+            .frames
+            .last()
+            .map_or(vec![String::new(); self.graph.node_count()], |view| {
+                view.node_labels.clone()
+            });
+
+        self.record_with_labels(current, message, labels);
+    }
+
+    fn record_with_labels(&mut self, current: Option<usize>, message: String, labels: Vec<String>) {
+        let mut view = GraphView::new(self.graph);
+
+        for (node, color) in self.colors.iter().enumerate() {
+            if let Some(color) = color {
+                view.node_colors[node] = palette_color(*color);
+            }
+        }
+
+        view.node_labels = labels;
+
+        if let Some(node) = current {
+            if self.colors[node].is_none() {
+                view.node_colors[node] = CURRENT_COLOR;
+            }
+
+            for &edge in self.graph.incident_edges(node) {
+                view.edge_colors[edge] = HIGHLIGHT_COLOR;
+            }
+        }
+
+        view.notes = vec![message, format!("colors used: {}", self.color_count())];
+
+        self.frames.push(view);
+    }
+}
+
+fn check_algorithm(graph: &GeneralGraph, algorithm: &str) -> Result<(), JsValue> {
+    if !ALGORITHMS.contains(&algorithm) {
+        return Err(JsValue::from_str(&format!(
+            "unknown coloring algorithm `{}`, expected one of {}",
+            algorithm,
+            ALGORITHMS.join(", ")
+        )));
+    }
+
+    if algorithm == "exact" && graph.node_count() > EXACT_NODE_LIMIT {
+        return Err(JsValue::from_str(&format!(
+            "the exact solver is limited to {} nodes, the graph has {}",
+            EXACT_NODE_LIMIT,
+            graph.node_count()
+        )));
+    }
+
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub struct ColoringResult {
+    colors: Vec<usize>,
+    color_count: usize,
+}
+
+#[wasm_bindgen]
+impl ColoringResult {
+    /// The color index of every node.
+    pub fn colors(&self) -> Vec<usize> {
+        self.colors.clone()
+    }
+
+    pub fn color_count(&self) -> usize {
+        self.color_count
+    }
+}
+
+/// Animates a vertex coloring with `"greedy"`, `"welsh-powell"`, `"dsatur"`
+/// or, for small graphs, the `"exact"` solver. Edge directions are ignored.
+#[wasm_bindgen]
+pub fn run_graph_coloring(
+    document_id: &str,
+    graph: &GeneralGraph,
+    algorithm: &str,
+) -> Result<ColoringResult, JsValue> {
+    set_panic_hook();
+
+    check_algorithm(graph, algorithm)?;
+
+    let mut coloring = Coloring::new(graph);
+    coloring.run(algorithm);
+
+    if coloring.color_count() > PALETTE.len() {
+        coloring
+            .frames
+            .last_mut()
+            .unwrap()
+            .notes
+            .push("more colors than the palette has, some colors repeat".to_string());
+    }
+
+    let result = ColoringResult {
+        colors: coloring.colors.iter().map(|color| color.unwrap()).collect(),
+        color_count: coloring.color_count(),
+    };
+
+    let ctx = canvas::context(document_id);
+    let graph = graph.clone();
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &GraphView| {
+        view.draw(&ctx, &graph)
+    });
+
+    animation.push(coloring.frames);
+
+    Ok(result)
+}
+
+/// Runs every heuristic, and the exact solver when the graph is small
+/// enough, returning an object mapping algorithm names to color counts.
+#[wasm_bindgen]
+pub fn compare_graph_colorings(graph: &GeneralGraph) -> js_sys::Object {
+    let counts = js_sys::Object::new();
+
+    for algorithm in ALGORITHMS {
+        if check_algorithm(graph, algorithm).is_err() {
+            continue;
+        }
+
+        let mut coloring = Coloring::new(graph);
+        coloring.run(algorithm);
+
+        let _ = js_sys::Reflect::set(
+            &counts,
+            &JsValue::from_str(algorithm),
+            &JsValue::from(coloring.color_count() as u32),
+        );
+    }
+
+    counts
+}
@@ -0,0 +1,437 @@
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::hash_table::{Collision, HashTable, Insertion, Location, Slot},
+    renderers::{
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR},
+        hash_table::HashTableView,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+/// Larger starting tables no longer fit on the canvas.
+const MAX_CAPACITY: usize = 128;
+/// Keys still waiting during a rehash listed before the rest is counted.
+const WAITING_SHOWN: usize = 12;
+
+const STRATEGIES: [&str; 4] = ["chaining", "linear", "quadratic", "double"];
+
+/// A hash table with the frames recorded while operating on it.
+struct Hashing {
+    table: HashTable,
+    max_load: f64,
+    frames: Vec<HashTableView>,
+}
+
+impl Hashing {
+    fn record(
+        &mut self,
+        current: Option<Location>,
+        passed: &[Location],
+        message: String,
+        detail: Option<String>,
+    ) {
+        self.record_with(
+            current.map(|location| (location, CURRENT_COLOR)),
+            passed,
+            message,
+            detail,
+        );
+    }
+
+    /// Records the table with the `passed` locations marked as probed and
+    /// `current` standing out. A chain position past the end of its chain
+    /// marks the bucket itself.
+    fn record_with(
+        &mut self,
+        current: Option<(Location, &'static str)>,
+        passed: &[Location],
+        message: String,
+        detail: Option<String>,
+    ) {
+        let mut view = HashTableView::new(&self.table);
+
+        for &location in passed {
+            view.highlight(location, ACTIVE_COLOR);
+        }
+
+        if let Some((location, color)) = current {
+            if view.chained && location.position >= view.buckets[location.bucket].chain.len() {
+                view.buckets[location.bucket].color = color;
+            } else {
+                view.highlight(location, color);
+            }
+        }
+
+        view.notes = vec![
+            message,
+            format!(
+                "keys: {}, buckets: {}, load factor: {:.2} (resize above {:.2})",
+                self.table.len(),
+                self.table.capacity(),
+                self.table.load_factor(),
+                self.max_load
+            ),
+        ];
+
+        if self.table.tombstones() > 0 {
+            view.notes[1] += &format!(", tombstones: {}", self.table.tombstones());
+        }
+
+        view.notes.extend(detail);
+
+        self.frames.push(view);
+    }
+
+    /// The hash of `key` and, for open addressing, how it probes.
+    fn hash_message(&self, key: i32) -> String {
+        let home = self.table.home(key);
+        let m = self.table.capacity();
+
+        match self.table.collision() {
+            Collision::Chaining => {
+                format!("h({}) = {} mod {} = {}: scan its chain", key, key, m, home)
+            }
+            Collision::Linear => format!("h({}) = {} mod {} = {}, probe h + i", key, key, m, home),
+            Collision::Quadratic => {
+                format!("h({}) = {} mod {} = {}, probe h + i²", key, key, m, home)
+            }
+            Collision::Double => format!(
+                "h({}) = {} mod {} = {}, step 1 + {} mod {} = {}, probe h + i·step",
+                key,
+                key,
+                m,
+                home,
+                key,
+                m - 1,
+                self.table.step(key)
+            ),
+        }
+    }
+
+    /// Records the hash of `key`, then every location in `path`.
+    fn record_probes(&mut self, key: i32, path: &[Location]) {
+        let bucket = self.table.home(key);
+        let home = Location {
+            bucket,
+            position: self.table.chain(bucket).len(),
+        };
+
+        self.record(Some(home), &[], self.hash_message(key), None);
+
+        for (step, &location) in path.iter().enumerate() {
+            let message = match self.table.collision() {
+                Collision::Chaining => format!(
+                    "chain {} position {}: {}",
+                    location.bucket,
+                    location.position,
+                    self.table.chain(location.bucket)[location.position]
+                ),
+                _ => match self.table.slot(location.bucket) {
+                    Slot::Empty => format!("probe {}: bucket {} is empty", step, location.bucket),
+                    Slot::Tombstone => format!(
+                        "probe {}: bucket {} holds a tombstone, keep probing",
+                        step, location.bucket
+                    ),
+                    Slot::Occupied(other) => {
+                        format!("probe {}: bucket {} holds {}", step, location.bucket, other)
+                    }
+                },
+            };
+
+            self.record(Some(location), &path[..step], message, None);
+        }
+    }
+
+    fn insert(&mut self, key: i32) -> bool {
+        let (path, _) = self.table.find(key);
+
+        self.record_probes(key, &path);
+
+        match self.table.insert(key).1 {
+            Insertion::Stored(location) => {
+                self.record_with(
+                    Some((location, RELAXED_COLOR)),
+                    &[],
+                    format!("store {} in bucket {}", key, location.bucket),
+                    None,
+                );
+
+                if self.table.load_factor() > self.max_load {
+                    self.rehash(format!(
+                        "load factor {:.2} is above {:.2}",
+                        self.table.load_factor(),
+                        self.max_load
+                    ));
+                }
+
+                true
+            }
+            Insertion::Duplicate(location) => {
+                self.record_with(
+                    Some((location, PATH_COLOR)),
+                    &[],
+                    format!("{} is already in the table", key),
+                    None,
+                );
+
+                false
+            }
+            Insertion::Full => {
+                self.rehash(format!("no free bucket on the probe sequence of {}", key));
+
+                self.insert(key)
+            }
+        }
+    }
+
+    fn lookup(&mut self, key: i32) -> bool {
+        let (path, found) = self.table.find(key);
+
+        self.record_probes(key, &path);
+
+        match found {
+            Some(location) => self.record_with(
+                Some((location, PATH_COLOR)),
+                &[],
+                format!("found {} after {} probes", key, path.len()),
+                None,
+            ),
+            None => self.record(None, &path, format!("{} is not in the table", key), None),
+        }
+
+        found.is_some()
+    }
+
+    fn remove(&mut self, key: i32) -> bool {
+        let (path, found) = self.table.find(key);
+
+        self.record_probes(key, &path);
+
+        let Some(location) = found else {
+            self.record(None, &path, format!("{} is not in the table", key), None);
+            return false;
+        };
+
+        self.record_with(
+            Some((location, HIGHLIGHT_COLOR)),
+            &[],
+            format!("found {}", key),
+            None,
+        );
+
+        self.table.remove(key);
+
+        // The keys after it in its chain moved up, so the bucket is marked
+        // instead.
+        let (location, message) = if self.table.collision() == Collision::Chaining {
+            (
+                Location {
+                    bucket: location.bucket,
+                    position: self.table.chain(location.bucket).len(),
+                },
+                format!("unlink {} from chain {}", key, location.bucket),
+            )
+        } else {
+            (
+                location,
+                format!(
+                    "leave a tombstone in bucket {} so later probes continue past it",
+                    location.bucket
+                ),
+            )
+        };
+
+        self.record(Some(location), &[], message, None);
+
+        true
+    }
+
+    /// Moves every key into a table with about twice the buckets, which
+    /// also drops the tombstones, showing the keys still waiting.
+    fn rehash(&mut self, reason: String) {
+        let mut waiting = VecDeque::from(self.table.keys());
+        let grown = self.table.grown();
+
+        self.record(
+            None,
+            &[],
+            format!("{}: rehash into {} buckets", reason, grown.capacity()),
+            None,
+        );
+
+        self.table = grown;
+
+        while let Some(key) = waiting.pop_front() {
+            if let (_, Insertion::Stored(location)) = self.table.insert(key) {
+                let mut detail = format!(
+                    "waiting: {}",
+                    waiting
+                        .iter()
+                        .take(WAITING_SHOWN)
+                        .map(|key| key.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+
+                if waiting.len() > WAITING_SHOWN {
+                    detail += &format!(" and {} more", waiting.len() - WAITING_SHOWN);
+                }
+
+                self.record_with(
+                    Some((location, RELAXED_COLOR)),
+                    &[],
+                    format!("rehash {} into bucket {}", key, location.bucket),
+                    Some(detail),
+                );
+            } else {
+                // Quadratic probing can still miss a free bucket; start over
+                // with an even larger table.
+                waiting = self
+                    .table
+                    .keys()
+                    .into_iter()
+                    .chain([key])
+                    .chain(waiting)
+                    .collect();
+                self.table = self.table.grown();
+            }
+        }
+
+        self.record(None, &[], "rehash done".to_string(), None);
+    }
+}
+
+/// A hash table drawn as a column of buckets, animating the probes of every
+/// operation and growing past a maximum load factor.
+#[wasm_bindgen]
+pub struct HashTableVisualization {
+    hashing: Hashing,
+    animation: Animation<HashTableView>,
+}
+
+#[wasm_bindgen]
+impl HashTableVisualization {
+    /// Adds `key`, returning `false` if it was already there.
+    pub fn insert(&mut self, key: i32) -> bool {
+        let inserted = self.hashing.insert(key);
+        self.flush();
+
+        inserted
+    }
+
+    pub fn lookup(&mut self, key: i32) -> bool {
+        let found = self.hashing.lookup(key);
+        self.flush();
+
+        found
+    }
+
+    /// Removes `key`, returning `false` if it was not there.
+    pub fn remove(&mut self, key: i32) -> bool {
+        let removed = self.hashing.remove(key);
+        self.flush();
+
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashing.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashing.table.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.hashing.table.capacity()
+    }
+
+    pub fn load_factor(&self) -> f64 {
+        self.hashing.table.load_factor()
+    }
+
+    pub fn keys(&self) -> Vec<i32> {
+        self.hashing.table.keys()
+    }
+
+    fn flush(&mut self) {
+        self.animation.push(self.hashing.frames.drain(..));
+    }
+}
+
+/// Starts a hash table visualization with the first prime number of
+/// buckets from `capacity`, at most 128, on, resolving collisions by `"chaining"`,
+/// `"linear"` or `"quadratic"` probing or `"double"` hashing, and rehashing
+/// into a larger table when the load factor exceeds `max_load`. `keys` are
+/// inserted one after another.
+#[wasm_bindgen]
+pub fn run_hash_table(
+    document_id: &str,
+    strategy: &str,
+    capacity: usize,
+    max_load: f64,
+    keys: Vec<i32>,
+) -> Result<HashTableVisualization, JsValue> {
+    set_panic_hook();
+
+    let collision = match strategy {
+        "chaining" => Collision::Chaining,
+        "linear" => Collision::Linear,
+        "quadratic" => Collision::Quadratic,
+        "double" => Collision::Double,
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown collision strategy `{}`, expected one of {}",
+                strategy,
+                STRATEGIES.join(", ")
+            )))
+        }
+    };
+
+    if capacity > MAX_CAPACITY {
+        return Err(JsValue::from_str(&format!(
+            "capacity {} is above the {} buckets that fit on the canvas",
+            capacity, MAX_CAPACITY
+        )));
+    }
+
+    let open_addressing = collision != Collision::Chaining;
+
+    if !(max_load > 0.0 && (max_load < 1.0 || !open_addressing)) {
+        return Err(JsValue::from_str(&format!(
+            "maximum load factor must be above 0{}, got {}",
+            if open_addressing { " and below 1" } else { "" },
+            max_load
+        )));
+    }
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &HashTableView| view.draw(&ctx));
+
+    let mut visualization = HashTableVisualization {
+        hashing: Hashing {
+            table: HashTable::new(collision, capacity),
+            max_load,
+            frames: vec![],
+        },
+        animation,
+    };
+
+    visualization
+        .hashing
+        .record(None, &[], "empty table".to_string(), None);
+
+    for key in keys {
+        visualization.hashing.insert(key);
+    }
+
+    visualization.flush();
+
+    Ok(visualization)
+}
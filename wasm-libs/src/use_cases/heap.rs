@@ -0,0 +1,253 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::binary_heap::{BinaryHeap, HeapStep},
+    renderers::{
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, NODE_COLOR, PATH_COLOR},
+        heap::HeapView,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+const KINDS: [&str; 2] = ["min", "max"];
+
+/// Replays the steps of one heap operation on a copy of the array, one
+/// frame per step, counting comparisons and swaps.
+struct Replay {
+    values: Vec<i32>,
+    comparisons: usize,
+    swaps: usize,
+    /// A line of notes describing the whole operation.
+    summary: String,
+    frames: Vec<HeapView>,
+}
+
+impl Replay {
+    fn new(values: &[i32], summary: String) -> Replay {
+        Replay {
+            values: values.to_vec(),
+            comparisons: 0,
+            swaps: 0,
+            summary,
+            frames: vec![],
+        }
+    }
+
+    fn run(&mut self, steps: &[HeapStep]) {
+        for &step in steps {
+            match step {
+                HeapStep::Append(value) => {
+                    self.values.push(value);
+                    self.record(
+                        &[(self.values.len() - 1, ACTIVE_COLOR)],
+                        format!("append {} at index {}", value, self.values.len() - 1),
+                    );
+                }
+                HeapStep::RemoveLast => {
+                    let value = self.values.pop().unwrap();
+
+                    self.record(&[], format!("remove {} from the end", value));
+                }
+                HeapStep::SiftDown(idx) => self.record(
+                    &[(idx, ACTIVE_COLOR)],
+                    format!("sift down the subtree at index {}", idx),
+                ),
+                HeapStep::Compare(a, b) => {
+                    self.comparisons += 1;
+                    self.record(
+                        &[(a, CURRENT_COLOR), (b, CURRENT_COLOR)],
+                        format!(
+                            "compare a[{}] = {} with a[{}] = {}",
+                            a, self.values[a], b, self.values[b]
+                        ),
+                    );
+                }
+                HeapStep::Swap(a, b) => {
+                    self.values.swap(a, b);
+                    self.swaps += 1;
+                    self.record(
+                        &[(a, HIGHLIGHT_COLOR), (b, HIGHLIGHT_COLOR)],
+                        format!("swap a[{}] and a[{}]", a, b),
+                    );
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, highlighted: &[(usize, &'static str)], message: String) {
+        let mut colors = vec![NODE_COLOR; self.values.len()];
+
+        for &(idx, color) in highlighted {
+            colors[idx] = color;
+        }
+
+        self.frames.push(HeapView {
+            values: self.values.clone(),
+            colors,
+            notes: vec![
+                message,
+                format!("comparisons: {}, swaps: {}", self.comparisons, self.swaps),
+                self.summary.clone(),
+            ],
+        });
+    }
+}
+
+/// A min- or max-heap drawn as an array strip above its tree. Each
+/// operation is animated step by step and its cost reported.
+#[wasm_bindgen]
+pub struct HeapVisualization {
+    heap: BinaryHeap,
+    comparisons: usize,
+    swaps: usize,
+    animation: Animation<HeapView>,
+}
+
+#[wasm_bindgen]
+impl HeapVisualization {
+    /// Appends `value` and sifts it up.
+    pub fn push(&mut self, value: i32) {
+        let before = self.heap.values().to_vec();
+        let steps = self.heap.push(value);
+
+        self.replay(
+            Replay::new(
+                &before,
+                format!("push {}: sift up from the last leaf", value),
+            ),
+            &steps,
+            false,
+        );
+    }
+
+    /// Removes the root, moving the last value up and sifting it down.
+    pub fn pop(&mut self) -> Option<i32> {
+        let before = self.heap.values().to_vec();
+        let (top, steps) = self.heap.pop();
+
+        let summary = match top {
+            Some(top) => format!("pop {}: move the last value to the root, sift down", top),
+            None => "pop: the heap is empty".to_string(),
+        };
+
+        self.replay(Replay::new(&before, summary), &steps, false);
+
+        top
+    }
+
+    pub fn peek(&self) -> Option<i32> {
+        self.heap.peek()
+    }
+
+    /// The heap array.
+    pub fn values(&self) -> Vec<i32> {
+        self.heap.values().to_vec()
+    }
+
+    /// Replaces the heap by one built from `values` with build-heap and
+    /// returns the comparisons and swaps it took, at most `3n`.
+    pub fn build(&mut self, values: Vec<i32>) -> usize {
+        let (heap, steps) = BinaryHeap::build(values.clone(), self.heap.is_max());
+
+        let mut replay = Replay::new(&values, format!("build-heap of {} values", values.len()));
+        replay.record(&[], "start from the unordered array".to_string());
+
+        self.heap = heap;
+        self.replay(replay, &steps, true);
+
+        self.comparisons + self.swaps
+    }
+
+    /// Comparisons made by the last operation.
+    pub fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    /// Swaps made by the last operation.
+    pub fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    /// Plays `steps` back from the state of `replay` and finishes with the
+    /// cost of the operation, relative to `n` for build-heap.
+    fn replay(&mut self, mut replay: Replay, steps: &[HeapStep], build: bool) {
+        replay.run(steps);
+
+        let operations = replay.comparisons + replay.swaps;
+        let mut colors = vec![NODE_COLOR; replay.values.len()];
+
+        if let Some(root) = colors.first_mut() {
+            *root = PATH_COLOR;
+        }
+
+        replay.frames.push(HeapView {
+            values: replay.values.clone(),
+            colors,
+            notes: vec![
+                format!(
+                    "done: {} at the root",
+                    if self.heap.is_max() {
+                        "maximum"
+                    } else {
+                        "minimum"
+                    }
+                ),
+                format!(
+                    "comparisons: {}, swaps: {}",
+                    replay.comparisons, replay.swaps
+                ),
+                if build {
+                    format!(
+                        "build-heap: {} operations for n = {}, {:.2} per value",
+                        operations,
+                        replay.values.len(),
+                        operations as f64 / replay.values.len().max(1) as f64
+                    )
+                } else {
+                    replay.summary.clone()
+                },
+            ],
+        });
+
+        self.comparisons = replay.comparisons;
+        self.swaps = replay.swaps;
+        self.animation.push(replay.frames);
+    }
+}
+
+/// Starts a `"min"` or `"max"` heap visualization by animating build-heap
+/// over `values`.
+#[wasm_bindgen]
+pub fn run_heap(
+    document_id: &str,
+    kind: &str,
+    values: Vec<i32>,
+) -> Result<HeapVisualization, JsValue> {
+    set_panic_hook();
+
+    if !KINDS.contains(&kind) {
+        return Err(JsValue::from_str(&format!(
+            "unknown heap `{}`, expected one of {}",
+            kind,
+            KINDS.join(", ")
+        )));
+    }
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &HeapView| view.draw(&ctx));
+
+    let mut visualization = HeapVisualization {
+        heap: BinaryHeap::new(kind == "max"),
+        comparisons: 0,
+        swaps: 0,
+        animation,
+    };
+
+    visualization.build(values);
+
+    Ok(visualization)
+}
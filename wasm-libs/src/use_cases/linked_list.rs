@@ -0,0 +1,468 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::linked_list::{LinkedList, ListStep, Queue, Stack},
+    renderers::{
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR},
+        list::ListView,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+const KINDS: [&str; 4] = ["singly", "doubly", "stack", "queue"];
+
+enum Structure {
+    List(LinkedList),
+    Stack(Stack),
+    Queue(Queue),
+}
+
+impl Structure {
+    fn list(&self) -> &LinkedList {
+        match self {
+            Structure::List(list) => list,
+            Structure::Stack(stack) => stack.list(),
+            Structure::Queue(queue) => queue.list(),
+        }
+    }
+
+    /// What the head and tail pointers are called.
+    fn labels(&self) -> (&'static str, Option<&'static str>) {
+        match self {
+            Structure::List(_) => ("head", Some("tail")),
+            Structure::Stack(_) => ("top", None),
+            Structure::Queue(_) => ("front", Some("back")),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Structure::List(list) if list.is_doubly() => "doubly linked list",
+            Structure::List(_) => "singly linked list",
+            Structure::Stack(_) => "stack",
+            Structure::Queue(_) => "queue",
+        }
+    }
+}
+
+/// Replays the steps of one list operation on a copy of the list, one frame
+/// per step. Nodes keep their columns throughout, so only the pointers move
+/// until the final frame lays the list out again.
+struct Replay {
+    list: LinkedList,
+    columns: Vec<Option<f64>>,
+    /// Whether nodes cut off from the head drop below the list. Reversing
+    /// cuts off every node on the way, so it keeps them in place.
+    lift_unreachable: bool,
+    labels: (&'static str, Option<&'static str>),
+    visited: Vec<usize>,
+    cursors: Vec<(&'static str, usize)>,
+    visits: usize,
+    pointer_writes: usize,
+    summary: String,
+    frames: Vec<ListView>,
+}
+
+impl Replay {
+    /// Lays the nodes out in the order of `before` or, if the operation
+    /// allocates a node, of `after`, so that a new node shows up below the
+    /// gap it will be linked into.
+    fn new(
+        structure: &Structure,
+        before: &LinkedList,
+        steps: &[ListStep],
+        summary: String,
+    ) -> Replay {
+        let after = structure.list();
+        let allocates = steps.iter().any(|step| matches!(step, ListStep::Alloc(..)));
+        let layout = if allocates { after } else { before };
+
+        let mut columns = vec![None; before.capacity().max(after.capacity())];
+
+        for (column, id) in layout.ids().into_iter().enumerate() {
+            columns[id] = Some(column as f64);
+        }
+
+        Replay {
+            list: before.clone(),
+            columns,
+            lift_unreachable: !steps
+                .iter()
+                .any(|step| matches!(step, ListStep::Cursor(..))),
+            labels: structure.labels(),
+            visited: vec![],
+            cursors: vec![],
+            visits: 0,
+            pointer_writes: 0,
+            summary,
+            frames: vec![],
+        }
+    }
+
+    fn value(&self, id: Option<usize>) -> String {
+        match id {
+            Some(id) => self.list.node(id).value.to_string(),
+            None => "null".to_string(),
+        }
+    }
+
+    fn run(&mut self, steps: &[ListStep]) {
+        for &step in steps {
+            // Describe a freed node while it is still there.
+            let message = match step {
+                ListStep::Free(id) => format!("free the node of {}", self.value(Some(id))),
+                _ => String::new(),
+            };
+
+            self.list.apply(step);
+
+            match step {
+                ListStep::Visit(id) => {
+                    self.visits += 1;
+                    self.record(
+                        Some((id, CURRENT_COLOR)),
+                        None,
+                        format!("visit {}", self.value(Some(id))),
+                    );
+                    self.visited.push(id);
+                }
+                ListStep::Cursor(name, id) => {
+                    self.cursors.retain(|&(other, _)| other != name);
+                    self.cursors.extend(id.map(|id| (name, id)));
+                    self.record(None, None, format!("{} = {}", name, self.value(id)));
+                }
+                ListStep::Alloc(id, value) => self.record(
+                    Some((id, RELAXED_COLOR)),
+                    None,
+                    format!("new node {}", value),
+                ),
+                ListStep::SetNext(id, next) => {
+                    self.pointer_writes += 1;
+                    self.record(
+                        None,
+                        Some((id, true)),
+                        format!("{}.next = {}", self.value(Some(id)), self.value(next)),
+                    );
+                }
+                ListStep::SetPrev(id, prev) => {
+                    self.pointer_writes += 1;
+                    self.record(
+                        None,
+                        Some((id, false)),
+                        format!("{}.prev = {}", self.value(Some(id)), self.value(prev)),
+                    );
+                }
+                ListStep::SetHead(head) => {
+                    self.pointer_writes += 1;
+                    self.record(
+                        head.map(|head| (head, HIGHLIGHT_COLOR)),
+                        None,
+                        format!("{} = {}", self.labels.0, self.value(head)),
+                    );
+                }
+                ListStep::SetTail(tail) => {
+                    self.pointer_writes += 1;
+                    self.record(
+                        tail.map(|tail| (tail, HIGHLIGHT_COLOR)),
+                        None,
+                        format!("{} = {}", self.labels.1.unwrap_or("tail"), self.value(tail)),
+                    );
+                }
+                ListStep::Free(_) => self.record(None, None, message),
+            }
+        }
+    }
+
+    /// Records the copy with the visited nodes marked, `current` standing
+    /// out and the `next` (`true`) or `prev` pointer of `pointer` just set.
+    fn record(
+        &mut self,
+        current: Option<(usize, &'static str)>,
+        pointer: Option<(usize, bool)>,
+        message: String,
+    ) {
+        let mut view = ListView::new(&self.list, self.labels.0, self.labels.1);
+        let reachable = self.list.ids();
+
+        for id in 0..view.nodes.len() {
+            let lifted = self.lift_unreachable && !reachable.contains(&id);
+            let column = self.columns.get(id).copied().flatten();
+
+            if let Some(node) = view.node_mut(id) {
+                if let Some(column) = column {
+                    node.column = column;
+                }
+
+                node.row = if lifted { 1.0 } else { 0.0 };
+            }
+        }
+
+        for &id in &self.visited {
+            if let Some(node) = view.node_mut(id) {
+                node.color = ACTIVE_COLOR;
+            }
+        }
+
+        for &(name, id) in &self.cursors {
+            if let Some(node) = view.node_mut(id) {
+                node.cursors.push(name);
+            }
+        }
+
+        if let Some((id, color)) = current {
+            if let Some(node) = view.node_mut(id) {
+                node.color = color;
+            }
+        }
+
+        if let Some((id, next)) = pointer {
+            if let Some(node) = view.node_mut(id) {
+                if next {
+                    node.next_color = HIGHLIGHT_COLOR;
+                } else {
+                    node.prev_color = HIGHLIGHT_COLOR;
+                }
+            }
+        }
+
+        view.notes = vec![
+            message,
+            format!(
+                "nodes visited: {}, pointer writes: {}",
+                self.visits, self.pointer_writes
+            ),
+            self.summary.clone(),
+        ];
+
+        self.frames.push(view);
+    }
+}
+
+/// A singly or doubly linked list, a stack or a queue drawn as boxes with
+/// arrow pointers, animating how every operation rewires the pointers.
+#[wasm_bindgen]
+pub struct LinkedListVisualization {
+    structure: Structure,
+    animation: Animation<ListView>,
+}
+
+#[wasm_bindgen]
+impl LinkedListVisualization {
+    /// Pushes onto a stack, enqueues into a queue and appends to a list.
+    pub fn push(&mut self, value: i32) {
+        let before = self.structure.list().clone();
+
+        let steps = match &mut self.structure {
+            Structure::List(list) => list.push_back(value),
+            Structure::Stack(stack) => stack.push(value),
+            Structure::Queue(queue) => queue.enqueue(value),
+        };
+
+        self.replay(&before, &steps, format!("push {}", value));
+    }
+
+    /// Pops from a stack, dequeues from a queue and removes the head of a
+    /// list.
+    pub fn pop(&mut self) -> Option<i32> {
+        let before = self.structure.list().clone();
+
+        let (value, steps) = match &mut self.structure {
+            Structure::List(list) => list.pop_front(),
+            Structure::Stack(stack) => stack.pop(),
+            Structure::Queue(queue) => queue.dequeue(),
+        };
+
+        self.replay(&before, &steps, popped("pop", value));
+
+        value
+    }
+
+    pub fn peek(&self) -> Option<i32> {
+        let list = self.structure.list();
+
+        list.head().map(|head| list.node(head).value)
+    }
+
+    pub fn push_front(&mut self, value: i32) -> Result<(), JsValue> {
+        let before = self.structure.list().clone();
+        let steps = self.list_mut("push_front")?.push_front(value);
+
+        self.replay(&before, &steps, format!("push_front {}", value));
+
+        Ok(())
+    }
+
+    pub fn push_back(&mut self, value: i32) -> Result<(), JsValue> {
+        let before = self.structure.list().clone();
+        let steps = self.list_mut("push_back")?.push_back(value);
+
+        self.replay(&before, &steps, format!("push_back {}", value));
+
+        Ok(())
+    }
+
+    /// Removes the tail, which a singly linked list has to walk to.
+    pub fn pop_back(&mut self) -> Result<Option<i32>, JsValue> {
+        let before = self.structure.list().clone();
+        let (value, steps) = self.list_mut("pop_back")?.pop_back();
+
+        self.replay(&before, &steps, popped("pop_back", value));
+
+        Ok(value)
+    }
+
+    /// Inserts `value` at `index`, walking to the node before it.
+    pub fn insert(&mut self, index: usize, value: i32) -> Result<(), JsValue> {
+        let before = self.structure.list().clone();
+        let len = before.len();
+
+        let Some(steps) = self.list_mut("insert")?.insert(index, value) else {
+            return Err(JsValue::from_str(&format!(
+                "index {} is past the end of a list of {} values",
+                index, len
+            )));
+        };
+
+        self.replay(
+            &before,
+            &steps,
+            format!("insert {} at index {}", value, index),
+        );
+
+        Ok(())
+    }
+
+    /// Removes the value at `index`, or returns `None` if there is none.
+    pub fn remove(&mut self, index: usize) -> Result<Option<i32>, JsValue> {
+        let before = self.structure.list().clone();
+        let (value, steps) = self.list_mut("remove")?.remove(index);
+
+        let summary = match value {
+            Some(value) => format!("remove {} at index {}", value, index),
+            None => format!("remove at index {}: there is no such index", index),
+        };
+
+        self.replay(&before, &steps, summary);
+
+        Ok(value)
+    }
+
+    pub fn reverse(&mut self) -> Result<(), JsValue> {
+        let before = self.structure.list().clone();
+        let steps = self.list_mut("reverse")?.reverse();
+
+        self.replay(&before, &steps, "reverse in place".to_string());
+
+        Ok(())
+    }
+
+    /// The values from the head, or top, on.
+    pub fn values(&self) -> Vec<i32> {
+        self.structure.list().values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.structure.list().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.structure.list().is_empty()
+    }
+
+    /// The list itself, for operations a stack or a queue does not have.
+    fn list_mut(&mut self, operation: &str) -> Result<&mut LinkedList, JsValue> {
+        let name = self.structure.name();
+
+        match &mut self.structure {
+            Structure::List(list) => Ok(list),
+            _ => Err(JsValue::from_str(&format!(
+                "a {} has no `{}`, only linked lists do",
+                name, operation
+            ))),
+        }
+    }
+
+    /// Plays `steps` back from `before` and finishes with the list laid out
+    /// again in its new order.
+    fn replay(&mut self, before: &LinkedList, steps: &[ListStep], summary: String) {
+        let mut replay = Replay::new(&self.structure, before, steps, summary);
+
+        replay.record(None, None, format!("{}: start", self.structure.name()));
+        replay.run(steps);
+
+        let (head_label, tail_label) = self.structure.labels();
+        let mut view = ListView::new(self.structure.list(), head_label, tail_label);
+
+        for node in view.nodes.iter_mut().flatten() {
+            node.color = PATH_COLOR;
+        }
+
+        view.notes = vec![
+            format!("done: {} values", self.structure.list().len()),
+            format!(
+                "nodes visited: {}, pointer writes: {}",
+                replay.visits, replay.pointer_writes
+            ),
+            replay.summary.clone(),
+        ];
+
+        replay.frames.push(view);
+        self.animation.push(replay.frames);
+    }
+}
+
+fn popped(operation: &str, value: Option<i32>) -> String {
+    match value {
+        Some(value) => format!("{} {}", operation, value),
+        None => format!("{}: nothing to remove", operation),
+    }
+}
+
+/// Starts a visualization of a `"singly"` or `"doubly"` linked list, a
+/// `"stack"` or a `"queue"`, pushing `values` one after another.
+#[wasm_bindgen]
+pub fn run_linked_list(
+    document_id: &str,
+    kind: &str,
+    values: Vec<i32>,
+) -> Result<LinkedListVisualization, JsValue> {
+    set_panic_hook();
+
+    let structure = match kind {
+        "singly" => Structure::List(LinkedList::new(false)),
+        "doubly" => Structure::List(LinkedList::new(true)),
+        "stack" => Structure::Stack(Stack::new()),
+        "queue" => Structure::Queue(Queue::new()),
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown list `{}`, expected one of {}",
+                kind,
+                KINDS.join(", ")
+            )))
+        }
+    };
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &ListView| view.draw(&ctx));
+
+    let mut visualization = LinkedListVisualization {
+        structure,
+        animation,
+    };
+
+    let (head_label, tail_label) = visualization.structure.labels();
+    let mut view = ListView::new(visualization.structure.list(), head_label, tail_label);
+
+    view.notes = vec![format!("empty {}", visualization.structure.name())];
+    visualization.animation.push(vec![view]);
+
+    for value in values {
+        visualization.push(value);
+    }
+
+    Ok(visualization)
+}
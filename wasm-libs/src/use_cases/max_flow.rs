@@ -0,0 +1,507 @@
+use std::{cell::Cell, collections::VecDeque, rc::Rc};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::{
+        flow_network::{FlowNetwork, EPSILON},
+        general_graph::GeneralGraph,
+    },
+    renderers::graph::{
+        GraphView, ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 45;
+
+const SOURCE_COLOR: &str = "#A1EEBD";
+const SINK_COLOR: &str = "#86A7FC";
+
+/// One step drawn either on the network, with edges labelled flow/capacity,
+/// or on the residual graph, whose edges change from step to step.
+#[derive(Clone)]
+struct FlowFrame {
+    network: GraphView,
+    residual: GeneralGraph,
+    residual_view: GraphView,
+}
+
+struct MaxFlow {
+    network: FlowNetwork,
+    network_graph: GeneralGraph,
+    source: usize,
+    sink: usize,
+    augmentations: usize,
+    frames: Vec<FlowFrame>,
+}
+
+impl MaxFlow {
+    fn new(network: FlowNetwork, source: usize, sink: usize) -> MaxFlow {
+        let mut network_graph = GeneralGraph::new(true);
+
+        for vertex in network.vertices() {
+            network_graph.add_labeled_node(
+                vertex.label.clone(),
+                vertex.coordinate.x,
+                vertex.coordinate.y,
+            );
+        }
+
+        for edge in 0..network.edge_count() {
+            let arc = network.edge(edge);
+            network_graph.push_edge(arc.from, arc.to, arc.capacity);
+        }
+
+        MaxFlow {
+            network,
+            network_graph,
+            source,
+            sink,
+            augmentations: 0,
+            frames: vec![],
+        }
+    }
+
+    fn edmonds_karp(&mut self) {
+        self.record(
+            &[],
+            None,
+            "Edmonds-Karp: BFS for shortest augmenting paths".to_string(),
+        );
+
+        while let Some(path) = self.bfs_path() {
+            self.augment(&path);
+        }
+    }
+
+    /// Shortest augmenting path as a list of arcs, found by BFS over the
+    /// residual graph.
+    fn bfs_path(&self) -> Option<Vec<usize>> {
+        let mut parent_arc: Vec<Option<usize>> = vec![None; self.network.node_count()];
+        let mut visited = vec![false; self.network.node_count()];
+        let mut queue = VecDeque::from([self.source]);
+
+        visited[self.source] = true;
+
+        while let Some(node) = queue.pop_front() {
+            for &arc in self.network.outgoing(node) {
+                let to = self.network.arcs()[arc].to;
+
+                if visited[to] || self.network.arcs()[arc].residual() <= EPSILON {
+                    continue;
+                }
+
+                visited[to] = true;
+                parent_arc[to] = Some(arc);
+                queue.push_back(to);
+            }
+        }
+
+        if !visited[self.sink] {
+            return None;
+        }
+
+        let mut path = vec![];
+        let mut node = self.sink;
+
+        while let Some(arc) = parent_arc[node] {
+            path.push(arc);
+            node = self.network.arcs()[arc].from;
+        }
+
+        path.reverse();
+
+        Some(path)
+    }
+
+    fn dinic(&mut self) {
+        self.record(
+            &[],
+            None,
+            "Dinic: blocking flows on level graphs".to_string(),
+        );
+
+        let mut phase = 1;
+
+        while let Some(levels) = self.levels() {
+            self.record(
+                &[],
+                Some(&levels),
+                format!(
+                    "phase {}: level graph built, sink at level {}",
+                    phase,
+                    levels[self.sink].unwrap()
+                ),
+            );
+
+            let mut next_arc = vec![0; self.network.node_count()];
+            let before = self.network.outflow(self.source);
+
+            while let Some(path) = self.level_path(&levels, &mut next_arc) {
+                self.augment(&path);
+            }
+
+            let pushed = self.network.outflow(self.source) - before;
+
+            self.record(
+                &[],
+                Some(&levels),
+                format!("phase {}: blocking flow of {} found", phase, pushed),
+            );
+
+            phase += 1;
+        }
+    }
+
+    /// BFS distances from the source in the residual graph, or `None` once
+    /// the sink is unreachable.
+    fn levels(&self) -> Option<Vec<Option<usize>>> {
+        let mut levels = vec![None; self.network.node_count()];
+        let mut queue = VecDeque::from([self.source]);
+
+        levels[self.source] = Some(0);
+
+        while let Some(node) = queue.pop_front() {
+            let level = levels[node].unwrap();
+
+            for &arc in self.network.outgoing(node) {
+                let to = self.network.arcs()[arc].to;
+
+                if levels[to].is_none() && self.network.arcs()[arc].residual() > EPSILON {
+                    levels[to] = Some(level + 1);
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        levels[self.sink].map(|_| levels)
+    }
+
+    /// Next source-sink path in the level graph. `next_arc` remembers, per
+    /// node, the first arc not yet known to lead to a dead end, so dead ends
+    /// are never explored twice within a phase.
+    fn level_path(&self, levels: &[Option<usize>], next_arc: &mut [usize]) -> Option<Vec<usize>> {
+        let mut path: Vec<usize> = vec![];
+        let mut node = self.source;
+
+        loop {
+            if node == self.sink {
+                return Some(path);
+            }
+
+            let outgoing = self.network.outgoing(node);
+            let mut advanced = false;
+
+            while next_arc[node] < outgoing.len() {
+                let arc = &self.network.arcs()[outgoing[next_arc[node]]];
+
+                if arc.residual() > EPSILON && levels[arc.to] == levels[node].map(|l| l + 1) {
+                    path.push(outgoing[next_arc[node]]);
+                    node = arc.to;
+                    advanced = true;
+                    break;
+                }
+
+                next_arc[node] += 1;
+            }
+
+            if !advanced {
+                let arc = path.pop()?;
+
+                node = self.network.arcs()[arc].from;
+                next_arc[node] += 1;
+            }
+        }
+    }
+
+    fn augment(&mut self, path: &[usize]) {
+        let bottleneck = path
+            .iter()
+            .map(|&arc| self.network.arcs()[arc].residual())
+            .fold(f64::INFINITY, f64::min);
+
+        let mut names = vec![self.name(self.source).to_string()];
+        names.extend(
+            path.iter()
+                .map(|&arc| self.name(self.network.arcs()[arc].to).to_string()),
+        );
+
+        self.record(
+            path,
+            None,
+            format!(
+                "augmenting path {}, bottleneck {}",
+                names.join(" -> "),
+                bottleneck
+            ),
+        );
+
+        for &arc in path {
+            self.network.push(arc, bottleneck);
+        }
+
+        self.augmentations += 1;
+
+        self.record(path, None, format!("pushed {} units", bottleneck));
+    }
+
+    /// Highlights the minimum cut: the nodes still reachable from the source
+    /// in the residual graph against the rest, and the saturated edges
+    /// between them.
+    fn record_min_cut(&mut self) -> (Vec<usize>, Vec<(usize, usize)>) {
+        let reachable = self.network.residual_reachable(self.source);
+        let mut cut_edges = vec![];
+        let mut frame = self.frame(&[], None);
+
+        for (node, &on_source_side) in reachable.iter().enumerate() {
+            frame.network.node_colors[node] = if on_source_side {
+                SOURCE_COLOR
+            } else {
+                SINK_COLOR
+            };
+        }
+
+        let mut cut_capacity = 0.0;
+
+        for edge in 0..self.network.edge_count() {
+            let arc = self.network.edge(edge);
+
+            if reachable[arc.from] && !reachable[arc.to] {
+                frame.network.edge_colors[edge] = HIGHLIGHT_COLOR;
+                cut_capacity += arc.capacity;
+                cut_edges.push((arc.from, arc.to));
+            }
+        }
+
+        let notes = vec![
+            format!(
+                "max flow = {}, min cut capacity = {}",
+                self.network.outflow(self.source),
+                cut_capacity
+            ),
+            format!("augmenting paths: {}", self.augmentations),
+        ];
+        frame.network.notes = notes.clone();
+        frame.residual_view.notes = notes;
+
+        self.frames.push(frame);
+
+        let source_side = (0..reachable.len())
+            .filter(|&node| reachable[node])
+            .collect();
+
+        (source_side, cut_edges)
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.network.vertices()[node].label
+    }
+
+    fn frame(&self, path: &[usize], levels: Option<&[Option<usize>]>) -> FlowFrame {
+        let mut network = GraphView::new(&self.network_graph);
+
+        for edge in 0..self.network.edge_count() {
+            let arc = self.network.edge(edge);
+
+            network.edge_labels[edge] = format!("{}/{}", arc.flow, arc.capacity);
+
+            if arc.flow > EPSILON {
+                network.edge_colors[edge] = PATH_COLOR;
+            }
+        }
+
+        let mut residual = GeneralGraph::new(true);
+        let mut residual_edge = vec![None; self.network.arcs().len()];
+
+        for vertex in self.network.vertices() {
+            residual.add_labeled_node(
+                vertex.label.clone(),
+                vertex.coordinate.x,
+                vertex.coordinate.y,
+            );
+        }
+
+        for (idx, arc) in self.network.arcs().iter().enumerate() {
+            if arc.residual() > EPSILON {
+                residual_edge[idx] = Some(residual.push_edge(arc.from, arc.to, arc.residual()));
+            }
+        }
+
+        let mut residual_view = GraphView::with_weights(&residual);
+
+        for &arc in path {
+            // Pushing along a reverse arc cancels flow on the original edge.
+            network.edge_colors[arc / 2] = if arc % 2 == 0 {
+                RELAXED_COLOR
+            } else {
+                CURRENT_COLOR
+            };
+
+            if let Some(edge) = residual_edge[arc] {
+                residual_view.edge_colors[edge] = RELAXED_COLOR;
+            }
+
+            let to = self.network.arcs()[arc].to;
+            network.node_colors[to] = ACTIVE_COLOR;
+            residual_view.node_colors[to] = ACTIVE_COLOR;
+        }
+
+        if let Some(levels) = levels {
+            for (node, level) in levels.iter().enumerate() {
+                if let Some(level) = level {
+                    network.node_labels[node] = format!("L{}", level);
+                    residual_view.node_labels[node] = format!("L{}", level);
+                }
+            }
+
+            for (idx, arc) in self.network.arcs().iter().enumerate() {
+                let admissible =
+                    levels[arc.to].is_some() && levels[arc.to] == levels[arc.from].map(|l| l + 1);
+
+                if let (true, Some(edge)) = (admissible, residual_edge[idx]) {
+                    residual_view.edge_colors[edge] = CURRENT_COLOR;
+                }
+            }
+        }
+
+        for view in [&mut network, &mut residual_view] {
+            view.node_colors[self.source] = SOURCE_COLOR;
+            view.node_colors[self.sink] = SINK_COLOR;
+        }
+
+        FlowFrame {
+            network,
+            residual,
+            residual_view,
+        }
+    }
+
+    fn record(&mut self, path: &[usize], levels: Option<&[Option<usize>]>, message: String) {
+        let mut frame = self.frame(path, levels);
+
+        let notes = vec![
+            message,
+            format!("flow: {}", self.network.outflow(self.source)),
+        ];
+        frame.network.notes = notes.clone();
+        frame.residual_view.notes = notes;
+        frame.residual_view.notes.push("residual graph".to_string());
+
+        self.frames.push(frame);
+    }
+}
+
+#[wasm_bindgen]
+pub struct MaxFlowVisualization {
+    max_flow: f64,
+    source_side: Vec<usize>,
+    cut_edges: Vec<(usize, usize)>,
+    show_residual: Rc<Cell<bool>>,
+    last_frame: FlowFrame,
+    animation: Animation<FlowFrame>,
+}
+
+#[wasm_bindgen]
+impl MaxFlowVisualization {
+    pub fn max_flow(&self) -> f64 {
+        self.max_flow
+    }
+
+    /// Nodes on the source side of the minimum cut.
+    pub fn source_side(&self) -> Vec<usize> {
+        self.source_side.clone()
+    }
+
+    /// `[from, to]` pairs of the edges crossing the minimum cut.
+    pub fn cut_edges(&self) -> js_sys::Array {
+        self.cut_edges
+            .iter()
+            .map(|&(from, to)| {
+                js_sys::Array::of2(&JsValue::from(from as u32), &JsValue::from(to as u32))
+            })
+            .collect()
+    }
+
+    /// Switches between drawing flow/capacity on the network and drawing
+    /// the residual graph.
+    pub fn set_show_residual(&self, show_residual: bool) {
+        self.show_residual.set(show_residual);
+
+        if self.animation.is_idle() {
+            self.animation.push([self.last_frame.clone()]);
+        }
+    }
+}
+
+/// Animates Edmonds-Karp (`"edmonds-karp"`) or Dinic (`"dinic"`) from
+/// `source` to `sink`, using the edge weights of `graph` as capacities, and
+/// finishes by highlighting the minimum cut. Every weight has to be finite
+/// and non-negative.
+#[wasm_bindgen]
+pub fn run_max_flow(
+    document_id: &str,
+    graph: &GeneralGraph,
+    source: usize,
+    sink: usize,
+    algorithm: &str,
+) -> Result<MaxFlowVisualization, JsValue> {
+    set_panic_hook();
+
+    if source >= graph.node_count() || sink >= graph.node_count() || source == sink {
+        return Err(JsValue::from_str(
+            "source and sink must be two different nodes of the graph",
+        ));
+    }
+
+    for (idx, edge) in graph.edges().iter().enumerate() {
+        if !(edge.weight.is_finite() && edge.weight >= 0.0) {
+            return Err(JsValue::from_str(&format!(
+                "edge {}: capacity {} must be finite and non-negative",
+                idx, edge.weight
+            )));
+        }
+    }
+
+    let mut max_flow = MaxFlow::new(FlowNetwork::from(graph), source, sink);
+
+    match algorithm {
+        "edmonds-karp" => max_flow.edmonds_karp(),
+        "dinic" => max_flow.dinic(),
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown max flow algorithm `{}`, expected `edmonds-karp` or `dinic`",
+                algorithm
+            )))
+        }
+    }
+
+    let (source_side, cut_edges) = max_flow.record_min_cut();
+
+    let ctx = canvas::context(document_id);
+    let network_graph = max_flow.network_graph.clone();
+    let show_residual = Rc::new(Cell::new(false));
+    let show = show_residual.clone();
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |frame: &FlowFrame| {
+        if show.get() {
+            frame.residual_view.draw(&ctx, &frame.residual);
+        } else {
+            frame.network.draw(&ctx, &network_graph);
+        }
+    });
+
+    let last_frame = max_flow.frames.last().unwrap().clone();
+
+    animation.push(max_flow.frames);
+
+    Ok(MaxFlowVisualization {
+        max_flow: max_flow.network.outflow(source),
+        source_side,
+        cut_edges,
+        show_residual,
+        last_frame,
+        animation,
+    })
+}
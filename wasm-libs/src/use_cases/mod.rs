@@ -1,3 +1,24 @@
+pub mod b_tree;
+pub mod balanced_tree;
+pub mod bellman_ford;
 pub mod bfs;
+pub mod biconnectivity;
+pub mod binary_search_tree;
+pub mod bipartite_matching;
 pub mod draw_a_square;
+pub mod eulerian_path;
+pub mod fenwick_tree;
+pub mod floyd_warshall;
+pub mod graph_coloring;
+pub mod hash_table;
+pub mod heap;
+pub mod linked_list;
+pub mod max_flow;
 pub mod random_circles_with_mouse_move_effets;
+pub mod scc;
+pub mod segment_tree;
+pub mod skip_list;
+pub mod sorting;
+pub mod sorting_network;
+pub mod traveling_salesman;
+pub mod trie;
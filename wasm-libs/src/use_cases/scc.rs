@@ -0,0 +1,646 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas::{self, Coordinate},
+    data_structures::general_graph::GeneralGraph,
+    renderers::graph::{
+        palette_color, GraphView, ACTIVE_COLOR, CURRENT_COLOR, DONE_COLOR, HIGHLIGHT_COLOR,
+        NODE_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+enum SccFrame {
+    Search(GraphView),
+    Condensation(GraphView),
+}
+
+struct Tarjan<'a> {
+    graph: &'a GeneralGraph,
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    component_of: Vec<Option<usize>>,
+    components: Vec<Vec<usize>>,
+    frames: Vec<GraphView>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn new(graph: &'a GeneralGraph) -> Tarjan<'a> {
+        let n = graph.node_count();
+
+        Tarjan {
+            graph,
+            index: vec![None; n],
+            low: vec![0; n],
+            on_stack: vec![false; n],
+            stack: vec![],
+            next_index: 0,
+            component_of: vec![None; n],
+            components: vec![],
+            frames: vec![],
+        }
+    }
+
+    fn run(mut self) -> (Vec<Vec<usize>>, Vec<GraphView>) {
+        for node in 0..self.graph.node_count() {
+            if self.index[node].is_none() {
+                self.visit(node);
+            }
+        }
+
+        self.record(None, None, "Tarjan finished".to_string());
+
+        (self.components, self.frames)
+    }
+
+    /// Depth-first search from `root` with an explicit stack of the nodes
+    /// being visited, the tree edge they were reached by and their
+    /// remaining neighbors, so deep graphs cannot overflow the call stack.
+    fn visit(&mut self, root: usize) {
+        let graph = self.graph;
+
+        self.enter(root);
+
+        let mut calls = vec![(root, None, graph.neighbors(root))];
+
+        while let Some((node, tree_edge, neighbors)) = calls.last_mut() {
+            let (node, tree_edge) = (*node, *tree_edge);
+
+            let (edge, neighbor) = match neighbors.next() {
+                Some(next) => next,
+                None => {
+                    calls.pop();
+                    self.leave(node);
+
+                    if let (Some(&(parent, _, _)), Some(edge)) = (calls.last(), tree_edge) {
+                        self.low[parent] = self.low[parent].min(self.low[node]);
+
+                        self.record(
+                            Some(parent),
+                            Some(edge),
+                            format!(
+                                "back at {}: low = min(low, low[{}]) = {}",
+                                self.name(parent),
+                                self.name(node),
+                                self.low[parent]
+                            ),
+                        );
+                    }
+
+                    continue;
+                }
+            };
+
+            match self.index[neighbor] {
+                None => {
+                    self.record(
+                        Some(node),
+                        Some(edge),
+                        format!("tree edge {} -> {}", self.name(node), self.name(neighbor)),
+                    );
+
+                    self.enter(neighbor);
+                    calls.push((neighbor, Some(edge), graph.neighbors(neighbor)));
+                }
+                Some(neighbor_index) if self.on_stack[neighbor] => {
+                    self.low[node] = self.low[node].min(neighbor_index);
+
+                    self.record(
+                        Some(node),
+                        Some(edge),
+                        format!(
+                            "{} is on the stack: low = min(low, index[{}]) = {}",
+                            self.name(neighbor),
+                            self.name(neighbor),
+                            self.low[node]
+                        ),
+                    );
+                }
+                Some(_) => {
+                    self.record(
+                        Some(node),
+                        Some(edge),
+                        format!(
+                            "{} already belongs to a component, ignored",
+                            self.name(neighbor)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Numbers `node` and pushes it on the stack.
+    fn enter(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        self.record(
+            Some(node),
+            None,
+            format!(
+                "visit {}: index = low = {}",
+                self.name(node),
+                self.low[node]
+            ),
+        );
+    }
+
+    /// Pops the component rooted at `node` once all its neighbors are done,
+    /// if it is a root.
+    fn leave(&mut self, node: usize) {
+        if Some(self.low[node]) == self.index[node] {
+            let component_index = self.components.len();
+            let mut component = vec![];
+
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                self.component_of[member] = Some(component_index);
+                component.push(member);
+
+                if member == node {
+                    break;
+                }
+            }
+
+            component.reverse();
+            self.components.push(component);
+
+            self.record(
+                Some(node),
+                None,
+                format!(
+                    "{} is a root (low == index): pop component #{}",
+                    self.name(node),
+                    component_index + 1
+                ),
+            );
+        }
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.graph.vertices()[node].label
+    }
+
+    fn record(&mut self, current: Option<usize>, edge: Option<usize>, message: String) {
+        let mut view = GraphView::new(self.graph);
+
+        for node in 0..self.graph.node_count() {
+            view.node_colors[node] = if let Some(component) = self.component_of[node] {
+                palette_color(component)
+            } else if current == Some(node) {
+                CURRENT_COLOR
+            } else if self.on_stack[node] {
+                ACTIVE_COLOR
+            } else {
+                NODE_COLOR
+            };
+
+            if let Some(index) = self.index[node] {
+                view.node_labels[node] = format!("{}/{}", index, self.low[node]);
+            }
+        }
+
+        if let Some(edge) = edge {
+            view.edge_colors[edge] = HIGHLIGHT_COLOR;
+        }
+
+        let stack = self
+            .stack
+            .iter()
+            .map(|&node| self.name(node))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        view.notes = vec![
+            message,
+            format!("stack: [{}]", stack),
+            format!("components: {}", self.components.len()),
+            "labels: index/low".to_string(),
+        ];
+
+        self.frames.push(view);
+    }
+}
+
+struct Kosaraju<'a> {
+    graph: &'a GeneralGraph,
+    visited: Vec<bool>,
+    finished: Vec<bool>,
+    finish_order: Vec<usize>,
+    component_of: Vec<Option<usize>>,
+    components: Vec<Vec<usize>>,
+    frames: Vec<GraphView>,
+}
+
+impl<'a> Kosaraju<'a> {
+    fn new(graph: &'a GeneralGraph) -> Kosaraju<'a> {
+        let n = graph.node_count();
+
+        Kosaraju {
+            graph,
+            visited: vec![false; n],
+            finished: vec![false; n],
+            finish_order: vec![],
+            component_of: vec![None; n],
+            components: vec![],
+            frames: vec![],
+        }
+    }
+
+    fn run(mut self) -> (Vec<Vec<usize>>, Vec<GraphView>) {
+        for node in 0..self.graph.node_count() {
+            if !self.visited[node] {
+                self.first_pass(node);
+            }
+        }
+
+        self.record(
+            None,
+            None,
+            "pass 1 done: process nodes by decreasing finish time".to_string(),
+        );
+
+        let transposed = self.graph.transpose();
+
+        for node in self.finish_order.clone().into_iter().rev() {
+            if self.component_of[node].is_none() {
+                let component_index = self.components.len();
+
+                self.components.push(vec![]);
+                self.second_pass(&transposed, node, component_index);
+
+                self.record(
+                    None,
+                    None,
+                    format!("component #{} complete", component_index + 1),
+                );
+            }
+        }
+
+        self.record(None, None, "Kosaraju finished".to_string());
+
+        (self.components, self.frames)
+    }
+
+    /// Depth-first search from `root` with an explicit stack of the nodes
+    /// being visited and their remaining neighbors, recording finish times.
+    fn first_pass(&mut self, root: usize) {
+        let graph = self.graph;
+
+        self.first_visit(root);
+
+        let mut calls = vec![(root, graph.neighbors(root))];
+
+        while let Some((node, neighbors)) = calls.last_mut() {
+            let node = *node;
+
+            match neighbors.next() {
+                Some((_, neighbor)) if self.visited[neighbor] => {}
+                Some((edge, neighbor)) => {
+                    self.record(
+                        Some(node),
+                        Some(edge),
+                        format!("pass 1: {} -> {}", self.name(node), self.name(neighbor)),
+                    );
+
+                    self.first_visit(neighbor);
+                    calls.push((neighbor, graph.neighbors(neighbor)));
+                }
+                None => {
+                    calls.pop();
+
+                    self.finished[node] = true;
+                    self.finish_order.push(node);
+                    self.record(
+                        Some(node),
+                        None,
+                        format!(
+                            "pass 1: {} finished at {}",
+                            self.name(node),
+                            self.finish_order.len()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn first_visit(&mut self, node: usize) {
+        self.visited[node] = true;
+        self.record(
+            Some(node),
+            None,
+            format!("pass 1: visit {}", self.name(node)),
+        );
+    }
+
+    /// Collects the nodes reachable from `root` in `transposed` that belong
+    /// to no component yet, with the same explicit stack as the first pass.
+    fn second_pass(&mut self, transposed: &GeneralGraph, root: usize, component_index: usize) {
+        self.join(root, component_index);
+
+        let mut calls = vec![(root, transposed.neighbors(root))];
+
+        while let Some((node, neighbors)) = calls.last_mut() {
+            let node = *node;
+
+            match neighbors.next() {
+                Some((_, neighbor)) if self.component_of[neighbor].is_some() => {}
+                Some((edge, neighbor)) => {
+                    // Transposing keeps edge indexes, so the original edge is highlighted.
+                    self.record(
+                        Some(node),
+                        Some(edge),
+                        format!(
+                            "pass 2 (transposed): {} -> {}",
+                            self.name(node),
+                            self.name(neighbor)
+                        ),
+                    );
+
+                    self.join(neighbor, component_index);
+                    calls.push((neighbor, transposed.neighbors(neighbor)));
+                }
+                None => {
+                    calls.pop();
+                }
+            }
+        }
+    }
+
+    fn join(&mut self, node: usize, component_index: usize) {
+        self.component_of[node] = Some(component_index);
+        self.components[component_index].push(node);
+
+        self.record(
+            Some(node),
+            None,
+            format!(
+                "pass 2 (transposed): {} joins component #{}",
+                self.name(node),
+                component_index + 1
+            ),
+        );
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.graph.vertices()[node].label
+    }
+
+    fn record(&mut self, current: Option<usize>, edge: Option<usize>, message: String) {
+        let mut view = GraphView::new(self.graph);
+        let finished = self.finish_order.len();
+
+        for node in 0..self.graph.node_count() {
+            view.node_colors[node] = if let Some(component) = self.component_of[node] {
+                palette_color(component)
+            } else if current == Some(node) {
+                CURRENT_COLOR
+            } else if self.finished[node] {
+                DONE_COLOR
+            } else if self.visited[node] {
+                ACTIVE_COLOR
+            } else {
+                NODE_COLOR
+            };
+        }
+
+        for (order, &node) in self.finish_order.iter().enumerate() {
+            view.node_labels[node] = format!("f={}", order + 1);
+        }
+
+        if let Some(edge) = edge {
+            view.edge_colors[edge] = HIGHLIGHT_COLOR;
+        }
+
+        let order = self
+            .finish_order
+            .iter()
+            .map(|&node| self.name(node))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        view.notes = vec![
+            message,
+            format!("finish order: [{}] ({} finished)", order, finished),
+            format!("components: {}", self.components.len()),
+        ];
+
+        self.frames.push(view);
+    }
+}
+
+pub fn tarjan(graph: &GeneralGraph) -> (Vec<Vec<usize>>, Vec<GraphView>) {
+    Tarjan::new(graph).run()
+}
+
+pub fn kosaraju(graph: &GeneralGraph) -> (Vec<Vec<usize>>, Vec<GraphView>) {
+    Kosaraju::new(graph).run()
+}
+
+/// The DAG of components: one node per component placed at the centroid of
+/// its members, and one edge per pair of components joined by an edge.
+pub fn condensation(graph: &GeneralGraph, components: &[Vec<usize>]) -> (GeneralGraph, GraphView) {
+    let mut component_of = vec![0; graph.node_count()];
+    let mut dag = GeneralGraph::new(true);
+
+    for (idx, component) in components.iter().enumerate() {
+        let count = component.len() as f64;
+        let (x, y) = component.iter().fold((0.0, 0.0), |(x, y), &node| {
+            let coordinate: &Coordinate = &graph.vertices()[node].coordinate;
+            (x + coordinate.x / count, y + coordinate.y / count)
+        });
+
+        for &node in component {
+            component_of[node] = idx;
+        }
+
+        dag.add_labeled_node(format!("C{}", idx + 1), x, y);
+    }
+
+    for edge in graph.edges() {
+        let (from, to) = (component_of[edge.from], component_of[edge.to]);
+
+        if from != to && !dag.has_edge(from, to) {
+            dag.push_edge(from, to, 1.0);
+        }
+    }
+
+    let mut view = GraphView::new(&dag);
+
+    for (idx, component) in components.iter().enumerate() {
+        view.node_colors[idx] = palette_color(idx);
+        view.node_labels[idx] = component
+            .iter()
+            .map(|&node| graph.vertices()[node].label.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+    }
+
+    view.notes = vec![format!("condensation DAG: {} components", components.len())];
+
+    (dag, view)
+}
+
+/// Animates Tarjan's or Kosaraju's algorithm on `graph` and returns the
+/// strongly connected components as an array of node index arrays.
+#[wasm_bindgen]
+pub fn run_scc(
+    document_id: &str,
+    graph: &GeneralGraph,
+    algorithm: &str,
+    draw_condensation: bool,
+) -> Result<js_sys::Array, JsValue> {
+    set_panic_hook();
+
+    let (components, views) = match algorithm {
+        "tarjan" => tarjan(graph),
+        "kosaraju" => kosaraju(graph),
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown SCC algorithm `{}`, expected `tarjan` or `kosaraju`",
+                algorithm
+            )))
+        }
+    };
+
+    let mut frames: Vec<SccFrame> = views.into_iter().map(SccFrame::Search).collect();
+
+    let dag = if draw_condensation {
+        let (dag, view) = condensation(graph, &components);
+        frames.push(SccFrame::Condensation(view));
+        Some(dag)
+    } else {
+        None
+    };
+
+    let ctx = canvas::context(document_id);
+    let graph = graph.clone();
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |frame: &SccFrame| match frame {
+        SccFrame::Search(view) => view.draw(&ctx, &graph),
+        SccFrame::Condensation(view) => view.draw(&ctx, dag.as_ref().unwrap()),
+    });
+
+    animation.push(frames);
+
+    Ok(components
+        .iter()
+        .map(|component| {
+            component
+                .iter()
+                .map(|&node| JsValue::from(node as u32))
+                .collect::<js_sys::Array>()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    /// Components as sorted lists of nodes, sorted by their first node.
+    fn normalized(mut components: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+        for component in components.iter_mut() {
+            component.sort_unstable();
+        }
+
+        components.sort_unstable();
+        components
+    }
+
+    /// Components from mutual reachability, computed by brute force.
+    fn reference(graph: &GeneralGraph) -> Vec<Vec<usize>> {
+        let n = graph.node_count();
+        let mut reaches = vec![vec![false; n]; n];
+
+        for (from, reached) in reaches.iter_mut().enumerate() {
+            let mut stack = vec![from];
+            reached[from] = true;
+
+            while let Some(node) = stack.pop() {
+                for (_, neighbor) in graph.neighbors(node) {
+                    if !reached[neighbor] {
+                        reached[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        let mut assigned = vec![false; n];
+        let mut components = vec![];
+
+        for node in 0..n {
+            if !assigned[node] {
+                let component: Vec<usize> = (node..n)
+                    .filter(|&other| reaches[node][other] && reaches[other][node])
+                    .collect();
+
+                for &member in component.iter() {
+                    assigned[member] = true;
+                }
+
+                components.push(component);
+            }
+        }
+
+        components
+    }
+
+    #[test]
+    fn tarjan_and_kosaraju_find_the_strongly_connected_components() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let mut graph = GeneralGraph::new(true);
+            let n = 2 + rng.below(14);
+
+            for _ in 0..n {
+                graph.add_node(0.0, 0.0);
+            }
+
+            for _ in 0..rng.below(3 * n) {
+                let (from, to) = (rng.below(n), rng.below(n));
+
+                if from != to && !graph.has_edge(from, to) {
+                    graph.push_edge(from, to, 1.0);
+                }
+            }
+
+            let expected = reference(&graph);
+
+            assert_eq!(normalized(tarjan(&graph).0), expected, "seed {}", seed);
+            assert_eq!(normalized(kosaraju(&graph).0), expected, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn a_long_cycle_is_one_component() {
+        let mut graph = GeneralGraph::new(true);
+        let n = 300;
+
+        for node in 0..n {
+            graph.add_node(0.0, 0.0);
+
+            if node > 0 {
+                graph.push_edge(node - 1, node, 1.0);
+            }
+        }
+
+        graph.push_edge(n - 1, 0, 1.0);
+
+        assert_eq!(tarjan(&graph).0.len(), 1);
+        assert_eq!(kosaraju(&graph).0.len(), 1);
+    }
+}
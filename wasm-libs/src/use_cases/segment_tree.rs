@@ -0,0 +1,279 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::segment_tree::{SegmentStep, SegmentTree},
+    renderers::{
+        graph::{
+            ACTIVE_COLOR, CURRENT_COLOR, DONE_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR,
+        },
+        tree::TreeView,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+/// Replays the steps of one segment tree operation on a copy of the tree,
+/// one frame per step, counting the nodes it touched.
+struct Replay {
+    tree: SegmentTree,
+    /// The color every node touched so far keeps.
+    colors: Vec<Option<&'static str>>,
+    touched: usize,
+    push_downs: usize,
+    summary: String,
+    frames: Vec<TreeView>,
+}
+
+impl Replay {
+    fn new(tree: &SegmentTree, summary: String) -> Replay {
+        Replay {
+            tree: tree.clone(),
+            colors: vec![None; tree.capacity()],
+            touched: 0,
+            push_downs: 0,
+            summary,
+            frames: vec![],
+        }
+    }
+
+    fn interval(&self, node: usize) -> String {
+        let (lo, hi) = self.tree.interval(node);
+
+        format!("[{}, {}]", lo, hi)
+    }
+
+    fn run(&mut self, steps: &[SegmentStep]) {
+        for &step in steps {
+            self.tree.apply(step);
+
+            let (node, color, message) = match step {
+                SegmentStep::Visit(node) => {
+                    self.touched += 1;
+                    (
+                        node,
+                        ACTIVE_COLOR,
+                        format!("{} overlaps the range partly: go down", self.interval(node)),
+                    )
+                }
+                SegmentStep::Take(node) => {
+                    self.touched += 1;
+                    (
+                        node,
+                        PATH_COLOR,
+                        format!(
+                            "{} lies inside the range: use its sum {}",
+                            self.interval(node),
+                            self.tree.sum(node)
+                        ),
+                    )
+                }
+                SegmentStep::Skip(node) => {
+                    self.touched += 1;
+                    (
+                        node,
+                        DONE_COLOR,
+                        format!("{} lies outside the range: skip it", self.interval(node)),
+                    )
+                }
+                SegmentStep::PushDown(node) => {
+                    self.push_downs += 1;
+                    (
+                        node,
+                        HIGHLIGHT_COLOR,
+                        format!(
+                            "pass the pending addition of {} on to its children",
+                            self.interval(node)
+                        ),
+                    )
+                }
+                SegmentStep::Add(node, amount) => (
+                    node,
+                    RELAXED_COLOR,
+                    if self.tree.is_leaf(node) {
+                        format!("add {:+} to {}", amount, self.interval(node))
+                    } else {
+                        format!(
+                            "add {:+} to each value of {}, leave it pending for the children",
+                            amount,
+                            self.interval(node)
+                        )
+                    },
+                ),
+                SegmentStep::Set(node, value) => (
+                    node,
+                    RELAXED_COLOR,
+                    format!("set {} to {}", self.interval(node), value),
+                ),
+                SegmentStep::Pull(node) => (
+                    node,
+                    ACTIVE_COLOR,
+                    format!(
+                        "{} sums its children: {}",
+                        self.interval(node),
+                        self.tree.sum(node)
+                    ),
+                ),
+            };
+
+            self.colors[node] = Some(color);
+            self.record(Some(node), message);
+        }
+    }
+
+    fn record(&mut self, current: Option<usize>, message: String) {
+        let mut view = TreeView::segment_tree(&self.tree);
+
+        for (node, color) in self.colors.iter().enumerate() {
+            if let Some(color) = color {
+                view.node_mut(node).color = color;
+            }
+        }
+
+        if let Some(current) = current {
+            if self.colors[current] != Some(DONE_COLOR) {
+                view.node_mut(current).edge_color = CURRENT_COLOR;
+            }
+        }
+
+        view.notes = vec![message, self.cost(), self.summary.clone()];
+
+        self.frames.push(view);
+    }
+
+    /// Nodes touched against `4⌈log2 n⌉`, the bound on a range operation.
+    fn cost(&self) -> String {
+        let log = (usize::BITS - (self.tree.len().max(2) - 1).leading_zeros()) as usize;
+
+        format!(
+            "nodes touched: {}, push-downs: {} (n = {}, 4⌈log2 n⌉ = {})",
+            self.touched,
+            self.push_downs,
+            self.tree.len(),
+            4 * log
+        )
+    }
+}
+
+/// A segment tree of sums over an array, animating the nodes each range
+/// query, point update and lazy range addition touches.
+#[wasm_bindgen]
+pub struct SegmentTreeVisualization {
+    tree: SegmentTree,
+    touched: usize,
+    animation: Animation<TreeView>,
+}
+
+#[wasm_bindgen]
+impl SegmentTreeVisualization {
+    /// The sum of the values at `lo..=hi`.
+    pub fn query(&mut self, lo: usize, hi: usize) -> Result<f64, JsValue> {
+        self.check_range(lo, hi)?;
+
+        let replay = Replay::new(&self.tree, format!("sum of [{}, {}]", lo, hi));
+        let (sum, steps) = self.tree.query(lo, hi);
+
+        self.replay(replay, &steps, format!("sum of [{}, {}] = {}", lo, hi, sum));
+
+        Ok(sum as f64)
+    }
+
+    /// Sets the value at `index`.
+    pub fn set(&mut self, index: usize, value: i32) -> Result<(), JsValue> {
+        self.check_range(index, index)?;
+
+        let replay = Replay::new(&self.tree, format!("set a[{}] = {}", index, value));
+        let steps = self.tree.set(index, value as i64);
+
+        self.replay(replay, &steps, format!("a[{}] = {}", index, value));
+
+        Ok(())
+    }
+
+    /// Adds `amount` to every value at `lo..=hi`, lazily.
+    pub fn add(&mut self, lo: usize, hi: usize, amount: i32) -> Result<(), JsValue> {
+        self.check_range(lo, hi)?;
+
+        let replay = Replay::new(&self.tree, format!("add {:+} to [{}, {}]", amount, lo, hi));
+        let steps = self.tree.add(lo, hi, amount as i64);
+
+        self.replay(
+            replay,
+            &steps,
+            format!("added {:+} to [{}, {}]", amount, lo, hi),
+        );
+
+        Ok(())
+    }
+
+    /// The array, with every pending addition applied.
+    pub fn values(&self) -> Vec<f64> {
+        self.tree
+            .values()
+            .into_iter()
+            .map(|value| value as f64)
+            .collect()
+    }
+
+    /// Nodes touched by the last operation.
+    pub fn touched(&self) -> usize {
+        self.touched
+    }
+
+    fn check_range(&self, lo: usize, hi: usize) -> Result<(), JsValue> {
+        if lo <= hi && hi < self.tree.len() {
+            Ok(())
+        } else {
+            Err(JsValue::from_str(&format!(
+                "range [{}, {}] is not within [0, {}]",
+                lo,
+                hi,
+                self.tree.len() - 1
+            )))
+        }
+    }
+
+    fn replay(&mut self, mut replay: Replay, steps: &[SegmentStep], result: String) {
+        replay.record(None, replay.summary.clone());
+        replay.run(steps);
+
+        replay.colors = vec![None; replay.colors.len()];
+        replay.record(None, format!("done: {}", result));
+
+        self.touched = replay.touched;
+        self.animation.push(replay.frames);
+    }
+}
+
+/// Starts a visualization of a segment tree over `values`.
+#[wasm_bindgen]
+pub fn run_segment_tree(
+    document_id: &str,
+    values: Vec<i32>,
+) -> Result<SegmentTreeVisualization, JsValue> {
+    set_panic_hook();
+
+    if values.is_empty() {
+        return Err(JsValue::from_str("a segment tree needs at least one value"));
+    }
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &TreeView| view.draw(&ctx));
+
+    let tree = SegmentTree::new(&values);
+    let mut view = TreeView::segment_tree(&tree);
+
+    view.notes = vec![format!(
+        "segment tree over {} values: every node sums its interval",
+        tree.len()
+    )];
+    animation.push([view]);
+
+    Ok(SegmentTreeVisualization {
+        tree,
+        touched: 0,
+        animation,
+    })
+}
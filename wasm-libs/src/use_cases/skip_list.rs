@@ -0,0 +1,373 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    data_structures::skip_list::{SkipList, SkipStep},
+    renderers::{
+        graph::{CURRENT_COLOR, DONE_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR},
+        skip_list::SkipListView,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 30;
+
+/// Replays the steps of one skip list operation on a copy of the list, one
+/// frame per step, measuring the search path against its expected length.
+struct Replay {
+    list: SkipList,
+    key: i32,
+    /// Pugh's bound for the list before the operation.
+    expected: f64,
+    /// Steps right and down taken so far.
+    path_length: usize,
+    /// Pointer cells gone through, as `(node, level)` with `None` for the
+    /// head, and the pointers followed.
+    cells: Vec<(Option<usize>, usize)>,
+    followed: Vec<(Option<usize>, usize)>,
+    /// Pointers written so far.
+    linked: Vec<(Option<usize>, usize)>,
+    /// Where the search stands.
+    cursor: Option<(Option<usize>, usize)>,
+    /// Nodes colored whole: found, allocated or about to be freed.
+    marked: Vec<(usize, &'static str)>,
+    /// A line of notes describing the whole operation.
+    summary: String,
+    frames: Vec<SkipListView>,
+}
+
+impl Replay {
+    fn new(list: &SkipList, key: i32, summary: String) -> Replay {
+        Replay {
+            list: list.clone(),
+            key,
+            expected: list.expected_search_length(),
+            path_length: 0,
+            cells: vec![],
+            followed: vec![],
+            linked: vec![],
+            cursor: Some((None, list.level() - 1)),
+            marked: vec![],
+            summary,
+            frames: vec![],
+        }
+    }
+
+    fn name(&self, at: Option<usize>) -> String {
+        match at {
+            Some(id) => self.list.node(id).key.to_string(),
+            None => "head".to_string(),
+        }
+    }
+
+    fn run(&mut self, steps: &[SkipStep]) {
+        for &step in steps {
+            let message = match step {
+                SkipStep::Right { level, to } => {
+                    let from = self.cursor.and_then(|(at, _)| at);
+
+                    self.path_length += 1;
+                    self.cells.push((from, level));
+                    self.followed.push((from, level));
+                    self.cursor = Some((Some(to), level));
+
+                    format!(
+                        "{} < {}: move right on L{} to {}",
+                        self.list.node(to).key,
+                        self.key,
+                        level,
+                        self.list.node(to).key
+                    )
+                }
+                SkipStep::Down { at, level } => {
+                    let next = match self.list.next(at, level) {
+                        Some(next) => format!(
+                            "the next key {} is ≥ {}",
+                            self.list.node(next).key,
+                            self.key
+                        ),
+                        None => format!("L{} ends", level),
+                    };
+
+                    self.path_length += 1;
+                    self.cells.push((at, level));
+                    self.cursor = level.checked_sub(1).map(|below| (at, below));
+
+                    if level > 0 {
+                        format!("{}: drop from {} to L{}", next, self.name(at), level - 1)
+                    } else {
+                        format!("{}: stop after {} on the bottom lane", next, self.name(at))
+                    }
+                }
+                SkipStep::Found(id) => {
+                    self.marked.push((id, PATH_COLOR));
+
+                    format!("found {}", self.key)
+                }
+                SkipStep::Promote(height) => {
+                    format!("heads: the new node reaches L{} too", height - 1)
+                }
+                SkipStep::SetLevel(level) => {
+                    if level > self.list.level() {
+                        format!(
+                            "the list grows to {} lanes, the head starting the new ones",
+                            level
+                        )
+                    } else {
+                        format!(
+                            "the lanes from L{} up are empty now: the list shrinks to {} lanes",
+                            level, level
+                        )
+                    }
+                }
+                SkipStep::Alloc(id, key, height) => {
+                    self.marked.push((id, RELAXED_COLOR));
+
+                    format!(
+                        "tails: allocate {} with {} lane{}",
+                        key,
+                        height,
+                        if height == 1 { "" } else { "s" }
+                    )
+                }
+                SkipStep::Link { from, level, to } => {
+                    let message = format!(
+                        "point the L{} pointer of {} at {}",
+                        level,
+                        self.name(from),
+                        match to {
+                            Some(to) => self.name(Some(to)),
+                            None => "the end of the lane".to_string(),
+                        }
+                    );
+
+                    self.linked.push((from, level));
+
+                    message
+                }
+                SkipStep::Free(id) => {
+                    self.marked.push((id, DONE_COLOR));
+
+                    format!("free {}", self.key)
+                }
+            };
+
+            self.list.apply(step);
+
+            let current_link = match step {
+                SkipStep::Link { from, level, .. } => Some((from, level)),
+                _ => None,
+            };
+
+            self.record(message, current_link);
+        }
+    }
+
+    /// Records `message` over the list, with `current_link` the pointer
+    /// just written if any.
+    fn record(&mut self, message: String, current_link: Option<(Option<usize>, usize)>) {
+        let mut view = SkipListView::new(&self.list);
+
+        // Nodes freed since are left out.
+        let exists = |view: &SkipListView, at: Option<usize>| {
+            at.is_none_or(|id| view.nodes.get(id).is_some_and(|node| node.is_some()))
+        };
+
+        for &(at, level) in &self.cells {
+            if exists(&view, at) && level < view.tower_mut(at).level_colors.len() {
+                view.tower_mut(at).level_colors[level] = PATH_COLOR;
+            }
+        }
+
+        for &(at, level) in &self.followed {
+            if exists(&view, at) && level < view.tower_mut(at).link_colors.len() {
+                view.tower_mut(at).link_colors[level] = PATH_COLOR;
+            }
+        }
+
+        for &(at, level) in &self.linked {
+            if exists(&view, at) && level < view.tower_mut(at).link_colors.len() {
+                view.tower_mut(at).link_colors[level] = if Some((at, level)) == current_link {
+                    HIGHLIGHT_COLOR
+                } else {
+                    RELAXED_COLOR
+                };
+            }
+        }
+
+        if let Some((at, level)) = self.cursor {
+            if exists(&view, at) && level < view.tower_mut(at).level_colors.len() {
+                view.tower_mut(at).level_colors[level] = CURRENT_COLOR;
+            }
+        }
+
+        for &(id, color) in &self.marked {
+            if exists(&view, Some(id)) {
+                view.tower_mut(Some(id)).color = color;
+            }
+        }
+
+        view.notes = vec![
+            message,
+            format!(
+                "search path: {} steps right and down; expected on average: at most {:.1} for n = {}",
+                self.path_length,
+                self.expected,
+                self.list.len().max(1)
+            ),
+            format!(
+                "{} keys on {} lanes; every node reaches each next lane with probability 1/2",
+                self.list.len(),
+                self.list.level()
+            ),
+            self.summary.clone(),
+        ];
+
+        self.frames.push(view);
+    }
+}
+
+/// A skip list of distinct keys drawn as towers linked along their express
+/// lanes, animating the descent of every search and the pointers inserts
+/// and removals rewire.
+#[wasm_bindgen]
+pub struct SkipListVisualization {
+    list: SkipList,
+    path_length: usize,
+    animation: Animation<SkipListView>,
+}
+
+#[wasm_bindgen]
+impl SkipListVisualization {
+    pub fn search(&mut self, key: i32) -> bool {
+        let replay = Replay::new(&self.list, key, format!("search {}", key));
+        let (found, steps) = self.list.search(key);
+
+        self.replay(
+            replay,
+            &steps,
+            if found {
+                format!("done: found {}", key)
+            } else {
+                format!("done: {} is not in the list", key)
+            },
+        );
+
+        found
+    }
+
+    /// Inserts `key`, returning `false` if it was already there.
+    pub fn insert(&mut self, key: i32) -> bool {
+        let replay = Replay::new(&self.list, key, format!("insert {}", key));
+        let (inserted, steps) = self.list.insert(key);
+
+        self.replay(
+            replay,
+            &steps,
+            if inserted {
+                format!("done: inserted {}", key)
+            } else {
+                format!("{} is in the list already", key)
+            },
+        );
+
+        inserted
+    }
+
+    /// Removes `key`, returning `false` if it was not there.
+    pub fn remove(&mut self, key: i32) -> bool {
+        let replay = Replay::new(&self.list, key, format!("remove {}", key));
+        let (removed, steps) = self.list.remove(key);
+
+        self.replay(
+            replay,
+            &steps,
+            if removed {
+                format!("done: removed {}", key)
+            } else {
+                format!("{} is not in the list", key)
+            },
+        );
+
+        removed
+    }
+
+    /// The keys in order.
+    pub fn keys(&self) -> Vec<i32> {
+        self.list.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// The number of lanes in use.
+    pub fn level(&self) -> usize {
+        self.list.level()
+    }
+
+    /// Steps right and down the last operation took on its way to the key.
+    pub fn path_length(&self) -> usize {
+        self.path_length
+    }
+
+    /// Pugh's bound on the expected search path length for the list as it
+    /// is now.
+    pub fn expected_path_length(&self) -> f64 {
+        self.list.expected_search_length()
+    }
+
+    fn replay(&mut self, mut replay: Replay, steps: &[SkipStep], result: String) {
+        replay.record(replay.summary.clone(), None);
+        replay.run(steps);
+
+        replay.cursor = None;
+        replay.record(result, None);
+
+        self.path_length = replay.path_length;
+        self.animation.push(replay.frames);
+    }
+}
+
+/// Starts a visualization of a skip list holding `keys`, promoting nodes
+/// with a generator seeded with `seed`.
+#[wasm_bindgen]
+pub fn run_skip_list(document_id: &str, seed: u32, keys: Vec<i32>) -> SkipListVisualization {
+    set_panic_hook();
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &SkipListView| view.draw(&ctx));
+
+    let mut list = SkipList::new(seed as u64);
+
+    for key in keys {
+        list.insert(key);
+    }
+
+    let mut view = SkipListView::new(&list);
+
+    view.notes = vec![
+        format!(
+            "skip list of {} keys on {} lanes, seed {}",
+            list.len(),
+            list.level(),
+            seed
+        ),
+        format!(
+            "expected search path: at most {:.1} steps right and down on average",
+            list.expected_search_length()
+        ),
+    ];
+    animation.push([view]);
+
+    SkipListVisualization {
+        list,
+        path_length: 0,
+        animation,
+    }
+}
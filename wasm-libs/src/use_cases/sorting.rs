@@ -0,0 +1,441 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas,
+    renderers::bars::{
+        BarsView, BucketsView, COMPARE_COLOR, PIVOT_COLOR, SORTED_COLOR, SWAP_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+mod comparison_sorts;
+mod distribution_sorts;
+mod presets;
+
+/// Sorting steps are small, so every animation tick shows one.
+const FRAMES_PER_STEP: u32 = 1;
+/// Animated arrays are capped so that the quadratic sorts, which record a
+/// frame per comparison and write, stay within a few tens of thousands of
+/// frames. Headless benchmarks go further.
+const MAX_ANIMATED_LEN: usize = 256;
+/// Benchmarks run the quadratic sorts on every preset, tens of millions
+/// of steps each at this size.
+const MAX_BENCHMARK_LEN: usize = 5_000;
+
+const ALGORITHMS: [&str; 11] = [
+    "bubble",
+    "insertion",
+    "selection",
+    "merge",
+    "quick",
+    "quick-median-of-three",
+    "heap",
+    "counting",
+    "radix-lsd",
+    "radix-msd",
+    "bucket",
+];
+
+/// The array being sorted plus everything the bars show about it. Every
+/// comparison and every write goes through `Sorter`, which counts it and,
+/// unless it runs headless, records a frame.
+struct Sorter {
+    values: Vec<u32>,
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+    /// Writes to buckets and other arrays besides `values`.
+    auxiliary_writes: usize,
+    /// Positions known to hold their final value.
+    sorted: Vec<bool>,
+    pivot: Option<usize>,
+    auxiliary: Vec<BucketsView>,
+    /// One line of statistics per finished pass of a distribution sort.
+    passes: Vec<String>,
+    recording: bool,
+    frames: Vec<BarsView>,
+}
+
+/// The counters when a pass started.
+struct PassStart {
+    comparisons: usize,
+    writes: usize,
+    auxiliary_writes: usize,
+}
+
+impl Sorter {
+    fn new(values: Vec<u32>) -> Sorter {
+        let mut sorter = Sorter::headless(values);
+
+        sorter.recording = true;
+        sorter.record(&[], "start".to_string());
+
+        sorter
+    }
+
+    /// A sorter that only counts, for benchmarks.
+    fn headless(values: Vec<u32>) -> Sorter {
+        let n = values.len();
+
+        Sorter {
+            values,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+            auxiliary_writes: 0,
+            sorted: vec![false; n],
+            pivot: None,
+            auxiliary: vec![],
+            passes: vec![],
+            recording: false,
+            frames: vec![],
+        }
+    }
+
+    fn run(&mut self, algorithm: &str) {
+        match algorithm {
+            "bubble" => self.bubble_sort(),
+            "insertion" => self.insertion_sort(),
+            "selection" => self.selection_sort(),
+            "merge" => self.merge_sort(),
+            "quick" => self.quick_sort(false),
+            "quick-median-of-three" => self.quick_sort(true),
+            "heap" => self.heap_sort(),
+            "counting" => self.counting_sort(),
+            "radix-lsd" => self.radix_sort_lsd(),
+            "radix-msd" => self.radix_sort_msd(),
+            "bucket" => self.bucket_sort(),
+            _ => unreachable!("algorithm names are checked by the caller"),
+        }
+
+        self.pivot = None;
+        self.sorted.iter_mut().for_each(|sorted| *sorted = true);
+        self.record(&[], format!("{} sort: done", algorithm));
+    }
+
+    /// Whether `values[i] < values[j]`, as one counted comparison.
+    fn less(&mut self, i: usize, j: usize) -> bool {
+        self.comparisons += 1;
+
+        let less = self.values[i] < self.values[j];
+
+        // Messages are built only when recorded, which keeps headless runs
+        // to the comparison itself.
+        if self.recording {
+            self.record(
+                &[(i, COMPARE_COLOR), (j, COMPARE_COLOR)],
+                format!(
+                    "compare a[{}] = {} {} a[{}] = {}",
+                    i,
+                    self.values[i],
+                    if less { "<" } else { ">=" },
+                    j,
+                    self.values[j]
+                ),
+            );
+        }
+
+        less
+    }
+
+    /// Whether `value`, held outside the array, is less than `values[j]`,
+    /// highlighting `at` as the position it is compared for.
+    fn less_than_value(&mut self, j: usize, value: u32, at: usize) -> bool {
+        self.comparisons += 1;
+
+        let less = self.values[j] < value;
+
+        if self.recording {
+            self.record(
+                &[(at, COMPARE_COLOR), (j, COMPARE_COLOR)],
+                format!(
+                    "compare a[{}] = {} {} {}",
+                    j,
+                    self.values[j],
+                    if less { "<" } else { ">=" },
+                    value
+                ),
+            );
+        }
+
+        less
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.values.swap(i, j);
+        self.swaps += 1;
+        self.writes += 2;
+
+        if self.recording {
+            self.record(
+                &[(i, SWAP_COLOR), (j, SWAP_COLOR)],
+                format!("swap a[{}] and a[{}]", i, j),
+            );
+        }
+    }
+
+    fn write(&mut self, i: usize, value: u32) {
+        self.values[i] = value;
+        self.writes += 1;
+
+        if self.recording {
+            self.record(&[(i, SWAP_COLOR)], format!("write {} to a[{}]", value, i));
+        }
+    }
+
+    fn mark_sorted(&mut self, i: usize) {
+        self.sorted[i] = true;
+    }
+
+    fn begin_pass(&self) -> PassStart {
+        PassStart {
+            comparisons: self.comparisons,
+            writes: self.writes,
+            auxiliary_writes: self.auxiliary_writes,
+        }
+    }
+
+    fn end_pass(&mut self, name: String, start: PassStart) {
+        self.passes.push(format!(
+            "{}: {} comparisons, {} writes, {} auxiliary writes",
+            name,
+            self.comparisons - start.comparisons,
+            self.writes - start.writes,
+            self.auxiliary_writes - start.auxiliary_writes
+        ));
+    }
+
+    fn record(&mut self, highlighted: &[(usize, &'static str)], message: String) {
+        if !self.recording {
+            return;
+        }
+
+        let mut view = BarsView::new(&self.values);
+
+        for (idx, &sorted) in self.sorted.iter().enumerate() {
+            if sorted {
+                view.colors[idx] = SORTED_COLOR;
+            }
+        }
+
+        if let Some(pivot) = self.pivot {
+            view.colors[pivot] = PIVOT_COLOR;
+        }
+
+        for &(idx, color) in highlighted {
+            view.colors[idx] = color;
+        }
+
+        view.auxiliary = self.auxiliary.clone();
+        view.notes = vec![
+            message,
+            format!("comparisons: {}, writes: {}", self.comparisons, self.writes),
+        ];
+
+        if self.auxiliary_writes > 0 {
+            view.notes[1].push_str(&format!(", auxiliary writes: {}", self.auxiliary_writes));
+        }
+
+        if let Some(pass) = self.passes.last() {
+            view.notes.push(format!("last pass, {}", pass));
+        }
+
+        self.frames.push(view);
+    }
+}
+
+#[wasm_bindgen]
+pub struct SortStats {
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+    auxiliary_writes: usize,
+    passes: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl SortStats {
+    pub fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    pub fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    /// Array writes, two per swap.
+    pub fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Writes to buckets, counters and output arrays.
+    pub fn auxiliary_writes(&self) -> usize {
+        self.auxiliary_writes
+    }
+
+    /// Statistics of every pass of a distribution sort, one line each.
+    pub fn passes(&self) -> js_sys::Array {
+        self.passes
+            .iter()
+            .map(|pass| JsValue::from_str(pass))
+            .collect()
+    }
+}
+
+fn check_algorithm(algorithm: &str) -> Result<(), JsValue> {
+    if ALGORITHMS.contains(&algorithm) {
+        Ok(())
+    } else {
+        Err(JsValue::from_str(&format!(
+            "unknown sorting algorithm `{}`, expected one of {}",
+            algorithm,
+            ALGORITHMS.join(", ")
+        )))
+    }
+}
+
+/// Animates `algorithm` sorting a seeded shuffle of `1..=n` drawn as bars,
+/// one comparison or write per step, with `n` at most 256. Distribution
+/// sorts draw their buckets beneath the array.
+#[wasm_bindgen]
+pub fn run_sort(
+    document_id: &str,
+    algorithm: &str,
+    n: usize,
+    seed: u32,
+) -> Result<SortStats, JsValue> {
+    run_sort_preset(document_id, algorithm, "random", n, seed)
+}
+
+/// Like `run_sort`, starting from one of the input presets: `"random"`,
+/// `"nearly-sorted"`, `"reversed"`, `"few-unique"` or `"sawtooth"`.
+#[wasm_bindgen]
+pub fn run_sort_preset(
+    document_id: &str,
+    algorithm: &str,
+    preset: &str,
+    n: usize,
+    seed: u32,
+) -> Result<SortStats, JsValue> {
+    set_panic_hook();
+
+    check_algorithm(algorithm)?;
+
+    if n > MAX_ANIMATED_LEN {
+        return Err(JsValue::from_str(&format!(
+            "cannot animate {} values, at most {} fit",
+            n, MAX_ANIMATED_LEN
+        )));
+    }
+
+    let mut sorter = Sorter::new(presets::values(preset, n, seed)?);
+    sorter.run(algorithm);
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &BarsView| view.draw(&ctx));
+
+    animation.push(sorter.frames);
+
+    Ok(SortStats {
+        comparisons: sorter.comparisons,
+        swaps: sorter.swaps,
+        writes: sorter.writes,
+        auxiliary_writes: sorter.auxiliary_writes,
+        passes: sorter.passes,
+    })
+}
+
+/// Runs every algorithm headless on every preset of size `n`, at most
+/// 5000, and returns one row object per run with `preset`, `algorithm`,
+/// `comparisons`, `swaps`, `writes` and `auxiliary_writes`.
+#[wasm_bindgen]
+pub fn benchmark_sorts(n: usize, seed: u32) -> Result<js_sys::Array, JsValue> {
+    if n > MAX_BENCHMARK_LEN {
+        return Err(JsValue::from_str(&format!(
+            "cannot benchmark {} values, at most {} run in reasonable time",
+            n, MAX_BENCHMARK_LEN
+        )));
+    }
+
+    let rows = js_sys::Array::new();
+
+    for preset in presets::PRESETS {
+        let input = presets::values(preset, n, seed).unwrap();
+
+        for algorithm in ALGORITHMS {
+            let mut sorter = Sorter::headless(input.clone());
+            sorter.run(algorithm);
+
+            let row = js_sys::Object::new();
+
+            for (key, value) in [
+                ("preset", JsValue::from_str(preset)),
+                ("algorithm", JsValue::from_str(algorithm)),
+                ("comparisons", JsValue::from(sorter.comparisons as f64)),
+                ("swaps", JsValue::from(sorter.swaps as f64)),
+                ("writes", JsValue::from(sorter.writes as f64)),
+                (
+                    "auxiliary_writes",
+                    JsValue::from(sorter.auxiliary_writes as f64),
+                ),
+            ] {
+                let _ = js_sys::Reflect::set(&row, &JsValue::from_str(key), &value);
+            }
+
+            rows.push(&row);
+        }
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sort(algorithm: &str, preset: &str, n: usize) -> Sorter {
+        let mut sorter = Sorter::headless(presets::values(preset, n, 7).unwrap());
+
+        sorter.run(algorithm);
+        sorter
+    }
+
+    #[test]
+    fn every_algorithm_sorts_every_preset() {
+        for preset in presets::PRESETS {
+            for algorithm in ALGORITHMS {
+                for n in [0, 1, 2, 3, 10, 97] {
+                    let sorter = sort(algorithm, preset, n);
+                    let mut expected = presets::values(preset, n, 7).unwrap();
+
+                    expected.sort();
+                    assert_eq!(sorter.values, expected, "{} on {} {}", algorithm, preset, n);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn quick_sort_handles_large_degenerate_inputs() {
+        let n = MAX_BENCHMARK_LEN;
+
+        for preset in presets::PRESETS {
+            let sorter = sort("quick-median-of-three", preset, n);
+
+            assert!(sorter.values.windows(2).all(|pair| pair[0] <= pair[1]));
+            assert!(
+                sorter.comparisons < 50 * n,
+                "{} comparisons on {}",
+                sorter.comparisons,
+                preset
+            );
+        }
+
+        // Plain quicksort is quadratic on sorted input, but must not run
+        // out of stack on it.
+        let sorter = sort("quick", "reversed", n);
+
+        assert!(sorter.values.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
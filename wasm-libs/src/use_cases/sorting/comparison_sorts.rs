@@ -0,0 +1,249 @@
+use super::Sorter;
+
+impl Sorter {
+    /// Bubbles the largest remaining value to the end on every pass,
+    /// stopping early after a pass without swaps.
+    pub(super) fn bubble_sort(&mut self) {
+        let n = self.values.len();
+
+        for end in (1..n).rev() {
+            let mut swapped = false;
+
+            for i in 0..end {
+                if self.less(i + 1, i) {
+                    self.swap(i, i + 1);
+                    swapped = true;
+                }
+            }
+
+            self.mark_sorted(end);
+
+            if !swapped {
+                break;
+            }
+        }
+    }
+
+    /// Swaps each value back into the sorted prefix until its left
+    /// neighbor is not larger.
+    pub(super) fn insertion_sort(&mut self) {
+        for i in 1..self.values.len() {
+            let mut j = i;
+
+            while j > 0 && self.less(j, j - 1) {
+                self.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+    }
+
+    /// Finds the minimum of the unsorted suffix and swaps it to its front.
+    pub(super) fn selection_sort(&mut self) {
+        let n = self.values.len();
+
+        for i in 0..n {
+            let mut min = i;
+
+            for j in (i + 1)..n {
+                if self.less(j, min) {
+                    min = j;
+                }
+            }
+
+            if min != i {
+                self.swap(i, min);
+            }
+
+            self.mark_sorted(i);
+        }
+    }
+
+    pub(super) fn merge_sort(&mut self) {
+        self.merge_sort_range(0, self.values.len());
+    }
+
+    /// Top-down merge sort of `values[lo..hi]`.
+    fn merge_sort_range(&mut self, lo: usize, hi: usize) {
+        if hi - lo < 2 {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+
+        self.merge_sort_range(lo, mid);
+        self.merge_sort_range(mid, hi);
+        self.merge(lo, mid, hi);
+    }
+
+    /// Merges the sorted runs `lo..mid` and `mid..hi`. The left run is
+    /// copied out, so the write position never passes the right run's head.
+    fn merge(&mut self, lo: usize, mid: usize, hi: usize) {
+        let left = self.values[lo..mid].to_vec();
+        let (mut i, mut j, mut k) = (0, mid, lo);
+
+        while i < left.len() && j < hi {
+            if self.less_than_value(j, left[i], k) {
+                let value = self.values[j];
+                self.write(k, value);
+                j += 1;
+            } else {
+                self.write(k, left[i]);
+                i += 1;
+            }
+
+            k += 1;
+        }
+
+        while i < left.len() {
+            self.write(k, left[i]);
+            i += 1;
+            k += 1;
+        }
+    }
+
+    /// Quicksort pivoting on the last value, or with `median_of_three` on
+    /// the median of the first, middle and last values, which keeps sorted
+    /// and reversed inputs from degrading to quadratic time. Runs of values
+    /// equal to the pivot are split off in one go, so few distinct values
+    /// do not degrade it either way.
+    pub(super) fn quick_sort(&mut self, median_of_three: bool) {
+        let n = self.values.len();
+
+        if n > 0 {
+            self.quick_sort_range(0, n - 1, median_of_three);
+        }
+    }
+
+    /// Quicksort of `values[lo..=hi]` around the value moved to `hi`.
+    /// Only the smaller side is sorted recursively and the larger one on
+    /// the same call, keeping the recursion depth logarithmic.
+    fn quick_sort_range(&mut self, mut lo: usize, mut hi: usize, median_of_three: bool) {
+        while lo < hi {
+            if median_of_three && hi - lo >= 2 {
+                self.move_median_to_end(lo, hi);
+            }
+
+            let (first, last) = self.partition(lo, hi);
+
+            if first - lo < hi - last {
+                if first > lo {
+                    self.quick_sort_range(lo, first - 1, median_of_three);
+                }
+                lo = last + 1;
+            } else {
+                if last < hi {
+                    self.quick_sort_range(last + 1, hi, median_of_three);
+                }
+                if first == 0 {
+                    return;
+                }
+                hi = first - 1;
+            }
+        }
+
+        if lo == hi {
+            self.mark_sorted(lo);
+        }
+    }
+
+    /// Three-way partitioning of `values[lo..=hi]` around the pivot at
+    /// `hi`: a Lomuto pass moves the smaller values to the front, then a
+    /// second one gathers the values equal to the pivot right after them.
+    /// Returns the first and last position of that run, which is sorted.
+    fn partition(&mut self, lo: usize, hi: usize) -> (usize, usize) {
+        self.pivot = Some(hi);
+
+        let mut first = lo;
+
+        for j in lo..hi {
+            if self.less(j, hi) {
+                if first != j {
+                    self.swap(first, j);
+                }
+                first += 1;
+            }
+        }
+
+        let mut last = first;
+
+        for j in first..hi {
+            if !self.less(hi, j) {
+                if last != j {
+                    self.swap(last, j);
+                }
+                last += 1;
+            }
+        }
+
+        self.pivot = None;
+
+        if last != hi {
+            self.swap(last, hi);
+        }
+
+        for position in first..=last {
+            self.mark_sorted(position);
+        }
+
+        (first, last)
+    }
+
+    /// Orders `values[lo]`, `values[mid]` and `values[hi]`, then swaps the
+    /// median of them into `hi` as the pivot.
+    fn move_median_to_end(&mut self, lo: usize, hi: usize) {
+        let mid = lo + (hi - lo) / 2;
+
+        if self.less(mid, lo) {
+            self.swap(lo, mid);
+        }
+        if self.less(hi, lo) {
+            self.swap(lo, hi);
+        }
+        if self.less(hi, mid) {
+            self.swap(mid, hi);
+        }
+
+        self.swap(mid, hi);
+    }
+
+    /// Builds a max-heap in place, then repeatedly swaps its root behind
+    /// the shrinking heap.
+    pub(super) fn heap_sort(&mut self) {
+        let n = self.values.len();
+
+        for root in (0..n / 2).rev() {
+            self.sift_down(root, n);
+        }
+
+        for end in (1..n).rev() {
+            self.swap(0, end);
+            self.mark_sorted(end);
+            self.sift_down(0, end);
+        }
+    }
+
+    /// Moves `values[root]` down the max-heap `values[..end]` until both
+    /// children are smaller.
+    fn sift_down(&mut self, mut root: usize, end: usize) {
+        loop {
+            let left = 2 * root + 1;
+
+            if left >= end {
+                return;
+            }
+
+            let mut largest = left;
+
+            if left + 1 < end && self.less(left, left + 1) {
+                largest = left + 1;
+            }
+
+            if !self.less(root, largest) {
+                return;
+            }
+
+            self.swap(root, largest);
+            root = largest;
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use super::Sorter;
+use crate::renderers::bars::{BucketsView, COMPARE_COLOR};
+
+const RADIX: u32 = 10;
+
+fn digit_buckets() -> BucketsView {
+    let mut buckets = BucketsView::new("digit buckets", RADIX as usize);
+    buckets.captions = (0..RADIX).map(|digit| digit.to_string()).collect();
+
+    buckets
+}
+
+impl Sorter {
+    /// Counts every value, turns the counts into end positions with prefix
+    /// sums, then places values into an output array from the right, which
+    /// keeps equal values in order.
+    pub(super) fn counting_sort(&mut self) {
+        let n = self.values.len();
+        let (Some(&min), Some(&max)) = (self.values.iter().min(), self.values.iter().max()) else {
+            return;
+        };
+        let range = (max - min + 1) as usize;
+
+        self.auxiliary = vec![
+            BucketsView::new("counts", range),
+            BucketsView::new("output", n),
+        ];
+
+        let start = self.begin_pass();
+
+        for i in 0..n {
+            let value = self.values[i];
+            let slot = (value - min) as usize;
+
+            self.push_auxiliary(0, slot, value);
+            self.auxiliary[0].captions[slot] = self.auxiliary[0].buckets[slot].len().to_string();
+            self.record(&[(i, COMPARE_COLOR)], format!("count a[{}] = {}", i, value));
+        }
+
+        self.end_pass("count occurrences".to_string(), start);
+
+        let start = self.begin_pass();
+        let mut ends = vec![0; range];
+        let mut position = 0;
+
+        for (slot, end) in ends.iter_mut().enumerate() {
+            position += self.auxiliary[0].buckets[slot].len();
+            *end = position;
+
+            self.auxiliary_writes += 1;
+            self.auxiliary[0].captions[slot] = position.to_string();
+            self.auxiliary[0].highlighted = Some(slot);
+            self.record(
+                &[],
+                format!(
+                    "prefix sum: values equal to {} end before position {}",
+                    min as usize + slot,
+                    position
+                ),
+            );
+        }
+
+        self.end_pass("prefix sums".to_string(), start);
+
+        let start = self.begin_pass();
+
+        for i in (0..n).rev() {
+            let value = self.values[i];
+            let slot = (value - min) as usize;
+
+            ends[slot] -= 1;
+            self.auxiliary[0].buckets[slot].pop();
+            self.auxiliary[0].captions[slot] = ends[slot].to_string();
+            self.auxiliary[0].highlighted = Some(slot);
+            self.auxiliary_writes += 1;
+
+            self.auxiliary[1].buckets[ends[slot]] = vec![value];
+            self.auxiliary[1].highlighted = Some(ends[slot]);
+            self.auxiliary_writes += 1;
+
+            self.record(
+                &[(i, COMPARE_COLOR)],
+                format!("place a[{}] = {} at output[{}]", i, value, ends[slot]),
+            );
+        }
+
+        self.auxiliary[0].highlighted = None;
+        self.end_pass("place into output".to_string(), start);
+
+        let start = self.begin_pass();
+
+        for position in 0..n {
+            let value = self.auxiliary[1].buckets[position].pop().unwrap();
+
+            self.auxiliary[1].highlighted = Some(position);
+            self.write(position, value);
+            self.mark_sorted(position);
+        }
+
+        self.auxiliary[1].highlighted = None;
+        self.end_pass("copy back".to_string(), start);
+    }
+
+    /// Stable bucket passes on the decimal digits, least significant first.
+    pub(super) fn radix_sort_lsd(&mut self) {
+        let max = self.values.iter().copied().max().unwrap_or(0);
+        let mut place = 1u32;
+
+        self.auxiliary = vec![digit_buckets()];
+
+        while max / place > 0 {
+            let start = self.begin_pass();
+
+            self.distribute(0, self.values.len(), place);
+            self.collect(0);
+
+            self.end_pass(format!("{}s digit", place), start);
+
+            match place.checked_mul(RADIX) {
+                Some(next) => place = next,
+                None => break,
+            }
+        }
+    }
+
+    /// Buckets on the most significant decimal digit, then sorts every
+    /// bucket recursively on the next digit.
+    pub(super) fn radix_sort_msd(&mut self) {
+        let max = self.values.iter().copied().max().unwrap_or(0);
+        let mut place = 1u32;
+
+        while let Some(next) = place.checked_mul(RADIX) {
+            if next > max {
+                break;
+            }
+            place = next;
+        }
+
+        self.auxiliary = vec![digit_buckets()];
+        self.radix_sort_msd_range(0, self.values.len(), place);
+    }
+
+    fn radix_sort_msd_range(&mut self, lo: usize, hi: usize, place: u32) {
+        if hi - lo < 2 {
+            (lo..hi).for_each(|idx| self.mark_sorted(idx));
+            return;
+        }
+
+        let start = self.begin_pass();
+
+        self.distribute(lo, hi, place);
+
+        let sizes = self.auxiliary[0]
+            .buckets
+            .iter()
+            .map(|bucket| bucket.len())
+            .collect::<Vec<_>>();
+
+        self.collect(lo);
+        self.end_pass(format!("{}s digit of a[{}..{}]", place, lo, hi), start);
+
+        let mut bucket_lo = lo;
+
+        for size in sizes {
+            if place == 1 {
+                (bucket_lo..bucket_lo + size).for_each(|idx| self.mark_sorted(idx));
+            } else {
+                self.radix_sort_msd_range(bucket_lo, bucket_lo + size, place / RADIX);
+            }
+
+            bucket_lo += size;
+        }
+    }
+
+    /// Splits the value range into about √n equal buckets, insertion sorts
+    /// every bucket and concatenates them.
+    pub(super) fn bucket_sort(&mut self) {
+        let n = self.values.len();
+        let (Some(&min), Some(&max)) = (self.values.iter().min(), self.values.iter().max()) else {
+            return;
+        };
+        let count = ((n as f64).sqrt().ceil() as usize).max(1);
+        let span = (max - min) as u64 + 1;
+        let bucket_of = |value: u32| ((value - min) as u64 * count as u64 / span) as usize;
+
+        let mut buckets = BucketsView::new("buckets", count);
+
+        for (bucket, caption) in buckets.captions.iter_mut().enumerate() {
+            let low = min as u64 + (bucket as u64 * span).div_ceil(count as u64);
+            let high = min as u64 + ((bucket as u64 + 1) * span).div_ceil(count as u64) - 1;
+            *caption = format!("{}-{}", low, high);
+        }
+
+        self.auxiliary = vec![buckets];
+
+        let start = self.begin_pass();
+
+        for i in 0..n {
+            let value = self.values[i];
+            let bucket = bucket_of(value);
+
+            self.push_auxiliary(0, bucket, value);
+            self.record(
+                &[(i, COMPARE_COLOR)],
+                format!("a[{}] = {} goes to bucket {}", i, value, bucket),
+            );
+        }
+
+        self.end_pass("distribute".to_string(), start);
+
+        let start = self.begin_pass();
+
+        for bucket in 0..count {
+            self.auxiliary[0].highlighted = Some(bucket);
+
+            for i in 1..self.auxiliary[0].buckets[bucket].len() {
+                let mut j = i;
+
+                while j > 0 {
+                    let (left, right) = (
+                        self.auxiliary[0].buckets[bucket][j - 1],
+                        self.auxiliary[0].buckets[bucket][j],
+                    );
+
+                    self.comparisons += 1;
+                    self.record(
+                        &[],
+                        format!("bucket {}: compare {} and {}", bucket, left, right),
+                    );
+
+                    if right >= left {
+                        break;
+                    }
+
+                    self.auxiliary[0].buckets[bucket].swap(j - 1, j);
+                    self.auxiliary_writes += 2;
+                    self.record(
+                        &[],
+                        format!("bucket {}: swap {} and {}", bucket, left, right),
+                    );
+
+                    j -= 1;
+                }
+            }
+        }
+
+        self.end_pass("sort buckets".to_string(), start);
+
+        let start = self.begin_pass();
+
+        self.collect(0);
+        (0..n).for_each(|idx| self.mark_sorted(idx));
+
+        self.end_pass("concatenate".to_string(), start);
+    }
+
+    /// Moves `values[lo..hi]` into the digit buckets by their `place` digit.
+    fn distribute(&mut self, lo: usize, hi: usize, place: u32) {
+        for i in lo..hi {
+            let value = self.values[i];
+            let digit = ((value / place) % RADIX) as usize;
+
+            self.push_auxiliary(0, digit, value);
+            self.record(
+                &[(i, COMPARE_COLOR)],
+                format!("a[{}] = {} has {} in the {}s place", i, value, digit, place),
+            );
+        }
+    }
+
+    /// Writes the buckets of the first lane back into the array from `lo`,
+    /// in bucket order.
+    fn collect(&mut self, lo: usize) {
+        let mut position = lo;
+
+        for bucket in 0..self.auxiliary[0].buckets.len() {
+            while !self.auxiliary[0].buckets[bucket].is_empty() {
+                let value = self.auxiliary[0].buckets[bucket].remove(0);
+
+                self.auxiliary[0].highlighted = Some(bucket);
+                self.write(position, value);
+                position += 1;
+            }
+        }
+
+        self.auxiliary[0].highlighted = None;
+    }
+
+    fn push_auxiliary(&mut self, lane: usize, bucket: usize, value: u32) {
+        self.auxiliary[lane].buckets[bucket].push(value);
+        self.auxiliary[lane].highlighted = Some(bucket);
+        self.auxiliary_writes += 1;
+    }
+}
@@ -0,0 +1,69 @@
+use wasm_bindgen::prelude::*;
+
+use crate::rng::Rng;
+
+pub(super) const PRESETS: [&str; 5] = [
+    "random",
+    "nearly-sorted",
+    "reversed",
+    "few-unique",
+    "sawtooth",
+];
+
+/// How many distinct values the `"few-unique"` preset uses.
+const FEW_UNIQUE_VALUES: u32 = 4;
+
+/// How far apart the neighbors exchanged by `"nearly-sorted"` may be.
+const NEARLY_SORTED_REACH: usize = 3;
+
+/// `n` values shaped by `preset`, all between 1 and `n`.
+pub(super) fn values(preset: &str, n: usize, seed: u32) -> Result<Vec<u32>, JsValue> {
+    let mut rng = Rng::new(seed as u64);
+    let mut values = (1..=n as u32).collect::<Vec<_>>();
+
+    match preset {
+        "random" => rng.shuffle(&mut values),
+        // About one value in ten exchanged with a close neighbor.
+        "nearly-sorted" => {
+            if n > 1 {
+                for _ in 0..n.div_ceil(10) {
+                    let i = rng.below(n);
+                    let reach = 1 + rng.below(NEARLY_SORTED_REACH);
+                    let j = if i + reach < n {
+                        i + reach
+                    } else {
+                        i.saturating_sub(reach)
+                    };
+
+                    values.swap(i, j);
+                }
+            }
+        }
+        "reversed" => values.reverse(),
+        "few-unique" => {
+            let step = (n as u32 / FEW_UNIQUE_VALUES).max(1);
+
+            for value in values.iter_mut() {
+                *value = (1 + rng.below(FEW_UNIQUE_VALUES as usize) as u32) * step;
+            }
+        }
+        // Five ascending runs.
+        "sawtooth" => {
+            let tooth = n.div_ceil(5).max(1);
+            let step = (n / tooth).max(1) as u32;
+
+            for (idx, value) in values.iter_mut().enumerate() {
+                *value = (idx % tooth + 1) as u32 * step;
+            }
+        }
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown input preset `{}`, expected one of {}",
+                preset,
+                PRESETS.join(", ")
+            )))
+        }
+    }
+
+    Ok(values)
+}
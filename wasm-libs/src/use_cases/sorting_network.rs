@@ -0,0 +1,209 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation, canvas, data_structures::sorting_network::SortingNetwork,
+    renderers::network::NetworkView, rng::Rng, utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 40;
+
+/// The 0-1 check tries all `2^wires` inputs, so it stops here.
+const CHECK_WIRE_LIMIT: usize = 20;
+
+/// Animated networks are capped like animated sorts, so the wires still
+/// fit on the canvas and the frames stay few.
+const MAX_ANIMATED_WIRES: usize = 256;
+
+const NETWORKS: [&str; 2] = ["bitonic", "odd-even-merge"];
+
+fn build_network(kind: &str, wires: usize) -> Result<SortingNetwork, JsValue> {
+    match kind {
+        "bitonic" => Ok(SortingNetwork::bitonic(wires)),
+        "odd-even-merge" => Ok(SortingNetwork::odd_even_merge(wires)),
+        _ => Err(JsValue::from_str(&format!(
+            "unknown sorting network `{}`, expected one of {}",
+            kind,
+            NETWORKS.join(", ")
+        ))),
+    }
+}
+
+/// Two frames per layer: its comparators about to fire, then the values
+/// after it with the swapping comparators highlighted.
+fn network_frames(network: &SortingNetwork, mut values: Vec<u32>) -> Vec<NetworkView> {
+    let layers = network.layers();
+    let mut frames = vec![];
+
+    for (layer, comparators) in layers.iter().enumerate() {
+        frames.push(NetworkView {
+            values: values.clone(),
+            passed: layer,
+            active: comparators.clone(),
+            swapped: vec![],
+            notes: vec![format!(
+                "layer {} of {}: {} comparators",
+                layer + 1,
+                layers.len(),
+                comparators.len()
+            )],
+        });
+
+        let swapped = comparators
+            .iter()
+            .copied()
+            .filter(|&idx| network.apply(idx, &mut values))
+            .collect::<Vec<_>>();
+
+        frames.push(NetworkView {
+            values: values.clone(),
+            passed: layer + 1,
+            active: comparators.clone(),
+            notes: vec![format!(
+                "layer {} of {}: {} of {} comparators swapped",
+                layer + 1,
+                layers.len(),
+                swapped.len(),
+                comparators.len()
+            )],
+            swapped,
+        });
+    }
+
+    frames.push(NetworkView {
+        values,
+        passed: layers.len(),
+        active: vec![],
+        swapped: vec![],
+        notes: vec![format!(
+            "done: depth {}, {} comparators",
+            layers.len(),
+            network.comparators().len()
+        )],
+    });
+
+    frames
+}
+
+#[wasm_bindgen]
+pub struct NetworkCheck {
+    depth: usize,
+    comparator_count: usize,
+    verified: Option<bool>,
+    counterexample: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl NetworkCheck {
+    /// The number of parallel layers.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn comparator_count(&self) -> usize {
+        self.comparator_count
+    }
+
+    /// Whether the 0-1 principle proves the network sorts, `undefined`
+    /// above the wire limit of the check.
+    pub fn verified(&self) -> Option<bool> {
+        self.verified
+    }
+
+    /// A 0-1 input the network leaves unsorted, empty if there is none.
+    pub fn counterexample(&self) -> Vec<u32> {
+        self.counterexample.clone()
+    }
+}
+
+fn check(network: &SortingNetwork) -> NetworkCheck {
+    let (verified, counterexample) = if network.wires() <= CHECK_WIRE_LIMIT {
+        match network.zero_one_counterexample() {
+            Some(input) => (Some(false), input),
+            None => (Some(true), vec![]),
+        }
+    } else {
+        (None, vec![])
+    };
+
+    NetworkCheck {
+        depth: network.depth(),
+        comparator_count: network.comparators().len(),
+        verified,
+        counterexample,
+    }
+}
+
+/// Animates a seeded shuffle of `1..=wires` flowing layer by layer through
+/// a `"bitonic"` or `"odd-even-merge"` network of at most 256 wires, and
+/// checks the network with the 0-1 principle.
+#[wasm_bindgen]
+pub fn run_sorting_network(
+    document_id: &str,
+    kind: &str,
+    wires: usize,
+    seed: u32,
+) -> Result<NetworkCheck, JsValue> {
+    set_panic_hook();
+
+    if wires > MAX_ANIMATED_WIRES {
+        return Err(JsValue::from_str(&format!(
+            "cannot animate {} wires, at most {} fit",
+            wires, MAX_ANIMATED_WIRES
+        )));
+    }
+
+    let network = build_network(kind, wires)?;
+
+    let mut values = (1..=wires as u32).collect::<Vec<_>>();
+    Rng::new(seed as u64).shuffle(&mut values);
+
+    let mut frames = network_frames(&network, values);
+    let result = check(&network);
+
+    if let Some(last) = frames.last_mut() {
+        last.notes.push(match result.verified {
+            Some(true) => "0-1 principle: sorts every input".to_string(),
+            Some(false) => format!("0-1 principle: fails on {:?}", result.counterexample),
+            None => format!("0-1 check skipped above {} wires", CHECK_WIRE_LIMIT),
+        });
+    }
+
+    let ctx = canvas::context(document_id);
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |view: &NetworkView| {
+        view.draw(&ctx, &network)
+    });
+
+    animation.push(frames);
+
+    Ok(result)
+}
+
+/// Checks a hand-written network on `wires` wires, given as flat
+/// `[a0, b0, a1, b1, ...]` comparator pairs.
+#[wasm_bindgen]
+pub fn check_sorting_network(
+    wires: usize,
+    comparators: Vec<usize>,
+) -> Result<NetworkCheck, JsValue> {
+    if comparators.len() % 2 == 1 {
+        return Err(JsValue::from_str(
+            "comparators must be given as pairs of wires",
+        ));
+    }
+
+    let mut network = SortingNetwork::new(wires);
+
+    for (idx, pair) in comparators.chunks(2).enumerate() {
+        if pair[0] >= wires || pair[1] >= wires || pair[0] == pair[1] {
+            return Err(JsValue::from_str(&format!(
+                "comparator {} joins wires {} and {}, expected two different wires below {}",
+                idx, pair[0], pair[1], wires
+            )));
+        }
+
+        network.add_comparator(pair[0], pair[1]);
+    }
+
+    Ok(check(&network))
+}
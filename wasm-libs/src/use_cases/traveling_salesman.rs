@@ -0,0 +1,483 @@
+use js_sys::Math::random;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::Animation,
+    canvas::{self, Boundary, Coordinate},
+    data_structures::general_graph::GeneralGraph,
+    renderers::graph::{
+        GraphView, ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, NODE_RADIUS, PATH_COLOR,
+        RELAXED_COLOR,
+    },
+    utils::set_panic_hook,
+};
+
+const FRAMES_PER_STEP: u32 = 20;
+
+/// Held-Karp is O(2^n n^2), so the optimum is only computed this far.
+const HELD_KARP_LIMIT: usize = 12;
+
+/// Improvements smaller than this are rounding noise.
+const EPSILON: f64 = 1e-9;
+
+/// Or-opt moves segments of up to this many consecutive cities.
+const OR_OPT_MAX_SEGMENT: usize = 3;
+
+type Frame = (GeneralGraph, GraphView);
+
+struct Tsp {
+    points: Vec<Coordinate>,
+    tour: Vec<usize>,
+    optimum: Option<f64>,
+    frames: Vec<Frame>,
+}
+
+impl Tsp {
+    fn new(points: Vec<Coordinate>) -> Tsp {
+        let optimum = if points.len() <= HELD_KARP_LIMIT {
+            held_karp(&points).map(|tour| tour_length(&points, &tour))
+        } else {
+            None
+        };
+
+        Tsp {
+            points,
+            tour: vec![],
+            optimum,
+            frames: vec![],
+        }
+    }
+
+    /// Starts at city 0 and always moves to the closest unvisited city.
+    fn nearest_neighbor(&mut self) {
+        let n = self.points.len();
+
+        if n == 0 {
+            return;
+        }
+
+        let mut visited = vec![false; n];
+
+        self.tour = vec![0];
+        visited[0] = true;
+
+        self.record(false, &[0], &[], "nearest neighbor: start at 0".to_string());
+
+        while self.tour.len() < n {
+            let last = *self.tour.last().unwrap();
+            let next = (0..n)
+                .filter(|&city| !visited[city])
+                .min_by(|&a, &b| self.distance(last, a).total_cmp(&self.distance(last, b)))
+                .unwrap();
+
+            visited[next] = true;
+            self.tour.push(next);
+
+            self.record(
+                false,
+                &[next],
+                &[(last, next, RELAXED_COLOR)],
+                format!(
+                    "nearest neighbor: {} is closest to {} ({:.1})",
+                    next,
+                    last,
+                    self.distance(last, next)
+                ),
+            );
+        }
+
+        let (first, last) = (self.tour[0], self.tour[n - 1]);
+
+        self.record(
+            true,
+            &[],
+            &[(last, first, RELAXED_COLOR)],
+            "nearest neighbor: close the tour".to_string(),
+        );
+    }
+
+    /// Replaces edges (a, b) and (c, d) by (a, c) and (b, d), reversing the
+    /// path between them, as long as that shortens the tour.
+    fn two_opt(&mut self) {
+        let n = self.tour.len();
+        let mut improved = true;
+
+        while improved {
+            improved = false;
+
+            for i in 0..n.saturating_sub(1) {
+                for j in (i + 2)..n {
+                    if i == 0 && j == n - 1 {
+                        continue;
+                    }
+
+                    let (a, b) = (self.tour[i], self.tour[i + 1]);
+                    let (c, d) = (self.tour[j], self.tour[(j + 1) % n]);
+
+                    let delta = self.distance(a, c) + self.distance(b, d)
+                        - self.distance(a, b)
+                        - self.distance(c, d);
+
+                    if delta >= -EPSILON {
+                        continue;
+                    }
+
+                    self.record(
+                        true,
+                        &[a, b, c, d],
+                        &[(a, b, HIGHLIGHT_COLOR), (c, d, HIGHLIGHT_COLOR)],
+                        format!("2-opt: remove {}-{} and {}-{}", a, b, c, d),
+                    );
+
+                    self.tour[i + 1..=j].reverse();
+                    improved = true;
+
+                    self.record(
+                        true,
+                        &[a, b, c, d],
+                        &[(a, c, RELAXED_COLOR), (b, d, RELAXED_COLOR)],
+                        format!(
+                            "2-opt: reconnect {}-{} and {}-{}, saved {:.1}",
+                            a, c, b, d, -delta
+                        ),
+                    );
+                }
+            }
+        }
+
+        self.record(true, &[], &[], "2-opt: no improving move left".to_string());
+    }
+
+    /// Moves a segment of up to `OR_OPT_MAX_SEGMENT` consecutive cities,
+    /// possibly reversed, to another place in the tour when that shortens
+    /// it.
+    fn or_opt(&mut self) {
+        let n = self.tour.len();
+
+        if n < OR_OPT_MAX_SEGMENT + 2 {
+            return;
+        }
+
+        while let Some((candidate, segment)) = self.improving_or_move() {
+            let saved = self.length() - tour_length(&self.points, &candidate);
+
+            self.record(
+                true,
+                &segment,
+                &[],
+                format!("Or-opt: move segment {:?}", segment),
+            );
+
+            self.tour = candidate;
+
+            self.record(
+                true,
+                &segment,
+                &[],
+                format!(
+                    "Or-opt: segment {:?} reinserted, saved {:.1}",
+                    segment, saved
+                ),
+            );
+        }
+
+        self.record(true, &[], &[], "Or-opt: no improving move left".to_string());
+    }
+
+    fn improving_or_move(&self) -> Option<(Vec<usize>, Vec<usize>)> {
+        let n = self.tour.len();
+        let current = self.length();
+
+        for length in 1..=OR_OPT_MAX_SEGMENT {
+            for start in 0..=(n - length) {
+                let segment = self.tour[start..start + length].to_vec();
+                let mut rest = self.tour[..start].to_vec();
+                rest.extend_from_slice(&self.tour[start + length..]);
+
+                for position in 0..=rest.len() {
+                    if position == start {
+                        continue;
+                    }
+
+                    for reversed in [false, true] {
+                        let mut moved = segment.clone();
+
+                        if reversed {
+                            moved.reverse();
+                        }
+
+                        let mut candidate = rest[..position].to_vec();
+                        candidate.extend_from_slice(&moved);
+                        candidate.extend_from_slice(&rest[position..]);
+
+                        if tour_length(&self.points, &candidate) < current - EPSILON {
+                            return Some((candidate, segment));
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn exact(&mut self) {
+        self.tour = held_karp(&self.points).unwrap_or_default();
+
+        self.record(
+            true,
+            &[],
+            &[],
+            format!("Held-Karp: optimal tour of {} cities", self.points.len()),
+        );
+    }
+
+    fn distance(&self, a: usize, b: usize) -> f64 {
+        distance(&self.points[a], &self.points[b])
+    }
+
+    fn length(&self) -> f64 {
+        tour_length(&self.points, &self.tour)
+    }
+
+    /// Draws the current tour, open while it is being built, plus `extra`
+    /// edges colored on top of it.
+    fn record(
+        &mut self,
+        closed: bool,
+        highlighted: &[usize],
+        extra: &[(usize, usize, &'static str)],
+        message: String,
+    ) {
+        let mut graph = GeneralGraph::new(false);
+
+        for point in self.points.iter() {
+            graph.add_node(point.x, point.y);
+        }
+
+        let n = self.tour.len();
+        let tour_edges = if closed { n } else { n.saturating_sub(1) };
+
+        for position in 0..tour_edges {
+            graph.push_edge(self.tour[position], self.tour[(position + 1) % n], 0.0);
+        }
+
+        for &(from, to, _) in extra {
+            graph.push_edge(from, to, 0.0);
+        }
+
+        let mut view = GraphView::new(&graph);
+
+        for edge in 0..tour_edges {
+            view.edge_colors[edge] = PATH_COLOR;
+        }
+
+        for (idx, &(_, _, color)) in extra.iter().enumerate() {
+            view.edge_colors[tour_edges + idx] = color;
+        }
+
+        for &city in self.tour.iter() {
+            view.node_colors[city] = ACTIVE_COLOR;
+        }
+
+        for &city in highlighted {
+            view.node_colors[city] = CURRENT_COLOR;
+        }
+
+        let length = if closed {
+            self.length()
+        } else {
+            tour_length(&self.points, &self.tour) - self.closing_edge()
+        };
+
+        view.notes = vec![message, format!("tour length: {:.1}", length)];
+
+        if let Some(optimum) = self.optimum {
+            view.notes.push(format!(
+                "optimum (Held-Karp): {:.1}, gap {:.1}%",
+                optimum,
+                if closed && optimum > 0.0 {
+                    (length / optimum - 1.0) * 100.0
+                } else {
+                    0.0
+                }
+            ));
+        }
+
+        self.frames.push((graph, view));
+    }
+
+    fn closing_edge(&self) -> f64 {
+        match (self.tour.first(), self.tour.last()) {
+            (Some(&first), Some(&last)) => self.distance(last, first),
+            _ => 0.0,
+        }
+    }
+}
+
+fn distance(a: &Coordinate, b: &Coordinate) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+/// Length of the closed tour visiting `tour` in order.
+fn tour_length(points: &[Coordinate], tour: &[usize]) -> f64 {
+    (0..tour.len())
+        .map(|idx| distance(&points[tour[idx]], &points[tour[(idx + 1) % tour.len()]]))
+        .sum()
+}
+
+/// The optimal tour by dynamic programming over subsets: `cost[set][last]`
+/// is the shortest path from city 0 through `set` ending at `last`.
+fn held_karp(points: &[Coordinate]) -> Option<Vec<usize>> {
+    let n = points.len();
+
+    if n == 0 || n > HELD_KARP_LIMIT {
+        return None;
+    }
+
+    let full = 1usize << n;
+    let mut cost = vec![vec![f64::INFINITY; n]; full];
+    let mut parent = vec![vec![usize::MAX; n]; full];
+
+    cost[1][0] = 0.0;
+
+    for set in 1..full {
+        if set & 1 == 0 {
+            continue;
+        }
+
+        for last in 0..n {
+            if set & (1 << last) == 0 || cost[set][last].is_infinite() {
+                continue;
+            }
+
+            for next in 0..n {
+                if set & (1 << next) != 0 {
+                    continue;
+                }
+
+                let extended = set | (1 << next);
+                let candidate = cost[set][last] + distance(&points[last], &points[next]);
+
+                if candidate < cost[extended][next] {
+                    cost[extended][next] = candidate;
+                    parent[extended][next] = last;
+                }
+            }
+        }
+    }
+
+    let (mut last, _) = (0..n)
+        .map(|last| {
+            (
+                last,
+                cost[full - 1][last] + distance(&points[last], &points[0]),
+            )
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let mut set = full - 1;
+    let mut tour = vec![];
+
+    while last != usize::MAX {
+        tour.push(last);
+        let previous = parent[set][last];
+        set &= !(1 << last);
+        last = previous;
+    }
+
+    tour.reverse();
+
+    Some(tour)
+}
+
+#[wasm_bindgen]
+pub struct TspResult {
+    points: Vec<Coordinate>,
+    tour: Vec<usize>,
+    optimum: Option<f64>,
+}
+
+#[wasm_bindgen]
+impl TspResult {
+    /// The cities as flat `[x0, y0, x1, y1, ...]` coordinates.
+    pub fn points(&self) -> Vec<f64> {
+        self.points
+            .iter()
+            .flat_map(|point| [point.x, point.y])
+            .collect()
+    }
+
+    pub fn tour(&self) -> Vec<usize> {
+        self.tour.clone()
+    }
+
+    pub fn length(&self) -> f64 {
+        tour_length(&self.points, &self.tour)
+    }
+
+    /// The Held-Karp optimum, `NaN` above the point limit.
+    pub fn optimum(&self) -> f64 {
+        self.optimum.unwrap_or(f64::NAN)
+    }
+}
+
+/// Scatters `num_of_points` cities inside `boundary` and animates either
+/// nearest neighbor followed by 2-opt and Or-opt (`"heuristic"`), comparing
+/// against Held-Karp for small instances, or the optimum alone (`"exact"`).
+#[wasm_bindgen]
+pub fn run_traveling_salesman(
+    document_id: &str,
+    boundary: Boundary,
+    num_of_points: usize,
+    mode: &str,
+) -> Result<TspResult, JsValue> {
+    set_panic_hook();
+
+    if mode == "exact" && num_of_points > HELD_KARP_LIMIT {
+        return Err(JsValue::from_str(&format!(
+            "Held-Karp is limited to {} points",
+            HELD_KARP_LIMIT
+        )));
+    }
+
+    let points = (0..num_of_points)
+        .map(|_| {
+            let x = NODE_RADIUS + random() * (boundary.width - NODE_RADIUS * 2.0);
+            let y = NODE_RADIUS + random() * (boundary.height - NODE_RADIUS * 2.0);
+
+            Coordinate { x, y }
+        })
+        .collect::<Vec<_>>();
+
+    let mut tsp = Tsp::new(points);
+
+    match mode {
+        "heuristic" => {
+            tsp.nearest_neighbor();
+            tsp.two_opt();
+            tsp.or_opt();
+        }
+        "exact" => tsp.exact(),
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "unknown mode `{}`, expected `heuristic` or `exact`",
+                mode
+            )))
+        }
+    }
+
+    let ctx = canvas::context(document_id);
+
+    let animation = Animation::start(FRAMES_PER_STEP, move |(graph, view): &Frame| {
+        view.draw(&ctx, graph)
+    });
+
+    animation.push(tsp.frames);
+
+    Ok(TspResult {
+        points: tsp.points,
+        tour: tsp.tour,
+        optimum: tsp.optimum,
+    })
+}
@@ -0,0 +1,487 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    animation::{Animation, TweenRecorder, STEP_TICKS},
+    canvas,
+    data_structures::trie::{Descent, Trie, ROOT},
+    renderers::{
+        graph::{ACTIVE_COLOR, CURRENT_COLOR, HIGHLIGHT_COLOR, PATH_COLOR, RELAXED_COLOR},
+        tree::TreeView,
+    },
+    utils::set_panic_hook,
+};
+
+const MODES: [&str; 2] = ["trie", "radix"];
+/// Completions listed in the notes before the rest is counted.
+const COMPLETIONS_SHOWN: usize = 8;
+
+/// A trie with the frames recorded while operating on it.
+struct Recorder {
+    trie: Trie,
+    frames: TweenRecorder<TreeView>,
+}
+
+impl Recorder {
+    /// The trie with `message` and its size in the notes.
+    fn view(&self, message: String) -> TreeView {
+        let mut view = TreeView::trie(&self.trie);
+
+        view.notes = vec![
+            message,
+            format!(
+                "{}: {} words, {} nodes; $ marks the end of a word",
+                self.name(),
+                self.trie.len(),
+                self.trie.node_count()
+            ),
+        ];
+
+        view
+    }
+
+    /// Records `view`, tweening node moves since the last frame.
+    fn record(&mut self, view: TreeView) {
+        self.frames.record(view);
+    }
+
+    /// Records `message` over the trie with `marked` nodes colored.
+    fn record_marked(&mut self, marked: &[(usize, &'static str)], message: String) {
+        let mut view = self.view(message);
+
+        for &(id, color) in marked {
+            view.node_mut(id).color = color;
+        }
+
+        self.record(view);
+    }
+
+    /// Records one frame per edge followed by `word`, and one for where it
+    /// got stuck if it did.
+    fn record_descent(&mut self, word: &str, descent: &Descent) {
+        self.record_marked(
+            &[(ROOT, CURRENT_COLOR)],
+            format!("walk down from the root along \"{}\"", word),
+        );
+
+        for step in 1..descent.path.len() {
+            let id = descent.path[step];
+            let label = &self.trie.node(id).label;
+            let before = self.trie.prefix(descent.path[step - 1]).len();
+
+            let message = if step == descent.path.len() - 1 && descent.inside_edge {
+                let common = &word[before..descent.matched];
+
+                if descent.matched == word.len() {
+                    format!("\"{}\" ends inside edge \"{}\"", word, label)
+                } else {
+                    format!(
+                        "edge \"{}\" matches only \"{}\" of \"{}\"",
+                        label,
+                        common,
+                        &word[before..]
+                    )
+                }
+            } else {
+                format!("follow edge \"{}\"", label)
+            };
+
+            let view = highlight_path(self.view(message), &descent.path[..=step], id);
+
+            self.record(view);
+        }
+
+        if descent.matched < word.len() && !descent.inside_edge {
+            let last = *descent.path.last().unwrap();
+            let view = highlight_path(
+                self.view(format!(
+                    "no edge starts with \"{}\"",
+                    word[descent.matched..].chars().next().unwrap()
+                )),
+                &descent.path,
+                last,
+            );
+
+            self.record(view);
+        }
+    }
+
+    fn insert(&mut self, word: &str) -> bool {
+        let descent = self.trie.descend(word);
+
+        self.record_descent(word, &descent);
+
+        let split_label = descent
+            .path
+            .last()
+            .filter(|_| descent.inside_edge)
+            .map(|&id| self.trie.node(id).label.clone());
+
+        let insertion = self.trie.insert(word);
+
+        if !insertion.inserted {
+            let last = *descent.path.last().unwrap();
+
+            self.record_marked(
+                &[(last, PATH_COLOR)],
+                format!("\"{}\" is already in the {}", word, self.name()),
+            );
+
+            return false;
+        }
+
+        let mut marked: Vec<_> = insertion
+            .created
+            .iter()
+            .map(|&id| (id, RELAXED_COLOR))
+            .collect();
+
+        let message = match (insertion.split, split_label) {
+            (Some(middle), Some(label)) => {
+                marked.push((middle, HIGHLIGHT_COLOR));
+
+                let head = &self.trie.node(middle).label;
+
+                format!(
+                    "split edge \"{}\" into \"{}\" and \"{}\"",
+                    label,
+                    head,
+                    &label[head.len()..]
+                )
+            }
+            _ if insertion.created.is_empty() => {
+                format!("mark the end of \"{}\" on an existing node", word)
+            }
+            _ => format!(
+                "add {} node{} for \"{}\"",
+                insertion.created.len(),
+                if insertion.created.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                &word[descent.matched..]
+            ),
+        };
+
+        self.record_marked(&marked, message);
+
+        true
+    }
+
+    fn lookup(&mut self, word: &str) -> bool {
+        let descent = self.trie.descend(word);
+
+        self.record_descent(word, &descent);
+
+        let last = *descent.path.last().unwrap();
+        let found = self.trie.contains(word);
+
+        let message = if found {
+            format!("found \"{}\"", word)
+        } else if descent.matched == word.len() {
+            format!("\"{}\" is only a prefix of other words", word)
+        } else {
+            format!("\"{}\" is not in the {}", word, self.name())
+        };
+
+        let color = if found { PATH_COLOR } else { HIGHLIGHT_COLOR };
+        let mut view = highlight_path(self.view(message), &descent.path, last);
+
+        view.node_mut(last).color = color;
+        self.record(view);
+
+        found
+    }
+
+    fn remove(&mut self, word: &str) -> bool {
+        let descent = self.trie.descend(word);
+        let last = *descent.path.last().unwrap();
+
+        self.record_descent(word, &descent);
+
+        let before = self.view(String::new());
+        let Some(removal) = self.trie.remove(word) else {
+            self.record_marked(
+                &[(last, HIGHLIGHT_COLOR)],
+                format!("\"{}\" is not in the {}", word, self.name()),
+            );
+
+            return false;
+        };
+
+        let mut view = before;
+        let mut message = format!("unmark the end of \"{}\"", word);
+
+        view.node_mut(last).label = String::new();
+
+        for &id in &removal.pruned {
+            view.node_mut(id).color = HIGHLIGHT_COLOR;
+        }
+
+        if !removal.pruned.is_empty() {
+            message += &format!(
+                ", free the {} node{} no other word uses",
+                removal.pruned.len(),
+                if removal.pruned.len() == 1 { "" } else { "s" }
+            );
+        }
+
+        if let Some((id, child)) = removal.merged {
+            view.node_mut(id).color = CURRENT_COLOR;
+            view.node_mut(child).color = CURRENT_COLOR;
+            message += ", then merge the edge left without a branch";
+        }
+
+        view.notes[0] = message;
+        self.record(view);
+
+        let marked: Vec<_> = removal
+            .merged
+            .iter()
+            .map(|&(id, _)| (id, RELAXED_COLOR))
+            .collect();
+
+        let message = match removal.merged {
+            Some((id, _)) => format!("merged into edge \"{}\"", self.trie.node(id).label),
+            None => format!("removed \"{}\"", word),
+        };
+
+        self.record_marked(&marked, message);
+
+        true
+    }
+
+    /// Records every merge turning the trie into a radix tree.
+    fn compress(&mut self) -> usize {
+        let mut merges = 0;
+
+        while let Some(id) = self.trie.next_mergeable() {
+            let child = self.trie.node(id).children[0];
+            let message = format!(
+                "\"{}\" has one child and ends no word: merge \"{}\" into it",
+                self.trie.node(id).label,
+                self.trie.node(child).label
+            );
+
+            self.record_marked(&[(id, CURRENT_COLOR), (child, CURRENT_COLOR)], message);
+            self.trie.merge(id);
+            self.record_marked(
+                &[(id, RELAXED_COLOR)],
+                format!("merged into edge \"{}\"", self.trie.node(id).label),
+            );
+
+            merges += 1;
+        }
+
+        self.trie.compress();
+        self.record_marked(&[], format!("compressed with {} merges", merges));
+
+        merges
+    }
+
+    /// The trie with the path of `prefix` and every word below it marked.
+    fn completion_view(&self, prefix: &str) -> (TreeView, Vec<String>) {
+        let (descent, subtree) = self.trie.prefix_node(prefix);
+        let last = *descent.path.last().unwrap();
+        let words = subtree
+            .map(|id| self.trie.words_below(id))
+            .unwrap_or_default();
+
+        let mut shown = words
+            .iter()
+            .take(COMPLETIONS_SHOWN)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if words.len() > COMPLETIONS_SHOWN {
+            shown += &format!(" and {} more", words.len() - COMPLETIONS_SHOWN);
+        }
+
+        let message = match subtree {
+            Some(_) if words.is_empty() => format!("\"{}\": no completions", prefix),
+            Some(_) => format!("\"{}\": {}", prefix, shown),
+            None => format!("\"{}\": no word starts with it", prefix),
+        };
+
+        let mut view = self.view(message);
+
+        if let Some(subtree) = subtree {
+            for id in self.trie.preorder(subtree) {
+                view.node_mut(id).color = ACTIVE_COLOR;
+            }
+        }
+
+        let mut view = highlight_path(view, &descent.path, last);
+
+        if subtree.is_none() && last != ROOT {
+            view.node_mut(last).color = HIGHLIGHT_COLOR;
+        }
+
+        (view, words)
+    }
+
+    fn name(&self) -> &'static str {
+        if self.trie.is_compressed() {
+            "radix tree"
+        } else {
+            "trie"
+        }
+    }
+}
+
+/// Marks the edges down `path` and its nodes, `current` standing out.
+fn highlight_path(mut view: TreeView, path: &[usize], current: usize) -> TreeView {
+    for (step, &id) in path.iter().enumerate() {
+        let node = view.node_mut(id);
+
+        node.color = PATH_COLOR;
+
+        if step > 0 {
+            node.edge_color = PATH_COLOR;
+        }
+    }
+
+    view.node_mut(current).color = CURRENT_COLOR;
+
+    view
+}
+
+/// A trie or radix tree of words drawn with labelled edges, animating how
+/// every operation follows, splits and merges them, with an autocomplete
+/// view that follows the user's typing.
+#[wasm_bindgen]
+pub struct TrieVisualization {
+    recorder: Recorder,
+    animation: Animation<TreeView>,
+}
+
+#[wasm_bindgen]
+impl TrieVisualization {
+    /// Adds `word`, returning `false` if it was already there.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let inserted = self.recorder.insert(word);
+        self.flush();
+
+        inserted
+    }
+
+    pub fn lookup(&mut self, word: &str) -> bool {
+        let found = self.recorder.lookup(word);
+        self.flush();
+
+        found
+    }
+
+    /// Removes `word`, returning `false` if it was not there.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let removed = self.recorder.remove(word);
+        self.flush();
+
+        removed
+    }
+
+    /// Animates the walk down to `prefix` and returns the words starting
+    /// with it, in sorted order.
+    pub fn words_with_prefix(&mut self, prefix: &str) -> js_sys::Array {
+        let descent = self.recorder.trie.descend(prefix);
+
+        self.recorder.record_descent(prefix, &descent);
+
+        let (view, words) = self.recorder.completion_view(prefix);
+
+        self.recorder.record(view);
+        self.flush();
+
+        words.iter().map(|word| JsValue::from_str(word)).collect()
+    }
+
+    /// Shows the completions of `prefix` at once, dropping any animation
+    /// still playing, so it can be called on every keystroke. The matched
+    /// path is highlighted and a mismatching edge marked.
+    pub fn autocomplete(&mut self, prefix: &str) -> js_sys::Array {
+        let (view, words) = self.recorder.completion_view(prefix);
+
+        self.recorder.frames.reset(view.clone());
+        self.animation.replace([view]);
+
+        words.iter().map(|word| JsValue::from_str(word)).collect()
+    }
+
+    /// Merges every chain of nodes without words or branches, turning the
+    /// trie into a radix tree, and returns the number of merges.
+    pub fn compress(&mut self) -> usize {
+        let merges = self.recorder.compress();
+        self.flush();
+
+        merges
+    }
+
+    /// Every word in sorted order.
+    pub fn words(&self) -> js_sys::Array {
+        self.recorder
+            .trie
+            .words()
+            .iter()
+            .map(|word| JsValue::from_str(word))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.recorder.trie.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recorder.trie.is_empty()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.recorder.trie.node_count()
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.recorder.trie.is_compressed()
+    }
+
+    fn flush(&mut self) {
+        self.animation.push_held(self.recorder.frames.take());
+    }
+}
+
+/// Starts a visualization of a `"trie"` or a `"radix"` tree, inserting the
+/// whitespace separated `words` one after another.
+#[wasm_bindgen]
+pub fn run_trie(document_id: &str, mode: &str, words: &str) -> Result<TrieVisualization, JsValue> {
+    set_panic_hook();
+
+    if !MODES.contains(&mode) {
+        return Err(JsValue::from_str(&format!(
+            "unknown mode `{}`, expected one of {}",
+            mode,
+            MODES.join(", ")
+        )));
+    }
+
+    let ctx = canvas::context(document_id);
+    let animation = Animation::start(STEP_TICKS, move |view: &TreeView| view.draw(&ctx));
+
+    let mut visualization = TrieVisualization {
+        recorder: Recorder {
+            trie: Trie::new(mode == "radix"),
+            frames: TweenRecorder::new(),
+        },
+        animation,
+    };
+
+    let view = visualization.recorder.view("empty".to_string());
+
+    visualization.recorder.record(view);
+
+    for word in words.split_whitespace() {
+        visualization.recorder.insert(word);
+    }
+
+    visualization.flush();
+
+    Ok(visualization)
+}